@@ -0,0 +1,300 @@
+use crate::animation::{Animation, AnimationController, Curve, Lerp};
+use crate::base::{Alignment, EdgeInsets};
+use crate::container::Container;
+use crate::decoration::{BorderRadius, Borders, BoxShadow, Color};
+use crate::element::Key;
+use crate::tree::{BoxConstraints, Layout, LayoutTree, SizedLayoutBox};
+use std::any::TypeId;
+use std::fmt::Debug;
+
+/// A `Container` that animates to its new `width`, `height`, `padding`,
+/// `margin`, `color`, `borders`, `border_radius` and `shadows` over `duration`
+/// milliseconds whenever they change, instead of jumping straight to them.
+///
+/// The animation is driven by the time of the `ElementTree` it is laid out
+/// with, so it only animates when the tree is laid out with
+/// `LayoutTree::from_root_with_elements`. A `width` or `height` can only be
+/// animated between two sizes. Changing to or from `None`, which shrinks to fit
+/// the child, takes effect immediately.
+#[derive(Debug, Default)]
+pub struct AnimatedContainer {
+    pub key: Option<Key>,
+    pub duration: f32,
+    pub curve: Curve,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub alignment: Alignment,
+    pub padding: EdgeInsets,
+    pub borders: Borders,
+    pub border_radius: BorderRadius,
+    pub shadows: Vec<BoxShadow>,
+    pub margin: EdgeInsets,
+    pub color: Color,
+    pub child: Option<Box<dyn Layout>>,
+}
+
+// The properties of the container that are animated.
+#[derive(PartialEq, Clone, Debug)]
+struct Properties {
+    width: Option<f32>,
+    height: Option<f32>,
+    padding: EdgeInsets,
+    borders: Borders,
+    border_radius: BorderRadius,
+    shadows: Vec<BoxShadow>,
+    margin: EdgeInsets,
+    color: Color,
+}
+
+impl Lerp for Properties {
+    fn lerp(&self, to: &Properties, t: f32) -> Properties {
+        Properties {
+            width: lerp_size(self.width, to.width, t),
+            height: lerp_size(self.height, to.height, t),
+            padding: self.padding.lerp(&to.padding, t),
+            borders: self.borders.lerp(&to.borders, t),
+            border_radius: self.border_radius.lerp(&to.border_radius, t),
+            shadows: self.shadows.lerp(&to.shadows, t),
+            margin: self.margin.lerp(&to.margin, t),
+            color: self.color.lerp(&to.color, t),
+        }
+    }
+}
+
+fn lerp_size(from: Option<f32>, to: Option<f32>, t: f32) -> Option<f32> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(from.lerp(&to, t)),
+        _ => to,
+    }
+}
+
+// The animation from the properties when the last change happened to the
+// current ones.
+#[derive(Clone)]
+struct AnimatedContainerState {
+    from: Properties,
+    to: Properties,
+    controller: AnimationController,
+}
+
+impl AnimatedContainerState {
+    fn value(&self) -> Properties {
+        self.from.lerp(&self.to, self.controller.value())
+    }
+}
+
+impl AnimatedContainer {
+    // Animate to `target` if it changed, and move the animation on to `time`.
+    fn advance(&self, state: &mut AnimatedContainerState, target: Properties, time: f32) {
+        if state.to != target {
+            // Start from wherever the last animation got to, so changing the
+            // target part way through doesn't jump.
+            state.from = state.value();
+            state.to = target;
+            state.controller = AnimationController::new(self.duration).with_curve(self.curve);
+            state.controller.forward();
+        }
+        state.controller.tick(time);
+    }
+
+    fn properties(&self) -> Properties {
+        Properties {
+            width: self.width,
+            height: self.height,
+            padding: self.padding,
+            borders: self.borders,
+            border_radius: self.border_radius,
+            shadows: self.shadows.clone(),
+            margin: self.margin,
+            color: self.color,
+        }
+    }
+}
+
+impl Layout for AnimatedContainer {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let target = self.properties();
+        let state =
+            tree.elements
+                .enter(TypeId::of::<AnimatedContainer>(), self.key.clone(), || {
+                    AnimatedContainerState {
+                        from: target.clone(),
+                        to: target.clone(),
+                        controller: AnimationController::new(self.duration),
+                    }
+                });
+
+        let time = tree.elements.time();
+        let current = if tree.elements.is_measuring() {
+            // Advance a copy, so the animation only ticks once per frame
+            let mut copy = state.untracked().clone();
+            self.advance(&mut copy, target, time);
+            copy.value()
+        } else {
+            let current = state.update_untracked(|state| {
+                self.advance(state, target, time);
+                state.value()
+            });
+            if state.untracked().controller.is_animating() {
+                tree.request_frame();
+            }
+            current
+        };
+
+        let container = Container {
+            width: current.width,
+            height: current.height,
+            alignment: self.alignment,
+            padding: current.padding,
+            borders: current.borders,
+            border_radius: current.border_radius,
+            shadows: current.shadows,
+            margin: current.margin,
+            color: current.color,
+            fill: None,
+            clip: false,
+            child: None,
+        };
+        let sbox = match &self.child {
+            Some(child) => container.layout_with_child(tree, constraints, child.as_ref()),
+            None => container.layout_without_child(constraints),
+        };
+        tree.elements.exit();
+        sbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::ElementTree;
+    use crate::reactive::{Reactive, Signal};
+    use math::Vector2;
+
+    #[test]
+    fn first_frame_does_not_animate() {
+        let mut elements = ElementTree::new();
+        let tree = layout(&container(50.0, Color::red()), 0.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 50, 10] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+        assert!(!elements.is_animating());
+    }
+
+    #[test]
+    fn animates_to_new_properties() {
+        let mut elements = ElementTree::new();
+        let _ = layout(&container(20.0, Color::black()), 0.0, &mut elements);
+
+        let widget = container(60.0, Color::rgba(100.0, 0.0, 0.0, 255.0));
+        let start = layout(&widget, 100.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 20, 10] fill rgba(0, 0, 0, 255)\n",
+            start.to_string()
+        );
+        assert!(elements.is_animating());
+
+        let middle = layout(&widget, 150.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 40, 10] fill rgba(50, 0, 0, 255)\n",
+            middle.to_string()
+        );
+
+        let end = layout(&widget, 200.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 60, 10] fill rgba(100, 0, 0, 255)\n",
+            end.to_string()
+        );
+        assert!(!elements.is_animating());
+    }
+
+    #[test]
+    fn retargeting_starts_from_current_value() {
+        let mut elements = ElementTree::new();
+        let _ = layout(&container(0.0, Color::black()), 0.0, &mut elements);
+        let _ = layout(&container(100.0, Color::black()), 0.0, &mut elements);
+        let _ = layout(&container(100.0, Color::black()), 50.0, &mut elements);
+
+        let widget = container(0.0, Color::black());
+        let start = layout(&widget, 60.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 50, 10] fill rgba(0, 0, 0, 255)\n",
+            start.to_string()
+        );
+        let middle = layout(&widget, 110.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 25, 10] fill rgba(0, 0, 0, 255)\n",
+            middle.to_string()
+        );
+    }
+
+    #[test]
+    fn animates_padding_margin_and_borders() {
+        let mut elements = ElementTree::new();
+        let widget = |inset: f32| AnimatedContainer {
+            duration: 100.0,
+            padding: EdgeInsets::all(inset),
+            margin: EdgeInsets::all(inset),
+            borders: Borders::top(Color::white(), inset),
+            child: Some(Box::new(Container {
+                width: Some(10.0),
+                height: Some(10.0),
+                ..Container::default()
+            })),
+            ..AnimatedContainer::default()
+        };
+        let _ = layout(&widget(0.0), 0.0, &mut elements);
+        let _ = layout(&widget(10.0), 0.0, &mut elements);
+        elements.set_time(50.0);
+        let middle = LayoutTree::from_root_with_elements(
+            &widget(10.0),
+            &BoxConstraints::from_max(Vector2::new(f32::INFINITY, f32::INFINITY)),
+            &mut elements,
+        );
+        assert_eq!(
+            "[0, 0, 20, 20] margin (5, 5, 5, 5) fill rgba(0, 0, 0, 0) top 5 rgba(255, 255, 255, 255)\n  [10, 15, 20, 25] fill rgba(0, 0, 0, 0)\n",
+            middle.to_string()
+        );
+    }
+
+    #[test]
+    fn animates_inside_a_cached_reactive_subtree() {
+        let width = Signal::new(20.0);
+        let mut elements = ElementTree::new();
+        let widget = || {
+            let width = width.clone();
+            Reactive::new(move || Box::new(container(width.get(), Color::black())))
+        };
+        let _ = layout(&widget(), 0.0, &mut elements);
+        width.set(60.0);
+        let _ = layout(&widget(), 100.0, &mut elements);
+        let middle = layout(&widget(), 150.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 40, 10] fill rgba(0, 0, 0, 255)\n",
+            middle.to_string()
+        );
+        let end = layout(&widget(), 200.0, &mut elements);
+        assert_eq!("[0, 0, 60, 10] fill rgba(0, 0, 0, 255)\n", end.to_string());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn layout(widget: &dyn Layout, time: f32, elements: &mut ElementTree) -> LayoutTree {
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        elements.set_time(time);
+        LayoutTree::from_root_with_elements(widget, &constraints, elements)
+    }
+
+    fn container(width: f32, color: Color) -> AnimatedContainer {
+        AnimatedContainer {
+            duration: 100.0,
+            width: Some(width),
+            height: Some(10.0),
+            color,
+            ..AnimatedContainer::default()
+        }
+    }
+}
@@ -0,0 +1,744 @@
+use crate::base::EdgeInsets;
+use crate::decoration::{BorderRadius, BorderSide, Borders, BoxShadow, Color};
+use math::{Rect, Vector2};
+
+// --------------------------------------------------
+// Curves
+// --------------------------------------------------
+
+/// Maps the linear progress of an animation to how far along its value
+/// should be. Both range from 0.0 at the start to 1.0 at the end.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub enum Curve {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A cubic bezier through (0, 0), the two control points and (1, 1), like
+    /// the CSS `cubic-bezier(x1, y1, x2, y2)` timing function.
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Curve {
+    #[must_use]
+    pub fn transform(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Curve::Linear => t,
+            // These match the CSS keywords of the same name
+            Curve::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Curve::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Curve::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Curve::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+// Find the `y` of the curve at `x`. The curve is parametric, so this first
+// solves for the parameter that gives `x` with Newton's method, and falls back
+// to bisection when the slope is too flat to converge.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    // The 1D bezier with endpoints 0 and 1, and control points `a` and `b`
+    let sample = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * a + 3.0 * u * t * t * b + t * t * t
+    };
+    let slope = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * a + 6.0 * u * t * (b - a) + 3.0 * t * t * (1.0 - b)
+    };
+
+    let mut t = x;
+    for _ in 0..8 {
+        let error = sample(x1, x2, t) - x;
+        if error.abs() < 1e-6 {
+            return sample(y1, y2, t);
+        }
+        let d = slope(x1, x2, t);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        t -= error / d;
+    }
+
+    let (mut lo, mut hi) = (0.0, 1.0);
+    t = x;
+    for _ in 0..32 {
+        let value = sample(x1, x2, t);
+        if (value - x).abs() < 1e-6 {
+            break;
+        }
+        if value < x {
+            lo = t;
+        } else {
+            hi = t;
+        }
+        t = lo + (hi - lo) * 0.5;
+    }
+    sample(y1, y2, t)
+}
+
+// --------------------------------------------------
+// Tweens
+// --------------------------------------------------
+
+/// Values that can be linearly interpolated. `t` is 0.0 at `self` and 1.0 at
+/// `to`, and may go outside that range for curves that overshoot.
+pub trait Lerp {
+    #[must_use]
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &f32, t: f32) -> f32 {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vector2 {
+    fn lerp(&self, to: &Vector2, t: f32) -> Vector2 {
+        Vector2::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, to: &Color, t: f32) -> Color {
+        Color::rgba(
+            self.r.lerp(&to.r, t),
+            self.g.lerp(&to.g, t),
+            self.b.lerp(&to.b, t),
+            self.a.lerp(&to.a, t),
+        )
+    }
+}
+
+impl Lerp for EdgeInsets {
+    fn lerp(&self, to: &EdgeInsets, t: f32) -> EdgeInsets {
+        EdgeInsets {
+            top: self.top.lerp(&to.top, t),
+            bottom: self.bottom.lerp(&to.bottom, t),
+            left: self.left.lerp(&to.left, t),
+            right: self.right.lerp(&to.right, t),
+        }
+    }
+}
+
+impl Lerp for BorderSide {
+    fn lerp(&self, to: &BorderSide, t: f32) -> BorderSide {
+        BorderSide::new(self.color.lerp(&to.color, t), self.width.lerp(&to.width, t))
+    }
+}
+
+// A missing side animates like a side of zero width, in the color of the side
+// that is present.
+fn lerp_border_side(
+    from: Option<BorderSide>,
+    to: Option<BorderSide>,
+    t: f32,
+) -> Option<BorderSide> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(from.lerp(&to, t)),
+        (Some(from), None) if t < 1.0 => Some(from.lerp(&BorderSide::new(from.color, 0.0), t)),
+        (None, Some(to)) if t > 0.0 => Some(BorderSide::new(to.color, 0.0).lerp(&to, t)),
+        (_, to) if t >= 1.0 => to,
+        (from, _) => from,
+    }
+}
+
+impl Lerp for Borders {
+    fn lerp(&self, to: &Borders, t: f32) -> Borders {
+        Borders {
+            top: lerp_border_side(self.top, to.top, t),
+            bottom: lerp_border_side(self.bottom, to.bottom, t),
+            left: lerp_border_side(self.left, to.left, t),
+            right: lerp_border_side(self.right, to.right, t),
+        }
+    }
+}
+
+impl Lerp for BorderRadius {
+    fn lerp(&self, to: &BorderRadius, t: f32) -> BorderRadius {
+        BorderRadius {
+            top_left: self.top_left.lerp(&to.top_left, t),
+            top_right: self.top_right.lerp(&to.top_right, t),
+            bottom_right: self.bottom_right.lerp(&to.bottom_right, t),
+            bottom_left: self.bottom_left.lerp(&to.bottom_left, t),
+        }
+    }
+}
+
+impl Lerp for BoxShadow {
+    fn lerp(&self, to: &BoxShadow, t: f32) -> BoxShadow {
+        BoxShadow {
+            offset: self.offset.lerp(&to.offset, t),
+            blur_radius: self.blur_radius.lerp(&to.blur_radius, t),
+            spread: self.spread.lerp(&to.spread, t),
+            color: self.color.lerp(&to.color, t),
+        }
+    }
+}
+
+// Shadows are interpolated pairwise. When one list is longer, its extra
+// shadows fade in or out from a transparent copy of themselves, and are only
+// in the list while they are visible.
+impl Lerp for Vec<BoxShadow> {
+    fn lerp(&self, to: &Vec<BoxShadow>, t: f32) -> Vec<BoxShadow> {
+        let transparent = |shadow: &BoxShadow| BoxShadow {
+            color: shadow.color.alpha(0.0),
+            ..*shadow
+        };
+        (0..self.len().max(to.len()))
+            .filter_map(|i| match (self.get(i), to.get(i)) {
+                (Some(from), Some(to)) => Some(from.lerp(to, t)),
+                (Some(from), None) if t < 1.0 => Some(from.lerp(&transparent(from), t)),
+                (None, Some(to)) if t > 0.0 => Some(transparent(to).lerp(to, t)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(&self, to: &Rect, t: f32) -> Rect {
+        Rect::new(self.min.lerp(&to.min, t), self.max.lerp(&to.max, t))
+    }
+}
+
+/// Interpolates between two values.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Tween<T> {
+    pub begin: T,
+    pub end: T,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(begin: T, end: T) -> Tween<T> {
+        Tween { begin, end }
+    }
+
+    /// The value at `t`, which is usually the value of an
+    /// `AnimationController`.
+    #[must_use]
+    pub fn transform(&self, t: f32) -> T {
+        self.begin.lerp(&self.end, t)
+    }
+}
+
+// --------------------------------------------------
+// Animation
+// --------------------------------------------------
+
+/// Something that changes over time. Animations are advanced by the `time`
+/// that is passed to `AppDriver::tick`, in milliseconds.
+pub trait Animation {
+    fn tick(&mut self, time: f32);
+
+    /// Whether the animation will change on the next tick. When nothing is
+    /// animating, the driver can stop requesting frames.
+    fn is_animating(&self) -> bool;
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AnimationStatus {
+    // Stopped at the start
+    Dismissed,
+    // Running towards the end
+    Forward,
+    // Running towards the start
+    Reverse,
+    // Stopped at the end
+    Completed,
+}
+
+/// Runs from 0.0 to 1.0 over `duration` milliseconds, and eases the value with
+/// `curve`.
+///
+/// Event handlers don't know the frame time, so `forward` and `reverse` only
+/// record the direction. The animation starts from its current value on the
+/// next `tick`.
+#[derive(Clone, Debug)]
+pub struct AnimationController {
+    pub duration: f32,
+    pub curve: Curve,
+    status: AnimationStatus,
+    // The linear progress, before the curve is applied
+    progress: f32,
+    // The progress and time that the current run started at. The time is
+    // `None` until the first tick after the run was started.
+    start_progress: f32,
+    start_time: Option<f32>,
+    repeat: Option<bool>,
+}
+
+impl AnimationController {
+    #[must_use]
+    pub fn new(duration: f32) -> AnimationController {
+        AnimationController {
+            duration,
+            curve: Curve::Linear,
+            status: AnimationStatus::Dismissed,
+            progress: 0.0,
+            start_progress: 0.0,
+            start_time: None,
+            repeat: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_curve(self, curve: Curve) -> AnimationController {
+        AnimationController { curve, ..self }
+    }
+
+    /// The eased value of the animation.
+    #[must_use]
+    pub fn value(&self) -> f32 {
+        self.curve.transform(self.progress)
+    }
+
+    /// The linear progress of the animation, before the curve is applied.
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    #[must_use]
+    pub fn status(&self) -> AnimationStatus {
+        self.status
+    }
+
+    /// Run towards the end.
+    pub fn forward(&mut self) {
+        self.run(AnimationStatus::Forward);
+    }
+
+    /// Run towards the start.
+    pub fn reverse(&mut self) {
+        self.run(AnimationStatus::Reverse);
+    }
+
+    /// Run forwards forever. When `reverse` is true it runs back and forth,
+    /// otherwise it jumps back to the start each time it completes.
+    pub fn repeat(&mut self, reverse: bool) {
+        self.repeat = Some(reverse);
+        if self.status != AnimationStatus::Reverse {
+            self.forward();
+        }
+    }
+
+    /// Stop at the current value.
+    pub fn stop(&mut self) {
+        self.repeat = None;
+        self.start_time = None;
+        self.status = if self.progress >= 1.0 {
+            AnimationStatus::Completed
+        } else {
+            AnimationStatus::Dismissed
+        };
+    }
+
+    /// Jump to `progress` without animating.
+    pub fn set_progress(&mut self, progress: f32) {
+        self.progress = progress.clamp(0.0, 1.0);
+        self.stop();
+    }
+
+    fn run(&mut self, status: AnimationStatus) {
+        self.status = status;
+        self.start_progress = self.progress;
+        self.start_time = None;
+    }
+
+    fn target(&self) -> f32 {
+        match self.status {
+            AnimationStatus::Reverse => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl Animation for AnimationController {
+    fn tick(&mut self, time: f32) {
+        if !self.is_animating() {
+            return;
+        }
+        let start_time = *self.start_time.get_or_insert(time);
+        let target = self.target();
+        // Only the remaining distance is animated, so reversing half way
+        // through takes half the duration.
+        let distance = (target - self.start_progress).abs();
+        let elapsed = time - start_time;
+        let t = if self.duration > 0.0 {
+            elapsed / self.duration
+        } else {
+            distance
+        };
+        // Allow for rounding, so the run doesn't need one more frame to finish
+        if t + 1e-6 < distance {
+            self.progress = if target > self.start_progress {
+                self.start_progress + t
+            } else {
+                self.start_progress - t
+            };
+            return;
+        }
+
+        self.progress = target;
+        match self.repeat {
+            Some(true) => {
+                let next = match self.status {
+                    AnimationStatus::Reverse => AnimationStatus::Forward,
+                    _ => AnimationStatus::Reverse,
+                };
+                self.run(next);
+                self.start_time = Some(time);
+            }
+            Some(false) => {
+                self.progress = 0.0;
+                self.run(AnimationStatus::Forward);
+                self.start_time = Some(time);
+            }
+            None => {
+                self.start_time = None;
+                self.status = match self.status {
+                    AnimationStatus::Reverse => AnimationStatus::Dismissed,
+                    _ => AnimationStatus::Completed,
+                };
+            }
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        matches!(
+            self.status,
+            AnimationStatus::Forward | AnimationStatus::Reverse
+        )
+    }
+}
+
+// --------------------------------------------------
+// Springs
+// --------------------------------------------------
+
+/// The physical properties of a damped spring.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Spring {
+    pub mass: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Default for Spring {
+    // Settles quickly with a little overshoot
+    fn default() -> Spring {
+        Spring {
+            mass: 1.0,
+            stiffness: 170.0,
+            damping: 26.0,
+        }
+    }
+}
+
+impl Spring {
+    /// A spring that settles as fast as possible without overshooting.
+    #[must_use]
+    pub fn critically_damped(mass: f32, stiffness: f32) -> Spring {
+        Spring {
+            mass,
+            stiffness,
+            damping: 2.0 * (mass * stiffness).sqrt(),
+        }
+    }
+}
+
+/// Moves a value towards a target as if it were attached to it by a spring.
+///
+/// Unlike an `AnimationController`, it has no fixed duration, and changing the
+/// target part way through keeps the current velocity, so interrupted
+/// animations stay smooth.
+#[derive(Clone, Debug)]
+pub struct SpringSimulation {
+    pub spring: Spring,
+    /// How close to the target, in both position and velocity, the value must
+    /// be before it is considered at rest.
+    pub tolerance: f32,
+    position: f32,
+    velocity: f32,
+    target: f32,
+    last_time: Option<f32>,
+}
+
+// The largest step, in seconds, that the simulation is advanced by at once. A
+// smaller step keeps stiff springs stable when frames are slow.
+const MAX_SPRING_STEP: f32 = 1.0 / 240.0;
+
+impl SpringSimulation {
+    #[must_use]
+    pub fn new(spring: Spring, position: f32) -> SpringSimulation {
+        SpringSimulation {
+            spring,
+            tolerance: 0.001,
+            position,
+            velocity: 0.0,
+            target: position,
+            last_time: None,
+        }
+    }
+
+    #[must_use]
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    /// The velocity in units per second.
+    #[must_use]
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    #[must_use]
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Jump to `position` and stop moving.
+    pub fn set_position(&mut self, position: f32) {
+        self.position = position;
+        self.velocity = 0.0;
+    }
+
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+    }
+
+    fn step(&mut self, dt: f32) {
+        let Spring {
+            mass,
+            stiffness,
+            damping,
+        } = self.spring;
+        let force = -stiffness * (self.position - self.target) - damping * self.velocity;
+        self.velocity += force / mass * dt;
+        self.position += self.velocity * dt;
+    }
+}
+
+impl Animation for SpringSimulation {
+    fn tick(&mut self, time: f32) {
+        // Forget the time while at rest, so a long pause between frames isn't
+        // simulated all at once when the target changes.
+        if !self.is_animating() {
+            self.last_time = None;
+            return;
+        }
+        let Some(last_time) = self.last_time.replace(time) else {
+            return;
+        };
+        let mut remaining = ((time - last_time) / 1000.0).max(0.0);
+        while remaining > 0.0 {
+            let dt = remaining.min(MAX_SPRING_STEP);
+            self.step(dt);
+            remaining -= dt;
+        }
+        if !self.is_animating() {
+            self.set_position(self.target);
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        (self.position - self.target).abs() > self.tolerance || self.velocity.abs() > self.tolerance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_one() {
+        let curves = [
+            Curve::Linear,
+            Curve::EaseIn,
+            Curve::EaseOut,
+            Curve::EaseInOut,
+            Curve::CubicBezier(0.1, 0.7, 0.1, 1.0),
+        ];
+        for curve in curves {
+            assert_close(0.0, curve.transform(0.0));
+            assert_close(1.0, curve.transform(1.0));
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_symmetric() {
+        assert_close(0.5, Curve::EaseInOut.transform(0.5));
+        let a = Curve::EaseInOut.transform(0.2);
+        let b = Curve::EaseInOut.transform(0.8);
+        assert_close(1.0, a + b);
+    }
+
+    #[test]
+    fn ease_in_starts_slow_and_ease_out_starts_fast() {
+        assert!(Curve::EaseIn.transform(0.25) < 0.25);
+        assert!(Curve::EaseOut.transform(0.25) > 0.25);
+    }
+
+    #[test]
+    fn linear_cubic_bezier_is_linear() {
+        let curve = Curve::CubicBezier(1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0);
+        for t in [0.1, 0.3, 0.5, 0.9] {
+            assert_close(t, curve.transform(t));
+        }
+    }
+
+    #[test]
+    fn tweens_interpolate() {
+        assert_close(15.0, Tween::new(10.0, 20.0).transform(0.5));
+        assert_eq!(
+            Vector2::new(5.0, 10.0),
+            Tween::new(Vector2::zero(), Vector2::new(10.0, 20.0)).transform(0.5)
+        );
+        assert_eq!(
+            Color::rgba(50.0, 0.0, 50.0, 255.0),
+            Tween::new(
+                Color::rgba(100.0, 0.0, 0.0, 255.0),
+                Color::rgba(0.0, 0.0, 100.0, 255.0)
+            )
+            .transform(0.5)
+        );
+        assert_eq!(
+            EdgeInsets::all(5.0),
+            Tween::new(EdgeInsets::zero(), EdgeInsets::all(10.0)).transform(0.5)
+        );
+        assert_eq!(
+            Rect::from_size((5.0, 5.0)),
+            Tween::new(Rect::zero(), Rect::from_size((10.0, 10.0))).transform(0.5)
+        );
+    }
+
+    #[test]
+    fn missing_border_sides_animate_from_zero_width() {
+        let from = Borders::none();
+        let to = Borders::top(Color::red(), 4.0);
+        let tween = Tween::new(from, to);
+        assert_eq!(Borders::none(), tween.transform(0.0));
+        assert_eq!(Borders::top(Color::red(), 2.0), tween.transform(0.5));
+        assert_eq!(to, tween.transform(1.0));
+
+        let tween = Tween::new(to, from);
+        assert_eq!(Borders::top(Color::red(), 1.0), tween.transform(0.75));
+        assert_eq!(Borders::none(), tween.transform(1.0));
+    }
+
+    #[test]
+    fn extra_shadows_fade_in_and_out() {
+        let shadow = BoxShadow::new(Vector2::new(0.0, 4.0), 8.0, 0.0, Color::black());
+        let tween = Tween::new(vec![], vec![shadow]);
+        assert_eq!(Vec::<BoxShadow>::new(), tween.transform(0.0));
+        assert_eq!(
+            vec![BoxShadow {
+                color: Color::black().alpha(0.5),
+                ..shadow
+            }],
+            tween.transform(0.5)
+        );
+        assert_eq!(vec![shadow], tween.transform(1.0));
+
+        let tween = Tween::new(vec![shadow], vec![]);
+        assert_eq!(Vec::<BoxShadow>::new(), tween.transform(1.0));
+    }
+
+    #[test]
+    fn controller_runs_forward_from_first_tick() {
+        let mut controller = AnimationController::new(100.0);
+        controller.forward();
+        controller.tick(1000.0);
+        assert_close(0.0, controller.value());
+        controller.tick(1050.0);
+        assert_close(0.5, controller.value());
+        assert!(controller.is_animating());
+        controller.tick(1100.0);
+        assert_close(1.0, controller.value());
+        assert_eq!(AnimationStatus::Completed, controller.status());
+        assert!(!controller.is_animating());
+    }
+
+    #[test]
+    fn controller_applies_curve() {
+        let mut controller = AnimationController::new(100.0).with_curve(Curve::EaseIn);
+        controller.forward();
+        controller.tick(0.0);
+        controller.tick(25.0);
+        assert_close(0.25, controller.progress());
+        assert_close(Curve::EaseIn.transform(0.25), controller.value());
+    }
+
+    #[test]
+    fn controller_reverses_from_current_value() {
+        let mut controller = AnimationController::new(100.0);
+        controller.forward();
+        controller.tick(0.0);
+        controller.tick(60.0);
+        controller.reverse();
+        controller.tick(100.0);
+        controller.tick(130.0);
+        assert_close(0.3, controller.value());
+        controller.tick(160.0);
+        assert_close(0.0, controller.value());
+        assert_eq!(AnimationStatus::Dismissed, controller.status());
+    }
+
+    #[test]
+    fn repeating_controller_keeps_animating() {
+        let mut controller = AnimationController::new(100.0);
+        controller.repeat(true);
+        controller.tick(0.0);
+        controller.tick(100.0);
+        assert_eq!(AnimationStatus::Reverse, controller.status());
+        controller.tick(150.0);
+        assert_close(0.5, controller.value());
+        controller.tick(200.0);
+        assert_eq!(AnimationStatus::Forward, controller.status());
+        assert!(controller.is_animating());
+        controller.stop();
+        assert!(!controller.is_animating());
+    }
+
+    #[test]
+    fn spring_settles_at_target() {
+        let mut spring = SpringSimulation::new(Spring::default(), 0.0);
+        assert!(!spring.is_animating());
+        spring.set_target(100.0);
+        assert!(spring.is_animating());
+        let mut time = 0.0;
+        while spring.is_animating() && time < 5000.0 {
+            spring.tick(time);
+            time += 16.0;
+        }
+        assert!(!spring.is_animating());
+        assert_close(100.0, spring.position());
+    }
+
+    #[test]
+    fn critically_damped_spring_does_not_overshoot() {
+        let mut spring = SpringSimulation::new(Spring::critically_damped(1.0, 100.0), 0.0);
+        spring.set_target(1.0);
+        let mut time = 0.0;
+        while spring.is_animating() && time < 5000.0 {
+            spring.tick(time);
+            assert!(spring.position() <= 1.0 + spring.tolerance);
+            time += 16.0;
+        }
+    }
+
+    fn assert_close(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use math::Vector2;
+use std::fmt;
 
 // The position of the center of a widget as a fraction of the available area.
 // The widget should not overflow at (0.0, 0.0) or at (1.0, 1.0), it should be
@@ -177,3 +178,14 @@ impl EdgeInsets {
         Vector2::new(self.right, self.bottom)
     }
 }
+
+/// Formats the insets in CSS order, as `(top, right, bottom, left)`.
+impl fmt::Display for EdgeInsets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}, {}, {})",
+            self.top, self.right, self.bottom, self.left
+        )
+    }
+}
@@ -1,7 +1,7 @@
 use crate::base::{Alignment, EdgeInsets};
-use crate::decoration::{Borders, Color, Material};
+use crate::decoration::{BorderRadius, Borders, BoxShadow, Color, Material, Paint};
 use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
-use math::Vector2;
+use math::{Rect, Vector2};
 use std::fmt::Debug;
 
 #[derive(Debug)]
@@ -14,6 +14,13 @@ impl Layout for Spacer {
             children: vec![],
             material: None,
             margin: EdgeInsets::zero(),
+            text: None,
+            image: None,
+            pointer_handler: None,
+            key_handler: None,
+            focus_id: None,
+            clip: None,
+            clip_radius: BorderRadius::zero(),
         }
     }
 }
@@ -25,8 +32,23 @@ pub struct Container {
     pub alignment: Alignment,
     pub padding: EdgeInsets,
     pub borders: Borders,
+    /// Rounds the corners of the fill and borders, and of `clip`. Doesn't
+    /// affect layout, or hit testing of the container itself.
+    pub border_radius: BorderRadius,
+    /// Painted beneath the box, following its rounded corners. Shadows don't
+    /// change the size of the container.
+    pub shadows: Vec<BoxShadow>,
+    /// Space around the box, which counts towards the size of the container.
+    /// It is taken out of the constraints first, so the box and its margin
+    /// fit together.
     pub margin: EdgeInsets,
     pub color: Color,
+    /// Fills the box instead of `color` when set, e.g. with a gradient.
+    pub fill: Option<Paint>,
+    /// Clip the child to the inside of the borders, so it doesn't paint or
+    /// receive pointer events outside of them. The clip follows the rounded
+    /// corners, see `BorderRadius::inset`.
+    pub clip: bool,
     pub child: Option<Box<dyn Layout>>,
 }
 
@@ -40,14 +62,20 @@ impl Layout for Container {
 }
 
 impl Container {
-    fn layout_with_child(
+    fn paint(&self) -> Paint {
+        match &self.fill {
+            Some(fill) => fill.clone(),
+            None => Paint::Solid(self.color),
+        }
+    }
+
+    pub(crate) fn layout_with_child(
         &self,
         tree: &mut LayoutTree,
         constraints: &BoxConstraints,
         child: &dyn Layout,
     ) -> SizedLayoutBox {
-        let h_axis_constraints = constraints.horizontal();
-        let v_axis_constraints = constraints.vertical();
+        let (h_axis_constraints, v_axis_constraints) = self.inner_constraints(constraints);
         let width = Container::calculate_size(self.width, h_axis_constraints);
         let height = Container::calculate_size(self.height, v_axis_constraints);
 
@@ -86,29 +114,50 @@ impl Container {
         };
         let size = Vector2::new(size_x, size_y) + self.margin.total();
 
-        let pos_x = (pos_x - child_size.x * 0.5).clamp(0.0, constraints.max.x - child_size.x);
-        let pos_y = (pos_y - child_size.y * 0.5).clamp(0.0, constraints.max.y - child_size.y);
+        let pos_x = (pos_x - child_size.x * 0.5).clamp(0.0, h_axis_constraints.y - child_size.x);
+        let pos_y = (pos_y - child_size.y * 0.5).clamp(0.0, v_axis_constraints.y - child_size.y);
         let pos = Vector2::new(pos_x, pos_y);
         let lbox = LayoutBox::from_child(
             sbox,
             pos + self.margin.min() + self.padding.min() + self.borders.min(),
         );
         let id = tree.insert(lbox);
+        let clip = self.clip.then(|| {
+            Rect::new(
+                self.margin.min() + self.borders.min(),
+                size - self.margin.max() - self.borders.max(),
+            )
+        });
+        let clip_radius = match clip {
+            Some(_) => self
+                .border_radius
+                .fit(size - self.margin.total())
+                .inset(&self.borders),
+            None => BorderRadius::zero(),
+        };
 
         SizedLayoutBox {
             size,
             children: vec![id],
             material: Some(Material {
-                fill: self.color,
+                fill: self.paint(),
                 borders: self.borders,
+                radius: self.border_radius,
+                shadows: self.shadows.clone(),
             }),
             margin: self.margin,
+            text: None,
+            image: None,
+            pointer_handler: None,
+            key_handler: None,
+            focus_id: None,
+            clip,
+            clip_radius,
         }
     }
 
-    fn layout_without_child(&self, constraints: &BoxConstraints) -> SizedLayoutBox {
-        let h_axis_constraints = constraints.horizontal();
-        let v_axis_constraints = constraints.vertical();
+    pub(crate) fn layout_without_child(&self, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let (h_axis_constraints, v_axis_constraints) = self.inner_constraints(constraints);
         let width = Container::calculate_size(self.width, h_axis_constraints).unwrap_or(0.0);
         let height = Container::calculate_size(self.height, v_axis_constraints).unwrap_or(0.0);
         let size = Vector2::new(width, height) + self.margin.total();
@@ -116,13 +165,35 @@ impl Container {
             size,
             children: vec![],
             material: Some(Material {
-                fill: self.color,
+                fill: self.paint(),
                 borders: self.borders,
+                radius: self.border_radius,
+                shadows: self.shadows.clone(),
             }),
             margin: self.margin,
+            text: None,
+            image: None,
+            pointer_handler: None,
+            key_handler: None,
+            focus_id: None,
+            clip: None,
+            clip_radius: BorderRadius::zero(),
         }
     }
 
+    // The horizontal and vertical constraints inside the margin. The margin is
+    // part of the size of the container, so it takes space away from the box.
+    fn inner_constraints(&self, constraints: &BoxConstraints) -> (Vector2, Vector2) {
+        let margin = self.margin.total();
+        let shrink = |axis: Vector2, margin: f32| {
+            Vector2::new((axis.x - margin).max(0.0), (axis.y - margin).max(0.0))
+        };
+        (
+            shrink(constraints.horizontal(), margin.x),
+            shrink(constraints.vertical(), margin.y),
+        )
+    }
+
     // If return none, then shrink to fit child
     fn calculate_size(desired_size: Option<f32>, axis_constraints: Vector2) -> Option<f32> {
         let (min_size, max_size) = axis_constraints.into();
@@ -137,8 +208,8 @@ impl Container {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use math::Rect;
-    use test_util::assert_slice_eq;
+    use crate::decoration::BorderSide;
+    use test_util::assert_snapshot_eq;
 
     #[test]
     pub fn container_with_no_child_fills_constraints() {
@@ -148,12 +219,12 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&container, &constraints);
-        let expected_layout = vec![LayoutBox {
-            bounds: Rect::from_pos((0.0, 0.0), (100.0, 100.0)),
-            ..fixed_child_lbox(Color::green())
-        }];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
     }
 
     #[test]
@@ -165,12 +236,12 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&container, &constraints);
-        let expected_layout = vec![LayoutBox {
-            bounds: Rect::from_pos((0.0, 0.0), (100.0, 50.0)),
-            ..fixed_child_lbox(Color::green())
-        }];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 50] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
     }
 
     #[test]
@@ -182,12 +253,12 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&container, &constraints);
-        let expected_layout = vec![LayoutBox {
-            bounds: Rect::from_pos((0.0, 0.0), (50.0, 100.0)),
-            ..fixed_child_lbox(Color::green())
-        }];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
     }
 
     #[test]
@@ -204,42 +275,141 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(f32::INFINITY, f32::INFINITY));
-        let actual_layout = layout_with_constraints(&container, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (50.0, 50.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (50.0, 50.0)),
-                children: vec![0],
-                ..fixed_child_lbox(Color::green())
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 50] fill rgba(0, 255, 0, 255)
+              [0, 0, 50, 50] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
+    }
+
+    #[test]
+    pub fn container_with_padding_and_margin_offsets_child() {
+        let container = Container {
+            padding: EdgeInsets::all(5.0),
+            margin: EdgeInsets::all(10.0),
+            color: Color::green(),
+            child: Some(Box::new(Container {
+                width: Some(20.0),
+                height: Some(20.0),
+                color: Color::red(),
+                ..Container::default()
+            })),
+            ..Container::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100] margin (10, 10, 10, 10) fill rgba(0, 255, 0, 255)
+              [15, 15, 35, 35] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
+    }
+
+    #[test]
+    pub fn container_margin_takes_space_from_constraints() {
+        let container = Container {
+            height: Some(20.0),
+            margin: EdgeInsets::all(4.0),
+            color: Color::green(),
+            ..Container::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 28] margin (4, 4, 4, 4) fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
+    }
+
+    #[test]
+    pub fn container_with_borders_offsets_child() {
+        let container = Container {
+            borders: Borders {
+                top: Some(BorderSide::new(Color::white(), 2.0)),
+                left: Some(BorderSide::new(Color::white(), 4.0)),
+                ..Borders::default()
             },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+            child: Some(Box::new(Container {
+                width: Some(10.0),
+                height: Some(10.0),
+                color: Color::red(),
+                ..Container::default()
+            })),
+            ..Container::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(f32::INFINITY, f32::INFINITY));
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 10] fill rgba(0, 0, 0, 0) top 2 rgba(255, 255, 255, 255) left 4 rgba(255, 255, 255, 255)
+              [4, 2, 14, 12] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&container, &constraints),
+        );
+    }
+
+    #[test]
+    pub fn container_with_clip_clips_child_inside_borders() {
+        let container = Container {
+            width: Some(40.0),
+            height: Some(40.0),
+            margin: EdgeInsets::all(10.0),
+            padding: EdgeInsets::all(5.0),
+            borders: Borders::all(Color::white(), 2.0),
+            clip: true,
+            child: Some(Box::new(Container {
+                width: Some(100.0),
+                height: Some(100.0),
+                ..Container::default()
+            })),
+            ..Container::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        let tree = dump(&container, &constraints);
+        let root = tree.lines().next().unwrap();
+        assert!(root.ends_with(" clip [12, 12, 48, 48]"), "{root}");
+    }
+
+    #[test]
+    pub fn container_with_clip_follows_rounded_corners() {
+        let container = Container {
+            width: Some(40.0),
+            height: Some(20.0),
+            borders: Borders::all(Color::white(), 2.0),
+            border_radius: BorderRadius::all(16.0),
+            clip: true,
+            child: Some(Box::new(Container::default())),
+            ..Container::default()
+        };
+
+        let tree = LayoutTree::from_root(
+            &container,
+            &BoxConstraints::from_max(Vector2::new(100.0, 100.0)),
+        );
+        let root = tree.to_string();
+        let root = root.lines().next().unwrap();
+        // The radius is fit to the 20px height before the border is removed
+        assert!(
+            root.ends_with(" clip [2, 2, 38, 18] radius (8, 8, 8, 8)"),
+            "{root}"
+        );
+        // Inside the clip, but outside of the rounded corner
+        assert_eq!(1, tree.hit_test(Vector2::new(3.0, 3.0)).len());
+        assert_eq!(2, tree.hit_test(Vector2::new(10.0, 10.0)).len());
     }
 
     // --------------------------------------------------
     // Helpers
     // --------------------------------------------------
 
-    fn layout_with_constraints(
-        widget: &dyn Layout,
-        constraints: &BoxConstraints,
-    ) -> Vec<LayoutBox> {
-        let mut tree = LayoutTree::new();
-        let sbox = widget.layout(&mut tree, constraints);
-        let lbox = LayoutBox::from_child(sbox, Vector2::zero());
-        tree.insert(lbox);
-        tree.boxes
-    }
-
-    fn fixed_child_lbox(color: Color) -> LayoutBox {
-        LayoutBox {
-            bounds: Rect::from_size((10.0, 10.0)),
-            children: vec![],
-            material: Some(Material::filled(color)),
-            ..LayoutBox::default()
-        }
+    fn dump(widget: &dyn Layout, constraints: &BoxConstraints) -> String {
+        LayoutTree::from_root(widget, constraints).to_string()
     }
 }
@@ -1,18 +1,329 @@
-use math::{Vector2, Vector4};
+use crate::base::Alignment;
+use math::{Rect, Vector2, Vector4};
+use std::fmt;
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Clone, Debug)]
 pub struct Material {
     pub borders: Borders,
-    pub fill: Color,
+    pub radius: BorderRadius,
+    /// Painted beneath the box, in order, so later shadows are on top.
+    pub shadows: Vec<BoxShadow>,
+    pub fill: Paint,
+}
+
+/// Formats the material as its fill followed by each border, the corner radii
+/// when they aren't zero and each shadow, e.g.
+/// `fill rgba(0, 0, 0, 255) top 1 rgba(255, 0, 0, 255) radius (4, 4, 0, 0)`.
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fill {}", self.fill)?;
+        let sides = [
+            ("top", self.borders.top),
+            ("bottom", self.borders.bottom),
+            ("left", self.borders.left),
+            ("right", self.borders.right),
+        ];
+        for (name, side) in sides {
+            if let Some(side) = side {
+                write!(f, " {name} {} {}", side.width, side.color)?;
+            }
+        }
+        if !self.radius.is_zero() {
+            write!(f, " radius {}", self.radius)?;
+        }
+        for shadow in &self.shadows {
+            write!(f, " shadow {shadow}")?;
+        }
+        Ok(())
+    }
 }
 
 impl Material {
     #[must_use]
-    pub fn filled(fill: Color) -> Material {
+    pub fn filled(fill: impl Into<Paint>) -> Material {
         Material {
             borders: Borders::none(),
-            fill,
+            radius: BorderRadius::zero(),
+            shadows: vec![],
+            fill: fill.into(),
+        }
+    }
+
+    /// The area painted by the material when it fills `rect`, which is larger
+    /// than `rect` when it has shadows.
+    #[must_use]
+    pub fn paint_bounds(&self, rect: Rect) -> Rect {
+        self.shadows
+            .iter()
+            .map(|shadow| shadow.paint_bounds(rect))
+            .fold(rect, Rect::union)
+    }
+}
+
+/// How the inside of a box is filled. Gradients are positioned relative to the
+/// box they fill, so they stretch with it.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Paint {
+    Solid(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+}
+
+impl Default for Paint {
+    fn default() -> Paint {
+        Paint::Solid(Color::transparent())
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Paint {
+        Paint::Solid(color)
+    }
+}
+
+/// Formats solid paint as its color, and gradients like CSS, e.g.
+/// `linear-gradient(90deg, rgba(255, 0, 0, 255) 0, rgba(0, 0, 255, 255) 1)` or
+/// `radial-gradient((0.5, 0.5) 0.5, rgba(255, 0, 0, 255) 0)`.
+impl fmt::Display for Paint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops = match self {
+            Paint::Solid(color) => return write!(f, "{color}"),
+            Paint::LinearGradient(gradient) => {
+                write!(f, "linear-gradient({}deg", gradient.angle)?;
+                &gradient.stops
+            }
+            Paint::RadialGradient(gradient) => {
+                let center = gradient.center;
+                write!(
+                    f,
+                    "radial-gradient(({}, {}) {}",
+                    center.x, center.y, gradient.radius
+                )?;
+                &gradient.stops
+            }
+        };
+        for stop in stops {
+            write!(f, ", {} {}", stop.color, stop.offset)?;
         }
+        write!(f, ")")
+    }
+}
+
+impl Paint {
+    /// The color painted at `point` when the paint fills `rect`.
+    #[must_use]
+    pub fn color_at(&self, point: Vector2, rect: Rect) -> Color {
+        match self {
+            Paint::Solid(color) => *color,
+            Paint::LinearGradient(gradient) => {
+                lerp_stops(&gradient.stops, gradient.offset_at(point, rect))
+            }
+            Paint::RadialGradient(gradient) => {
+                lerp_stops(&gradient.stops, gradient.offset_at(point, rect))
+            }
+        }
+    }
+}
+
+/// The color at `offset` along a gradient, where 0 is the start and 1 is the
+/// end.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl ColorStop {
+    #[must_use]
+    pub fn new(offset: f32, color: Color) -> ColorStop {
+        ColorStop { offset, color }
+    }
+}
+
+/// The color at `offset` along a gradient with `stops`, which are sorted by
+/// their offsets. Before the first stop and after the last one the color
+/// doesn't change. Colors are blended with premultiplied alpha, so fading to
+/// transparent doesn't darken the gradient.
+#[must_use]
+pub fn lerp_stops(stops: &[ColorStop], offset: f32) -> Color {
+    let Some(first) = stops.first() else {
+        return Color::transparent();
+    };
+    if offset <= first.offset {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if offset < to.offset {
+            let t = (offset - from.offset) / (to.offset - from.offset);
+            return from.color.lerp_premultiplied(to.color, t);
+        }
+    }
+    stops[stops.len() - 1].color
+}
+
+/// Colors blended along a straight line through the center of the box.
+///
+/// Like CSS, `angle` is in degrees clockwise from pointing up, so 0 runs from
+/// the bottom to the top and 90 runs from left to right. The line is long
+/// enough that the corners of the box get the first and last colors.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct LinearGradient {
+    pub angle: f32,
+    pub stops: Vec<ColorStop>,
+}
+
+impl LinearGradient {
+    #[must_use]
+    pub fn new(angle: f32, stops: Vec<ColorStop>) -> LinearGradient {
+        LinearGradient { angle, stops }
+    }
+
+    /// The start and end of the gradient line when it fills `rect`.
+    #[must_use]
+    pub fn line(&self, rect: Rect) -> (Vector2, Vector2) {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let size = rect.size();
+        let length = (size.x * sin).abs() + (size.y * cos).abs();
+        let center = (rect.min + rect.max) / 2.0;
+        let direction = Vector2::new(sin, -cos) * (length / 2.0);
+        (center - direction, center + direction)
+    }
+
+    /// How far `point` is along the gradient line when it fills `rect`, from
+    /// 0 at the start to 1 at the end.
+    #[must_use]
+    pub fn offset_at(&self, point: Vector2, rect: Rect) -> f32 {
+        let (start, end) = self.line(rect);
+        let delta = end - start;
+        let length = Vector2::dot(delta, delta);
+        if length <= 0.0 {
+            return 0.0;
+        }
+        Vector2::dot(point - start, delta) / length
+    }
+}
+
+/// Colors blended outwards in a circle. `center` is relative to the size of
+/// the box, and `radius` is a fraction of its shorter side, so a radius of 0.5
+/// centered in the box reaches its nearest edges.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct RadialGradient {
+    pub center: Alignment,
+    pub radius: f32,
+    pub stops: Vec<ColorStop>,
+}
+
+impl RadialGradient {
+    #[must_use]
+    pub fn new(center: Alignment, radius: f32, stops: Vec<ColorStop>) -> RadialGradient {
+        RadialGradient {
+            center,
+            radius,
+            stops,
+        }
+    }
+
+    /// The center and radius of the gradient in pixels when it fills `rect`.
+    #[must_use]
+    pub fn circle(&self, rect: Rect) -> (Vector2, f32) {
+        let size = rect.size();
+        let center = rect.min + Vector2::new(size.x * self.center.x, size.y * self.center.y);
+        (center, self.radius * size.x.min(size.y))
+    }
+
+    /// How far `point` is from the center when the gradient fills `rect`, from
+    /// 0 at the center to 1 at the radius.
+    #[must_use]
+    pub fn offset_at(&self, point: Vector2, rect: Rect) -> f32 {
+        let (center, radius) = self.circle(rect);
+        if radius <= 0.0 {
+            return 1.0;
+        }
+        (point - center).magnitude() / radius
+    }
+}
+
+/// A shadow cast by a box, drawn as a blurred copy of the box's shape.
+///
+/// Like CSS, the shape is moved by `offset`, then grown by `spread` on every
+/// side, and blurred with a Gaussian whose standard deviation is half of
+/// `blur_radius`. Shadows are painted beneath the box, and don't take up any
+/// space in layout.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct BoxShadow {
+    pub offset: Vector2,
+    pub blur_radius: f32,
+    pub spread: f32,
+    pub color: Color,
+}
+
+/// Formats the shadow as `(x, y) blur spread color`.
+impl fmt::Display for BoxShadow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.offset, self.blur_radius, self.spread, self.color
+        )
+    }
+}
+
+impl BoxShadow {
+    #[must_use]
+    pub fn new(offset: Vector2, blur_radius: f32, spread: f32, color: Color) -> BoxShadow {
+        BoxShadow {
+            offset,
+            blur_radius,
+            spread,
+            color,
+        }
+    }
+
+    /// The shape of the shadow of a box filling `rect`, before it is blurred.
+    /// A negative spread that is more than half the size of the box shrinks
+    /// the shape down to its center, rather than turning it inside out.
+    #[must_use]
+    pub fn rect(&self, rect: Rect) -> Rect {
+        let rect = rect.translate(self.offset).inflate(self.spread);
+        let center = (rect.min + rect.max) / 2.0;
+        Rect::new(
+            Vector2::new(rect.min.x.min(center.x), rect.min.y.min(center.y)),
+            Vector2::new(rect.max.x.max(center.x), rect.max.y.max(center.y)),
+        )
+    }
+
+    /// The corner radii of the shadow of a box with `radius` corners. The
+    /// spread is added to rounded corners, but square corners stay square.
+    #[must_use]
+    pub fn radius(&self, radius: BorderRadius) -> BorderRadius {
+        let [top_left, top_right, bottom_right, bottom_left] = radius.to_array().map(|radius| {
+            if radius > 0.0 {
+                (radius + self.spread).max(0.0)
+            } else {
+                0.0
+            }
+        });
+        BorderRadius {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// The standard deviation of the blur.
+    #[must_use]
+    pub fn sigma(&self) -> f32 {
+        self.blur_radius.max(0.0) / 2.0
+    }
+
+    /// Everything the blurred shadow of a box filling `rect` paints. The blur
+    /// is cut off three standard deviations past the edge of the shape, where
+    /// it is too faint to see.
+    #[must_use]
+    pub fn paint_bounds(&self, rect: Rect) -> Rect {
+        self.rect(rect).inflate(3.0 * self.sigma())
     }
 }
 
@@ -90,6 +401,12 @@ impl Borders {
         }
     }
 
+    /// The sides as `[top, bottom, left, right]`.
+    #[must_use]
+    pub fn to_array(&self) -> [Option<BorderSide>; 4] {
+        [self.top, self.bottom, self.left, self.right]
+    }
+
     #[must_use]
     pub fn min(&self) -> Vector2 {
         let top = match self.top {
@@ -143,6 +460,152 @@ impl Borders {
     }
 }
 
+/// The radius of each corner of a box, in pixels. Borders follow the curve of
+/// the corners, and the inside edge of a border is rounded by the corner radius
+/// minus the width of the border on each side.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct BorderRadius {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+/// Formats the radii clockwise from the top-left corner, e.g. `(4, 4, 0, 0)`.
+impl fmt::Display for BorderRadius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}, {}, {})",
+            self.top_left, self.top_right, self.bottom_right, self.bottom_left
+        )
+    }
+}
+
+impl BorderRadius {
+    #[must_use]
+    pub fn all(radius: f32) -> BorderRadius {
+        BorderRadius {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    #[must_use]
+    pub fn zero() -> BorderRadius {
+        BorderRadius::all(0.0)
+    }
+
+    #[must_use]
+    pub fn top(radius: f32) -> BorderRadius {
+        BorderRadius {
+            top_left: radius,
+            top_right: radius,
+            ..BorderRadius::default()
+        }
+    }
+
+    #[must_use]
+    pub fn bottom(radius: f32) -> BorderRadius {
+        BorderRadius {
+            bottom_right: radius,
+            bottom_left: radius,
+            ..BorderRadius::default()
+        }
+    }
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.to_array().iter().all(|radius| *radius <= 0.0)
+    }
+
+    /// The radii as `[top_left, top_right, bottom_right, bottom_left]`.
+    #[must_use]
+    pub fn to_array(&self) -> [f32; 4] {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+
+    /// Scale the radii down so that the corners on each side of a box of
+    /// `size` don't overlap, like CSS does. Every radius is scaled by the same
+    /// factor so the corners keep their proportions. Negative radii are
+    /// treated as zero.
+    #[must_use]
+    pub fn fit(&self, size: Vector2) -> BorderRadius {
+        let [top_left, top_right, bottom_right, bottom_left] =
+            self.to_array().map(|radius| radius.max(0.0));
+        let sides = [
+            (top_left + top_right, size.x),
+            (bottom_left + bottom_right, size.x),
+            (top_left + bottom_left, size.y),
+            (top_right + bottom_right, size.y),
+        ];
+        let scale = sides
+            .iter()
+            .filter(|(sum, _)| *sum > 0.0)
+            .map(|(sum, length)| (length.max(0.0) / sum).min(1.0))
+            .fold(1.0, f32::min);
+        BorderRadius {
+            top_left: top_left * scale,
+            top_right: top_right * scale,
+            bottom_right: bottom_right * scale,
+            bottom_left: bottom_left * scale,
+        }
+    }
+
+    /// The radii of the inside edge of `borders` around a box with these
+    /// corners. Each corner shrinks by the wider of the two borders next to
+    /// it, so it stays circular where the widths differ.
+    #[must_use]
+    pub fn inset(&self, borders: &Borders) -> BorderRadius {
+        let [top, bottom, left, right] = borders
+            .to_array()
+            .map(|side| side.map_or(0.0, |side| side.width));
+        let shrink = |radius: f32, a: f32, b: f32| (radius - a.max(b)).max(0.0);
+        BorderRadius {
+            top_left: shrink(self.top_left, top, left),
+            top_right: shrink(self.top_right, top, right),
+            bottom_right: shrink(self.bottom_right, bottom, right),
+            bottom_left: shrink(self.bottom_left, bottom, left),
+        }
+    }
+
+    /// Whether `point` is inside `rect` with these corners, after they are
+    /// fit to its size. Like `Rect::intersects`, points on the edge are
+    /// outside.
+    #[must_use]
+    pub fn contains(&self, rect: Rect, point: Vector2) -> bool {
+        if !rect.intersects(point) {
+            return false;
+        }
+        let radii = self.fit(rect.size());
+        let middle = (rect.min + rect.max) / 2.0;
+        let (radius, corner) = match (point.x < middle.x, point.y < middle.y) {
+            (true, true) => (radii.top_left, rect.min),
+            (false, true) => (radii.top_right, Vector2::new(rect.max.x, rect.min.y)),
+            (false, false) => (radii.bottom_right, rect.max),
+            (true, false) => (radii.bottom_left, Vector2::new(rect.min.x, rect.max.y)),
+        };
+        let toward_middle = Vector2::new(
+            (middle.x - corner.x).signum(),
+            (middle.y - corner.y).signum(),
+        );
+        let center = corner + toward_middle * radius;
+        let delta = point - center;
+        // Only points past the center of the curve on both axes can be cut off
+        if delta.x * toward_middle.x > 0.0 || delta.y * toward_middle.y > 0.0 {
+            return true;
+        }
+        delta.magnitude() < radius
+    }
+}
+
 impl Default for Material {
     fn default() -> Material {
         Material::filled(Color::transparent())
@@ -164,6 +627,13 @@ impl Default for Color {
     }
 }
 
+/// Formats the color as `rgba(r, g, b, a)`, with components from 0 - 255.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+    }
+}
+
 impl Color {
     #[must_use]
     pub fn rgba(r: f32, g: f32, b: f32, a: f32) -> Color {
@@ -211,6 +681,25 @@ impl Color {
         Color::rgba(self.r, self.g, self.b, alpha * 255.0)
     }
 
+    /// Blend towards `to` with premultiplied alpha, so the color of a
+    /// transparent end doesn't show through.
+    #[must_use]
+    pub fn lerp_premultiplied(self, to: Color, t: f32) -> Color {
+        let a = self.a + (to.a - self.a) * t;
+        if a <= 0.0 {
+            return Color::transparent();
+        }
+        let channel = |from: f32, to_channel: f32| {
+            (from * self.a + (to_channel * to.a - from * self.a) * t) / a
+        };
+        Color::rgba(
+            channel(self.r, to.r),
+            channel(self.g, to.g),
+            channel(self.b, to.b),
+            a,
+        )
+    }
+
     #[must_use]
     pub fn to_linear(&self) -> Vector4 {
         let r = self.r / 255.0;
@@ -245,4 +734,163 @@ mod tests {
         let green = Material::filled(Color::green());
         assert_ne!(red, green);
     }
+
+    #[test]
+    fn material_display_formats_gradient_fill() {
+        let material = Material::filled(Paint::LinearGradient(LinearGradient::new(
+            90.0,
+            vec![
+                ColorStop::new(0.0, Color::red()),
+                ColorStop::new(1.0, Color::blue()),
+            ],
+        )));
+        assert_eq!(
+            "fill linear-gradient(90deg, rgba(255, 0, 0, 255) 0, rgba(0, 0, 255, 255) 1)",
+            material.to_string()
+        );
+    }
+
+    #[test]
+    fn lerp_stops_holds_colors_outside_of_stops() {
+        let stops = [
+            ColorStop::new(0.25, Color::red()),
+            ColorStop::new(0.75, Color::blue()),
+        ];
+        assert_eq!(Color::red(), lerp_stops(&stops, 0.0));
+        assert_eq!(
+            Color::rgba(127.5, 0.0, 127.5, 255.0),
+            lerp_stops(&stops, 0.5)
+        );
+        assert_eq!(Color::blue(), lerp_stops(&stops, 1.0));
+        assert_eq!(Color::transparent(), lerp_stops(&[], 0.5));
+    }
+
+    #[test]
+    fn lerp_stops_to_transparent_keeps_color() {
+        let stops = [
+            ColorStop::new(0.0, Color::red()),
+            ColorStop::new(1.0, Color::transparent()),
+        ];
+        assert_eq!(Color::red().alpha(0.5), lerp_stops(&stops, 0.5));
+    }
+
+    #[test]
+    fn linear_gradient_line_reaches_corners() {
+        let rect = Rect::from_size((20.0, 10.0));
+        let gradient = LinearGradient::new(90.0, vec![]);
+        let (start, end) = gradient.line(rect);
+        assert!((start - Vector2::new(0.0, 5.0)).magnitude() < 1e-4);
+        assert!((end - Vector2::new(20.0, 5.0)).magnitude() < 1e-4);
+
+        // Diagonal gradients are long enough that the corners are at the ends
+        let gradient = LinearGradient::new(135.0, vec![]);
+        assert!(gradient.offset_at(Vector2::zero(), rect).abs() < 1e-4);
+        let end = gradient.offset_at(Vector2::new(20.0, 10.0), rect);
+        assert!((end - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn radial_gradient_radius_is_fraction_of_shorter_side() {
+        let rect = Rect::from_pos((10.0, 10.0), (40.0, 20.0));
+        let gradient = RadialGradient::new(Alignment::new(0.5, 0.5), 0.5, vec![]);
+        assert_eq!((Vector2::new(30.0, 20.0), 10.0), gradient.circle(rect));
+        let edge = gradient.offset_at(Vector2::new(30.0, 30.0), rect);
+        assert!((edge - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn material_display_includes_radius_when_rounded() {
+        let material = Material {
+            radius: BorderRadius::top(4.0),
+            ..Material::filled(Color::red())
+        };
+        assert_eq!(
+            "fill rgba(255, 0, 0, 255) radius (4, 4, 0, 0)",
+            material.to_string()
+        );
+        assert_eq!(
+            "fill rgba(255, 0, 0, 255)",
+            Material::filled(Color::red()).to_string()
+        );
+    }
+
+    #[test]
+    fn material_paint_bounds_include_shadows() {
+        let material = Material {
+            shadows: vec![
+                BoxShadow::new(Vector2::new(0.0, 4.0), 2.0, 0.0, Color::black()),
+                BoxShadow::new(Vector2::new(-10.0, 0.0), 0.0, 1.0, Color::black()),
+            ],
+            ..Material::default()
+        };
+        assert_eq!(
+            Rect::new(Vector2::new(-11.0, -1.0), Vector2::new(23.0, 27.0)),
+            material.paint_bounds(Rect::from_size((20.0, 20.0)))
+        );
+    }
+
+    #[test]
+    fn box_shadow_spread_grows_rounded_corners_only() {
+        let shadow = BoxShadow::new(Vector2::zero(), 0.0, 3.0, Color::black());
+        assert_eq!(
+            BorderRadius::top(7.0),
+            shadow.radius(BorderRadius::top(4.0))
+        );
+        let shadow = BoxShadow::new(Vector2::zero(), 0.0, -6.0, Color::black());
+        assert_eq!(BorderRadius::zero(), shadow.radius(BorderRadius::all(4.0)));
+    }
+
+    #[test]
+    fn box_shadow_negative_spread_stops_at_center() {
+        let shadow = BoxShadow::new(Vector2::new(0.0, 5.0), 8.0, -30.0, Color::black());
+        let rect = shadow.rect(Rect::from_pos((10.0, 10.0), (40.0, 20.0)));
+        assert_eq!(
+            Rect::new(Vector2::new(30.0, 25.0), Vector2::new(30.0, 25.0)),
+            rect
+        );
+        let shadow = BoxShadow::new(Vector2::zero(), 0.0, -5.0, Color::black());
+        assert_eq!(
+            Rect::from_pos((15.0, 15.0), (30.0, 10.0)),
+            shadow.rect(Rect::from_pos((10.0, 10.0), (40.0, 20.0)))
+        );
+    }
+
+    #[test]
+    fn border_radius_fit_keeps_radii_that_fit() {
+        let radius = BorderRadius::all(5.0);
+        assert_eq!(radius, radius.fit(Vector2::new(10.0, 20.0)));
+    }
+
+    #[test]
+    fn border_radius_fit_scales_overlapping_corners_proportionally() {
+        let radius = BorderRadius {
+            top_left: 30.0,
+            top_right: 10.0,
+            ..BorderRadius::default()
+        };
+        let fitted = radius.fit(Vector2::new(20.0, 100.0));
+        assert_eq!(
+            BorderRadius {
+                top_left: 15.0,
+                top_right: 5.0,
+                ..BorderRadius::default()
+            },
+            fitted
+        );
+    }
+
+    #[test]
+    fn border_radius_fit_ignores_negative_radii() {
+        let radius = BorderRadius {
+            top_left: -4.0,
+            ..BorderRadius::all(2.0)
+        };
+        assert_eq!(
+            BorderRadius {
+                top_left: 0.0,
+                ..BorderRadius::all(2.0)
+            },
+            radius.fit(Vector2::new(10.0, 10.0))
+        );
+    }
 }
@@ -0,0 +1,703 @@
+use crate::focus::FocusManager;
+use crate::reactive::Subscribers;
+use crate::tree::{BoxConstraints, Layout, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+/// Distinguishes a widget from its siblings of the same type, so it keeps its
+/// state when siblings are inserted, removed or reordered.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub enum Key {
+    Int(u64),
+    Str(String),
+}
+
+impl From<u64> for Key {
+    fn from(key: u64) -> Key {
+        Key::Int(key)
+    }
+}
+
+impl From<&str> for Key {
+    fn from(key: &str) -> Key {
+        Key::Str(key.to_string())
+    }
+}
+
+impl From<String> for Key {
+    fn from(key: String) -> Key {
+        Key::Str(key)
+    }
+}
+
+/// Identifies an element. It stays the same for as long as the element is in
+/// the tree, so it can be used to look up per-widget data between frames.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct ElementId(pub u64);
+
+/// A widget that owns state that survives between frames.
+///
+/// The state is created with `create_state` the first time the widget appears
+/// in the tree, and is handed back to `build` every frame after that, for as
+/// long as a widget of the same type (and with the same key) is built in the
+/// same place. Every `StatefulWidget` is also a `Layout`, so it can be used
+/// anywhere other widgets can.
+pub trait StatefulWidget: Debug + 'static {
+    type State: 'static;
+
+    fn create_state(&self) -> Self::State;
+
+    fn build(&self, state: &State<Self::State>) -> Box<dyn Layout>;
+
+    /// Used to match the widget with its state from the previous frame. Without
+    /// a key, widgets are matched by their position among their siblings of
+    /// the same type.
+    fn key(&self) -> Option<Key> {
+        None
+    }
+}
+
+impl<W> Layout for W
+where
+    W: StatefulWidget,
+{
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let state = tree
+            .elements
+            .enter(TypeId::of::<W>(), self.key(), || self.create_state());
+        let child = tree.elements.build_once(state.id(), || self.build(&state));
+        let sbox = child.layout(tree, constraints);
+        tree.elements.exit();
+        sbox
+    }
+}
+
+/// A handle to the state of a `StatefulWidget`.
+///
+/// It can be cloned into event handlers, so that they can update the state
+/// that the next frame is built with. Like a `Signal`, reading the state inside
+/// a `Reactive` widget makes it a dependency, so updating it rebuilds the
+/// widget.
+pub struct State<S> {
+    id: ElementId,
+    cell: Rc<StateCell<S>>,
+}
+
+struct StateCell<S> {
+    value: RefCell<S>,
+    subscribers: Subscribers,
+}
+
+impl<S> State<S> {
+    #[must_use]
+    pub fn id(&self) -> ElementId {
+        self.id
+    }
+
+    /// Borrow the state. Panics if it is currently being updated.
+    #[must_use]
+    pub fn get(&self) -> Ref<'_, S> {
+        self.cell.subscribers.track();
+        self.cell.value.borrow()
+    }
+
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut S),
+    {
+        f(&mut self.cell.value.borrow_mut());
+        self.cell.subscribers.notify();
+    }
+
+    pub(crate) fn untracked(&self) -> Ref<'_, S> {
+        self.cell.value.borrow()
+    }
+
+    pub(crate) fn update_untracked<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut S) -> R,
+    {
+        f(&mut self.cell.value.borrow_mut())
+    }
+}
+
+// Derive would require `S: Clone`, but only the `Rc` is cloned.
+impl<S> Clone for State<S> {
+    fn clone(&self) -> State<S> {
+        State {
+            id: self.id,
+            cell: Rc::clone(&self.cell),
+        }
+    }
+}
+
+impl<S: Debug> Debug for State<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("id", &self.id)
+            .field("value", &self.cell.value)
+            .finish()
+    }
+}
+
+// Where an element sits among the children of its parent element.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+enum Slot {
+    Keyed(Key),
+    Unkeyed(usize),
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+struct ElementPath {
+    parent: Option<ElementId>,
+    widget: TypeId,
+    slot: Slot,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    id: ElementId,
+    state: Rc<dyn Any>,
+    // The last frame that built this element
+    frame: u64,
+}
+
+// The element that is currently being built, and how many unkeyed children of
+// each widget type it has built so far.
+#[derive(Clone, Debug)]
+struct Scope {
+    id: ElementId,
+    unkeyed: HashMap<TypeId, usize>,
+}
+
+/// The state of every `StatefulWidget` in the tree, which persists between
+/// frames.
+///
+/// Each frame's widget tree is reconciled against the elements of the previous
+/// frame. A stateful widget is matched to an existing element when it has the
+/// same type, the same nearest stateful ancestor, and either the same key or
+/// the same position among its unkeyed siblings of that type. Elements that
+/// aren't matched are dropped at the end of the frame, along with their state.
+///
+/// Only stateful widgets create elements. Every other widget is transparent,
+/// so wrapping a stateful widget in a `Container` doesn't lose its state.
+#[derive(Clone, Default, Debug)]
+pub struct ElementTree {
+    elements: HashMap<ElementPath, Element>,
+    scopes: Vec<Scope>,
+    root_unkeyed: HashMap<TypeId, usize>,
+    frame: u64,
+    next_id: u64,
+    time: f32,
+    animating: bool,
+    focus: FocusManager,
+    // How many `LayoutTree::measure` calls are in progress
+    measuring: usize,
+    // The children that stateful widgets built while they were measured,
+    // which are reused when they are laid out for real
+    measured: HashMap<ElementId, Rc<dyn Layout>>,
+}
+
+impl ElementTree {
+    #[must_use]
+    pub fn new() -> ElementTree {
+        ElementTree::default()
+    }
+
+    /// The number of elements that were built in the last frame.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Set the time of the next frame in milliseconds, which widgets that
+    /// animate themselves are driven by. This is usually the `time` passed to
+    /// `AppDriver::tick`.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    #[must_use]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Share the app's `FocusManager` with the widgets that are laid out with
+    /// this tree, see `LayoutTree::focus`.
+    pub fn set_focus(&mut self, focus: FocusManager) {
+        self.focus = focus;
+    }
+
+    #[must_use]
+    pub fn focus(&self) -> &FocusManager {
+        &self.focus
+    }
+
+    /// Whether a widget in the last frame is still animating, and needs
+    /// another frame.
+    #[must_use]
+    pub fn is_animating(&self) -> bool {
+        self.animating
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.frame += 1;
+        self.animating = false;
+        self.scopes.clear();
+        self.root_unkeyed.clear();
+        self.measured.clear();
+    }
+
+    pub(crate) fn end_frame(&mut self) {
+        let frame = self.frame;
+        self.elements.retain(|_, element| element.frame == frame);
+        self.measured.clear();
+    }
+
+    // Whether the widgets are only being laid out to find out their size, see
+    // `LayoutTree::measure`. Widgets shouldn't update their state then.
+    pub(crate) fn is_measuring(&self) -> bool {
+        self.measuring > 0
+    }
+
+    // Build the child of a stateful element at most once per frame. A child
+    // that was built while measuring is kept for the layout that follows.
+    pub(crate) fn build_once<F>(&mut self, id: ElementId, build: F) -> Rc<dyn Layout>
+    where
+        F: FnOnce() -> Box<dyn Layout>,
+    {
+        if self.is_measuring() {
+            let child = self.measured.entry(id).or_insert_with(|| Rc::from(build()));
+            Rc::clone(child)
+        } else {
+            self.measured
+                .remove(&id)
+                .unwrap_or_else(|| Rc::from(build()))
+        }
+    }
+
+    // Find or create the element for a widget, and make it the parent of the
+    // elements built until the matching `exit`.
+    pub(crate) fn enter<S, F>(
+        &mut self,
+        widget: TypeId,
+        key: Option<Key>,
+        create_state: F,
+    ) -> State<S>
+    where
+        S: 'static,
+        F: FnOnce() -> S,
+    {
+        let (parent, unkeyed) = match self.scopes.last_mut() {
+            Some(scope) => (Some(scope.id), &mut scope.unkeyed),
+            None => (None, &mut self.root_unkeyed),
+        };
+        let slot = if let Some(key) = key {
+            Slot::Keyed(key)
+        } else {
+            let index = unkeyed.entry(widget).or_insert(0);
+            *index += 1;
+            Slot::Unkeyed(*index - 1)
+        };
+        let path = ElementPath {
+            parent,
+            widget,
+            slot,
+        };
+
+        let frame = self.frame;
+        let next_id = &mut self.next_id;
+        let element = self.elements.entry(path).or_insert_with(|| {
+            *next_id += 1;
+            Element {
+                id: ElementId(*next_id),
+                state: Rc::new(StateCell {
+                    value: RefCell::new(create_state()),
+                    subscribers: Subscribers::default(),
+                }),
+                frame,
+            }
+        });
+        element.frame = frame;
+        let id = element.id;
+        // The path includes the widget type, and each widget type only has one
+        // type of state.
+        let cell = Rc::clone(&element.state)
+            .downcast::<StateCell<S>>()
+            .expect("element state should match its widget type");
+
+        self.scopes.push(Scope {
+            id,
+            unkeyed: HashMap::new(),
+        });
+        State { id, cell }
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.scopes.pop();
+    }
+
+    // How many unkeyed children of each widget type the current element has
+    // built so far. Restoring this after building a subtree lets it be built
+    // again and matched to the same elements.
+    pub(crate) fn unkeyed_slots(&self) -> HashMap<TypeId, usize> {
+        match self.scopes.last() {
+            Some(scope) => scope.unkeyed.clone(),
+            None => self.root_unkeyed.clone(),
+        }
+    }
+
+    pub(crate) fn restore_unkeyed_slots(&mut self, slots: HashMap<TypeId, usize>) {
+        match self.scopes.last_mut() {
+            Some(scope) => scope.unkeyed = slots,
+            None => self.root_unkeyed = slots,
+        }
+    }
+
+    // Keep the descendants of an element whose subtree was reused from the
+    // last frame instead of being built again.
+    pub(crate) fn keep_descendants(&mut self, id: ElementId) {
+        let parents: HashMap<ElementId, Option<ElementId>> = self
+            .elements
+            .iter()
+            .map(|(path, element)| (element.id, path.parent))
+            .collect();
+        let is_descendant = |mut current: ElementId| {
+            while let Some(Some(parent)) = parents.get(&current) {
+                if *parent == id {
+                    return true;
+                }
+                current = *parent;
+            }
+            false
+        };
+        let frame = self.frame;
+        for element in self.elements.values_mut() {
+            if is_descendant(element.id) {
+                element.frame = frame;
+            }
+        }
+    }
+}
+
+impl LayoutTree {
+    /// Lay out a widget as the root of a new tree, reusing the state of the
+    /// stateful widgets in `elements` from the previous frame. Afterwards
+    /// `elements` only contains the widgets that were built in this frame.
+    #[must_use]
+    pub fn from_root_with_elements(
+        widget: &dyn Layout,
+        constraints: &BoxConstraints,
+        elements: &mut ElementTree,
+    ) -> LayoutTree {
+        let mut tree = LayoutTree::new();
+        tree.elements = std::mem::take(elements);
+        tree.elements.begin_frame();
+        tree.layout_root(widget, constraints);
+        tree.elements.end_frame();
+        *elements = std::mem::take(&mut tree.elements);
+        tree
+    }
+
+    /// Lay out `widget` only to find out how large it would be. The boxes it
+    /// inserts are removed again, and stateful widgets inside it are matched
+    /// to the same elements when it is laid out for real afterwards.
+    ///
+    /// Measuring doesn't have side effects: stateful widgets reuse the child
+    /// they build here when they are laid out for real, animations don't
+    /// advance, and `Reactive` widgets don't cache the measured layout.
+    pub fn measure(&mut self, widget: &dyn Layout, constraints: &BoxConstraints) -> Vector2 {
+        let boxes = self.boxes.len();
+        let slots = self.elements.unkeyed_slots();
+        self.elements.measuring += 1;
+        let size = widget.layout(self, constraints).size;
+        self.elements.measuring -= 1;
+        self.boxes.truncate(boxes);
+        self.elements.restore_unkeyed_slots(slots);
+        size
+    }
+
+    // Called by widgets that are still animating, so the driver keeps
+    // requesting frames, and cached `Reactive` ancestors are laid out again.
+    // Widgets are laid out for real after being measured, so this only has to
+    // happen then.
+    pub(crate) fn request_frame(&mut self) {
+        if self.elements.is_measuring() {
+            return;
+        }
+        self.elements.animating = true;
+        if let Some(scope) = self.reactive_scopes.last() {
+            scope.mark_needs_layout();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::flex::{Flex, FlexLayout};
+
+    // Records the state it was built with, and counts how many frames it has
+    // been built for.
+    #[derive(Debug)]
+    struct Counter {
+        key: Option<Key>,
+        seen: Rc<RefCell<Vec<(ElementId, u32)>>>,
+    }
+
+    impl StatefulWidget for Counter {
+        type State = u32;
+
+        fn create_state(&self) -> u32 {
+            0
+        }
+
+        fn build(&self, state: &State<u32>) -> Box<dyn Layout> {
+            state.update(|count| *count += 1);
+            self.seen.borrow_mut().push((state.id(), *state.get()));
+            Box::new(Container::default())
+        }
+
+        fn key(&self) -> Option<Key> {
+            self.key.clone()
+        }
+    }
+
+    // A stateful widget that builds other widgets
+    #[derive(Debug)]
+    struct Parent {
+        children: Vec<Counter>,
+    }
+
+    impl StatefulWidget for Parent {
+        type State = ();
+
+        fn create_state(&self) {}
+
+        fn build(&self, _: &State<()>) -> Box<dyn Layout> {
+            let children = self
+                .children
+                .iter()
+                .map(|child| -> Box<dyn FlexLayout> {
+                    Box::new(Counter {
+                        key: child.key.clone(),
+                        seen: Rc::clone(&child.seen),
+                    })
+                })
+                .collect();
+            Box::new(Flex {
+                children,
+                ..Flex::default()
+            })
+        }
+    }
+
+    // A stateful widget that always builds the same container
+    #[derive(Debug)]
+    struct FixedSize;
+
+    impl StatefulWidget for FixedSize {
+        type State = ();
+
+        fn create_state(&self) {}
+
+        fn build(&self, _: &State<()>) -> Box<dyn Layout> {
+            Box::new(Container {
+                width: Some(20.0),
+                height: Some(10.0),
+                ..Container::default()
+            })
+        }
+    }
+
+    #[test]
+    fn state_persists_between_frames() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        for _ in 0..3 {
+            build(&counter(None, &seen), &mut elements);
+        }
+        let counts: Vec<u32> = seen.borrow().iter().map(|(_, count)| *count).collect();
+        assert_eq!(vec![1, 2, 3], counts);
+        assert_eq!(1, elements.len());
+    }
+
+    #[test]
+    fn state_is_fresh_without_element_tree() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        for _ in 0..2 {
+            let _ = LayoutTree::from_root(&counter(None, &seen), &constraints());
+        }
+        let counts: Vec<u32> = seen.borrow().iter().map(|(_, count)| *count).collect();
+        assert_eq!(vec![1, 1], counts);
+    }
+
+    #[test]
+    fn element_id_is_stable_between_frames() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(&counter(None, &seen), &mut elements);
+        build(&counter(None, &seen), &mut elements);
+        let seen = seen.borrow();
+        assert_eq!(seen[0].0, seen[1].0);
+    }
+
+    #[test]
+    fn state_survives_being_wrapped_in_stateless_widgets() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(&counter(None, &seen), &mut elements);
+        let wrapped = Container {
+            child: Some(Box::new(counter(None, &seen))),
+            ..Container::default()
+        };
+        build(&wrapped, &mut elements);
+        let counts: Vec<u32> = seen.borrow().iter().map(|(_, count)| *count).collect();
+        assert_eq!(vec![1, 2], counts);
+    }
+
+    #[test]
+    fn state_is_dropped_when_widget_is_removed() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(&counter(None, &seen), &mut elements);
+        build(&Container::default(), &mut elements);
+        assert!(elements.is_empty());
+        build(&counter(None, &seen), &mut elements);
+        let counts: Vec<u32> = seen.borrow().iter().map(|(_, count)| *count).collect();
+        assert_eq!(vec![1, 1], counts);
+    }
+
+    #[test]
+    fn unkeyed_children_are_matched_by_position() {
+        let a = Rc::new(RefCell::new(vec![]));
+        let b = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(
+            &parent(vec![counter(None, &a), counter(None, &b)]),
+            &mut elements,
+        );
+        // Without keys, the state follows the position, not the widget.
+        build(&parent(vec![counter(None, &b)]), &mut elements);
+        assert_eq!(a.borrow()[0].0, b.borrow()[1].0);
+        assert_eq!(2, b.borrow()[1].1);
+    }
+
+    #[test]
+    fn keyed_children_keep_state_when_reordered() {
+        let a = Rc::new(RefCell::new(vec![]));
+        let b = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(
+            &parent(vec![counter(Some("a"), &a), counter(Some("b"), &b)]),
+            &mut elements,
+        );
+        build(
+            &parent(vec![counter(Some("b"), &b), counter(Some("a"), &a)]),
+            &mut elements,
+        );
+        assert_eq!(a.borrow()[0].0, a.borrow()[1].0);
+        assert_eq!(b.borrow()[0].0, b.borrow()[1].0);
+        assert_ne!(a.borrow()[0].0, b.borrow()[0].0);
+        assert_eq!(2, a.borrow()[1].1);
+        assert_eq!(2, b.borrow()[1].1);
+    }
+
+    #[test]
+    fn keyed_child_keeps_state_when_sibling_is_removed() {
+        let a = Rc::new(RefCell::new(vec![]));
+        let b = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(
+            &parent(vec![counter(Some("a"), &a), counter(Some("b"), &b)]),
+            &mut elements,
+        );
+        build(&parent(vec![counter(Some("b"), &b)]), &mut elements);
+        assert_eq!(2, b.borrow()[1].1);
+        assert_eq!(2, elements.len());
+    }
+
+    #[test]
+    fn changing_key_resets_state() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        build(&counter(Some("a"), &seen), &mut elements);
+        build(&counter(Some("b"), &seen), &mut elements);
+        let counts: Vec<u32> = seen.borrow().iter().map(|(_, count)| *count).collect();
+        assert_eq!(vec![1, 1], counts);
+        assert_eq!(1, elements.len());
+    }
+
+    #[test]
+    fn stateful_widget_is_transparent_to_layout() {
+        let widget = Container {
+            width: Some(20.0),
+            height: Some(10.0),
+            ..Container::default()
+        };
+        let expected = LayoutTree::from_root(&widget, &constraints());
+
+        let mut elements = ElementTree::new();
+        let actual = LayoutTree::from_root_with_elements(&FixedSize, &constraints(), &mut elements);
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn measuring_doesnt_change_layout_or_elements() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut elements = ElementTree::new();
+        let measured = MeasuredFirst(counter(None, &seen));
+        let tree = LayoutTree::from_root_with_elements(&measured, &constraints(), &mut elements);
+        assert_eq!(
+            LayoutTree::from_root(&Container::default(), &constraints()).to_string(),
+            tree.to_string()
+        );
+        assert_eq!(1, elements.len());
+        // The child built while measuring is reused
+        assert_eq!(1, seen.borrow().len());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    // Measures its child before laying it out
+    #[derive(Debug)]
+    struct MeasuredFirst(Counter);
+
+    impl Layout for MeasuredFirst {
+        fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+            let _ = tree.measure(&self.0, constraints);
+            self.0.layout(tree, constraints)
+        }
+    }
+
+    fn constraints() -> BoxConstraints {
+        BoxConstraints::from_max(Vector2::new(100.0, 100.0))
+    }
+
+    fn build(widget: &dyn Layout, elements: &mut ElementTree) {
+        let _ = LayoutTree::from_root_with_elements(widget, &constraints(), elements);
+    }
+
+    fn counter(key: Option<&str>, seen: &Rc<RefCell<Vec<(ElementId, u32)>>>) -> Counter {
+        Counter {
+            key: key.map(Key::from),
+            seen: Rc::clone(seen),
+        }
+    }
+
+    fn parent(children: Vec<Counter>) -> Parent {
+        Parent { children }
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::rc::Rc;
+
+// Whether an event handler consumed the event. Handled events stop bubbling
+// up to ancestors.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum EventStatus {
+    Ignored,
+    Handled,
+}
+
+/// A callback for events of type `E` that is attached to a `LayoutBox`.
+///
+/// Handlers are compared by identity, so that `LayoutBox` can still be
+/// compared in tests.
+pub struct EventHandler<E> {
+    callback: Rc<dyn Fn(&E) -> EventStatus>,
+}
+
+impl<E> EventHandler<E> {
+    pub fn new<F>(callback: F) -> EventHandler<E>
+    where
+        F: Fn(&E) -> EventStatus + 'static,
+    {
+        EventHandler {
+            callback: Rc::new(callback),
+        }
+    }
+
+    #[must_use]
+    pub fn handle(&self, event: &E) -> EventStatus {
+        (self.callback)(event)
+    }
+}
+
+// Derive would require `E: Clone`, but only the `Rc` is cloned.
+impl<E> Clone for EventHandler<E> {
+    fn clone(&self) -> EventHandler<E> {
+        EventHandler {
+            callback: Rc::clone(&self.callback),
+        }
+    }
+}
+
+impl<E> PartialEq for EventHandler<E> {
+    fn eq(&self, other: &EventHandler<E>) -> bool {
+        let lhs = Rc::as_ptr(&self.callback).cast::<()>();
+        let rhs = Rc::as_ptr(&other.callback).cast::<()>();
+        lhs == rhs
+    }
+}
+
+impl<E> fmt::Debug for EventHandler<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventHandler").finish_non_exhaustive()
+    }
+}
@@ -1,4 +1,4 @@
-use super::{BoxConstraints, Container, Layout, LayoutBox, LayoutTree, Material, SizedLayoutBox};
+use super::{BoxConstraints, Container, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
 use math::Vector2;
 use std::collections::VecDeque;
 use std::fmt::Debug;
@@ -437,8 +437,8 @@ mod tests {
     use super::*;
     use crate::container;
     use crate::decoration::Color;
-    use math::{Rect, Vector2};
-    use test_util::assert_slice_eq;
+    use math::Vector2;
+    use test_util::assert_snapshot_eq;
 
     // --------------------------------------------------
     // Flexible
@@ -474,24 +474,14 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..flex_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (10.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 20.0)),
-                children: vec![0, 1],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 20]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [0, 10, 10, 20] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     // --------------------------------------------------
@@ -509,17 +499,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            fixed_child_lbox(Color::green()),
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -533,17 +519,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            fixed_child_lbox(Color::green()),
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -561,28 +543,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 30.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 30]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [0, 10, 10, 20] fill rgba(0, 255, 0, 255)
+              [0, 20, 10, 30] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -600,28 +569,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((30.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 30, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [10, 0, 20, 10] fill rgba(0, 255, 0, 255)
+              [20, 0, 30, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -635,20 +591,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -662,20 +611,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -693,29 +635,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -733,29 +661,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -769,20 +683,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -796,20 +703,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -827,28 +727,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [0, 10, 10, 20] fill rgba(0, 255, 0, 255)
+              [0, 20, 10, 30] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -866,28 +753,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [10, 0, 20, 10] fill rgba(0, 255, 0, 255)
+              [20, 0, 30, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -901,20 +775,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -928,20 +795,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -959,29 +819,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -999,29 +845,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     // --------------------------------------------------
@@ -1039,20 +871,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1066,20 +891,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1097,28 +915,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [0, 10, 10, 20] fill rgba(0, 255, 0, 255)
+              [0, 20, 10, 30] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1136,28 +941,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [10, 0, 20, 10] fill rgba(0, 255, 0, 255)
+              [20, 0, 30, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1171,20 +963,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1198,20 +983,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1229,29 +1007,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1269,29 +1033,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1305,20 +1055,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 90, 10, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1332,20 +1075,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [90, 0, 100, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1363,31 +1099,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 70.0), (10.0, 10.0)),
-                material: Some(Material::filled(Color::red())),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 80.0), (10.0, 10.0)),
-                material: Some(Material::filled(Color::green())),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (10.0, 10.0)),
-                material: Some(Material::filled(Color::blue())),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 70, 10, 80] fill rgba(255, 0, 0, 255)
+              [0, 80, 10, 90] fill rgba(0, 255, 0, 255)
+              [0, 90, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1401,20 +1121,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1428,20 +1141,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1455,20 +1161,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 45.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1482,20 +1181,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((45.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1509,20 +1201,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1536,20 +1221,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1563,20 +1241,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 45.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1590,20 +1261,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((45.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1621,28 +1285,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 45.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+              [0, 90, 10, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1660,28 +1311,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((45.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+              [90, 0, 100, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1695,20 +1333,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1722,20 +1353,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1753,29 +1377,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1793,29 +1403,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1829,20 +1425,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 45.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1856,20 +1445,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((45.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1887,29 +1469,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let spacing = (100.0 - 30.0) / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, spacing * 0.5), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0 + spacing * 1.5), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0 + spacing * 2.5), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 11.666667, 10, 21.666668] fill rgba(0, 255, 0, 255)
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+              [0, 78.333336, 10, 88.333336] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1927,29 +1495,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let spacing = (100.0 - 30.0) / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((spacing * 0.5, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0 + spacing * 1.5, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0 + spacing * 2.5, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [11.666667, 0, 21.666668, 10] fill rgba(0, 255, 0, 255)
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+              [78.333336, 0, 88.333336, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -1963,20 +1517,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -1990,20 +1537,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2021,29 +1561,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2061,29 +1587,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2097,20 +1609,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 45.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2124,20 +1629,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((45.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2155,29 +1653,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let spacing = (100.0 - 30.0) / 4.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, spacing), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0 + spacing * 2.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0 + spacing * 3.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 17.5, 10, 27.5] fill rgba(0, 255, 0, 255)
+              [0, 45, 10, 55] fill rgba(0, 255, 0, 255)
+              [0, 72.5, 10, 82.5] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2195,29 +1679,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let spacing = (100.0 - 30.0) / 4.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((spacing, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0 + spacing * 2.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0 + spacing * 3.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [17.5, 0, 27.5, 10] fill rgba(0, 255, 0, 255)
+              [45, 0, 55, 10] fill rgba(0, 255, 0, 255)
+              [72.5, 0, 82.5, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2231,20 +1701,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2258,20 +1721,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     // --------------------------------------------------
@@ -2289,20 +1745,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2316,20 +1765,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2347,28 +1789,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [0, 10, 10, 20] fill rgba(0, 255, 0, 255)
+              [0, 20, 10, 30] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2386,28 +1815,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 10, 10] fill rgba(0, 255, 0, 255)
+              [10, 0, 20, 10] fill rgba(0, 255, 0, 255)
+              [20, 0, 30, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2421,20 +1837,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2448,20 +1857,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2479,29 +1881,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((10.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 100]
+              [0, 0, 10, 33.333332] fill rgba(0, 0, 255, 255)
+              [0, 33.333332, 10, 66.666664] fill rgba(0, 0, 255, 255)
+              [0, 66.666664, 10, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2519,29 +1907,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 10.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 33.333332, 10] fill rgba(0, 0, 255, 255)
+              [33.333332, 0, 66.666664, 10] fill rgba(0, 0, 255, 255)
+              [66.666664, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2555,20 +1929,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [90, 0, 100, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2582,20 +1949,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 90, 10, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2613,28 +1973,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 10.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 20.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [90, 0, 100, 10] fill rgba(255, 0, 0, 255)
+              [90, 10, 100, 20] fill rgba(0, 255, 0, 255)
+              [90, 20, 100, 30] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2652,28 +1999,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 90.0), (10.0, 10.0)),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 90, 10, 100] fill rgba(255, 0, 0, 255)
+              [10, 90, 20, 100] fill rgba(0, 255, 0, 255)
+              [20, 90, 30, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2687,20 +2021,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [90, 0, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2714,20 +2041,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (100.0, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 90, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2745,29 +2065,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, 0.0), (10.0, size)),
-                ..flex_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, size), (10.0, size)),
-                ..flex_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((90.0, size * 2.0), (10.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [90, 0, 100, 33.333332] fill rgba(255, 0, 0, 255)
+              [90, 33.333332, 100, 66.666664] fill rgba(0, 255, 0, 255)
+              [90, 66.666664, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2785,29 +2091,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 90.0), (size, 10.0)),
-                ..flex_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 90.0), (size, 10.0)),
-                ..flex_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 90.0), (size, 10.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 90, 33.333332, 100] fill rgba(255, 0, 0, 255)
+              [33.333332, 90, 66.666664, 100] fill rgba(0, 255, 0, 255)
+              [66.666664, 90, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2821,20 +2113,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 100, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2848,20 +2133,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 10, 100] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2879,28 +2157,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 10.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 10.0), (100.0, 10.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 20.0), (100.0, 10.0)),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 100, 10] fill rgba(255, 0, 0, 255)
+              [0, 10, 100, 20] fill rgba(0, 255, 0, 255)
+              [0, 20, 100, 30] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2918,28 +2183,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (10.0, 100.0)),
-                ..fixed_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((10.0, 0.0), (10.0, 100.0)),
-                ..fixed_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((20.0, 0.0), (10.0, 100.0)),
-                ..fixed_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 10, 100] fill rgba(255, 0, 0, 255)
+              [10, 0, 20, 100] fill rgba(0, 255, 0, 255)
+              [20, 0, 30, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -2953,20 +2205,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -2980,20 +2225,13 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
     }
 
     #[test]
@@ -3011,29 +2249,15 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&column, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (100.0, size)),
-                ..flex_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size), (100.0, size)),
-                ..flex_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, size * 2.0), (100.0, size)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 100, 33.333332] fill rgba(255, 0, 0, 255)
+              [0, 33.333332, 100, 66.666664] fill rgba(0, 255, 0, 255)
+              [0, 66.666664, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&column, &constraints),
+        );
     }
 
     #[test]
@@ -3051,44 +2275,90 @@ mod tests {
         };
 
         let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
-        let actual_layout = layout_with_constraints(&row, &constraints);
-        let size = 100.0 / 3.0;
-        let expected_layout = vec![
-            LayoutBox {
-                bounds: Rect::from_pos((0.0, 0.0), (size, 100.0)),
-                ..flex_child_lbox(Color::red())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size, 0.0), (size, 100.0)),
-                ..flex_child_lbox(Color::green())
-            },
-            LayoutBox {
-                bounds: Rect::from_pos((size * 2.0, 0.0), (size, 100.0)),
-                ..flex_child_lbox(Color::blue())
-            },
-            LayoutBox {
-                bounds: Rect::from_size((100.0, 100.0)),
-                children: vec![0, 1, 2],
-                material: None,
-                ..LayoutBox::default()
-            },
-        ];
-        assert_slice_eq(&expected_layout, &actual_layout);
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 100]
+              [0, 0, 33.333332, 100] fill rgba(255, 0, 0, 255)
+              [33.333332, 0, 66.666664, 100] fill rgba(0, 255, 0, 255)
+              [66.666664, 0, 100, 100] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&row, &constraints),
+        );
+    }
+
+    // --------------------------------------------------
+    // Snapshots
+    // --------------------------------------------------
+
+    #[test]
+    fn row_with_flexible_child_between_fixed_children_snapshot() {
+        let flex = Flex {
+            axis: Axis::Horizontal,
+            children: vec![
+                create_fixed_child(Color::red()),
+                Box::new(Flexible {
+                    flex_factor: 1.0,
+                    child: Box::new(Container {
+                        color: Color::green(),
+                        ..Container::default()
+                    }),
+                }),
+                create_fixed_child(Color::blue()),
+            ],
+            ..Flex::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 20.0));
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 20]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [10, 0, 90, 20] fill rgba(0, 255, 0, 255)
+              [90, 0, 100, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&flex, &constraints),
+        );
+    }
+
+    #[test]
+    fn nested_column_in_row_snapshot() {
+        let flex = Flex {
+            axis: Axis::Horizontal,
+            main_axis_alignment: MainAxisAlignment::SpaceBetween,
+            children: vec![
+                create_fixed_child(Color::red()),
+                Box::new(Flex {
+                    axis: Axis::Vertical,
+                    main_axis_size: MainAxisSize::Min,
+                    children: vec![
+                        create_fixed_child(Color::green()),
+                        create_fixed_child(Color::blue()),
+                    ],
+                    ..Flex::default()
+                }),
+            ],
+            ..Flex::default()
+        };
+
+        let constraints = BoxConstraints::from_max(Vector2::new(50.0, 50.0));
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 20]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [40, 0, 50, 20]
+                [40, 0, 50, 10] fill rgba(0, 255, 0, 255)
+                [40, 10, 50, 20] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&flex, &constraints),
+        );
     }
 
     // --------------------------------------------------
     // Helpers
     // --------------------------------------------------
 
-    fn layout_with_constraints(
-        widget: &dyn Layout,
-        constraints: &BoxConstraints,
-    ) -> Vec<LayoutBox> {
-        let mut tree = LayoutTree::new();
-        let sbox = widget.layout(&mut tree, constraints);
-        let lbox = LayoutBox::from_child(sbox, Vector2::zero());
-        tree.insert(lbox);
-        tree.boxes
+    fn dump(widget: &dyn Layout, constraints: &BoxConstraints) -> String {
+        LayoutTree::from_root(widget, constraints).to_string()
     }
 
     fn create_fixed_child(color: Color) -> Box<Container> {
@@ -3100,15 +2370,6 @@ mod tests {
         })
     }
 
-    fn fixed_child_lbox(color: Color) -> LayoutBox {
-        LayoutBox {
-            bounds: Rect::from_size((10.0, 10.0)),
-            children: vec![],
-            material: Some(Material::filled(color)),
-            ..LayoutBox::default()
-        }
-    }
-
     fn create_flex_child(color: Color) -> Box<Flexible> {
         Box::new(Flexible {
             flex_factor: 1.0,
@@ -3120,13 +2381,4 @@ mod tests {
             }),
         })
     }
-
-    fn flex_child_lbox(color: Color) -> LayoutBox {
-        LayoutBox {
-            bounds: Rect::from_size((10.0, 10.0)),
-            children: vec![],
-            material: Some(Material::filled(color)),
-            ..LayoutBox::default()
-        }
-    }
 }
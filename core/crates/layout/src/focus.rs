@@ -0,0 +1,538 @@
+use crate::event::EventStatus;
+use crate::keyboard::KeyboardEvent;
+use crate::reactive::Signal;
+use crate::tree::{LayoutBoxId, LayoutTree};
+use math::Vector2;
+
+/// Identifies a focusable widget. Unlike a `LayoutBoxId` it stays the same
+/// between frames, so focus survives the tree being rebuilt.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct FocusId(pub u64);
+
+/// Tracks which widget has keyboard focus, and delivers keyboard events to it.
+///
+/// The manager only stores the `FocusId`, so it can be kept for the lifetime
+/// of the app and used with a new `LayoutTree` every frame. Clones share the
+/// same focus, so widgets can keep one from `LayoutTree::focus` in their event
+/// handlers to move focus themselves.
+///
+/// The focused widget is stored in a `Signal`, so reading it while laying out
+/// a `Reactive` widget lays it out again when focus moves.
+#[derive(Clone, Debug)]
+pub struct FocusManager {
+    focused: Signal<Option<FocusId>>,
+}
+
+impl Default for FocusManager {
+    fn default() -> FocusManager {
+        FocusManager {
+            focused: Signal::new(None),
+        }
+    }
+}
+
+impl FocusManager {
+    #[must_use]
+    pub fn new() -> FocusManager {
+        FocusManager::default()
+    }
+
+    #[must_use]
+    pub fn focused(&self) -> Option<FocusId> {
+        self.focused.get()
+    }
+
+    pub fn focus(&self, id: FocusId) {
+        self.set_focused(Some(id));
+    }
+
+    pub fn blur(&self) {
+        self.set_focused(None);
+    }
+
+    /// Move focus to the next focusable widget in tree order, wrapping around
+    /// to the first one. Returns the newly focused widget.
+    pub fn focus_next(&mut self, tree: &LayoutTree) -> Option<FocusId> {
+        let order = focus_order(tree);
+        let next = match self.position_in(&order) {
+            Some(index) => order.get((index + 1) % order.len()),
+            None => order.first(),
+        };
+        self.set_focused(next.copied());
+        next.copied()
+    }
+
+    /// Move focus to the previous focusable widget in tree order, wrapping
+    /// around to the last one. Returns the newly focused widget.
+    pub fn focus_previous(&mut self, tree: &LayoutTree) -> Option<FocusId> {
+        let order = focus_order(tree);
+        let previous = match self.position_in(&order) {
+            Some(index) => order.get((index + order.len() - 1) % order.len()),
+            None => order.last(),
+        };
+        self.set_focused(previous.copied());
+        previous.copied()
+    }
+
+    /// Focus the innermost focusable widget under `point`, or remove focus
+    /// when there isn't one. The driver calls this when a pointer goes down,
+    /// before the event is dispatched, so handlers can still move focus
+    /// somewhere else.
+    pub fn focus_at(&mut self, tree: &LayoutTree, point: Vector2) -> Option<FocusId> {
+        let focus_id = tree
+            .hit_test(point)
+            .iter()
+            .find_map(|entry| tree.get(entry.id).and_then(|lbox| lbox.focus_id));
+        self.set_focused(focus_id);
+        focus_id
+    }
+
+    /// Send a keyboard event to the focused widget. It bubbles up to the
+    /// ancestors of the widget until a handler returns `EventStatus::Handled`.
+    ///
+    /// When no handler consumes a Tab key press, focus moves to the next
+    /// widget, or the previous one when Shift is held.
+    ///
+    /// Tab is only handled when there is a widget to move focus to, so
+    /// otherwise the browser can move focus out of the canvas.
+    pub fn dispatch(&mut self, tree: &LayoutTree, event: &KeyboardEvent) -> EventStatus {
+        if let Some(focused) = self.focused() {
+            for id in focus_path(tree, focused) {
+                let handler = tree.get(id).and_then(|lbox| lbox.key_handler.as_ref());
+                if let Some(handler) = handler {
+                    if handler.handle(event) == EventStatus::Handled {
+                        return EventStatus::Handled;
+                    }
+                }
+            }
+        }
+        match event {
+            KeyboardEvent::Key(key) if key.is_down("Tab") => {
+                let focused = if key.modifiers.shift {
+                    self.focus_previous(tree)
+                } else {
+                    self.focus_next(tree)
+                };
+                match focused {
+                    Some(_) => EventStatus::Handled,
+                    None => EventStatus::Ignored,
+                }
+            }
+            _ => EventStatus::Ignored,
+        }
+    }
+
+    // Only notifies when focus actually moves, so reactive widgets that read
+    // it aren't laid out again for nothing.
+    fn set_focused(&self, focused: Option<FocusId>) {
+        if self.focused() != focused {
+            self.focused.set(focused);
+        }
+    }
+
+    fn position_in(&self, order: &[FocusId]) -> Option<usize> {
+        let focused = self.focused()?;
+        order.iter().position(|id| *id == focused)
+    }
+}
+
+impl LayoutTree {
+    /// The app's `FocusManager`, so widgets can check whether they are
+    /// focused while they are laid out, e.g. to draw a focus ring, and keep a
+    /// clone to move focus from their event handlers. It is only shared with
+    /// the app when set with `ElementTree::set_focus`.
+    #[must_use]
+    pub fn focus(&self) -> &FocusManager {
+        self.elements.focus()
+    }
+}
+
+/// The focusable widgets in the tree, in the order that Tab visits them. This
+/// is a pre-order traversal, so parents come before their children.
+#[must_use]
+pub fn focus_order(tree: &LayoutTree) -> Vec<FocusId> {
+    fn visit(tree: &LayoutTree, id: LayoutBoxId, order: &mut Vec<FocusId>) {
+        let Some(lbox) = tree.get(id) else {
+            return;
+        };
+        if let Some(focus_id) = lbox.focus_id {
+            order.push(focus_id);
+        }
+        for child in &lbox.children {
+            visit(tree, *child, order);
+        }
+    }
+    let mut order = vec![];
+    if let Some(root) = tree.root {
+        visit(tree, root, &mut order);
+    }
+    order
+}
+
+// The boxes from the focused widget up to the root, in the order that events
+// bubble in. Empty when the widget isn't in the tree.
+fn focus_path(tree: &LayoutTree, focused: FocusId) -> Vec<LayoutBoxId> {
+    fn visit(
+        tree: &LayoutTree,
+        id: LayoutBoxId,
+        focused: FocusId,
+        path: &mut Vec<LayoutBoxId>,
+    ) -> bool {
+        let Some(lbox) = tree.get(id) else {
+            return false;
+        };
+        let found = lbox.focus_id == Some(focused)
+            || lbox
+                .children
+                .iter()
+                .any(|child| visit(tree, *child, focused, path));
+        if found {
+            path.push(id);
+        }
+        found
+    }
+    let mut path = vec![];
+    if let Some(root) = tree.root {
+        visit(tree, root, focused, &mut path);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::decoration::{Borders, Color};
+    use crate::element::ElementTree;
+    use crate::flex::{Flex, FlexLayout};
+    use crate::keyboard::{Focus, KeyEvent, KeyEventKind, KeyHandler, KeyListener, Modifiers};
+    use crate::pointer::{Listener, PointerEvent, PointerEventKind, PointerHandler};
+    use crate::reactive::Reactive;
+    use crate::tree::{BoxConstraints, Layout, SizedLayoutBox};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // A focusable square that draws a border while it is focused, and asks
+    // for focus when it is clicked. It is wrapped in a `Reactive`, so it is
+    // only laid out again when focus changes.
+    #[derive(Debug)]
+    struct FocusRing {
+        id: FocusId,
+    }
+
+    impl Layout for FocusRing {
+        fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+            let id = self.id;
+            Reactive::new(move || Box::new(FocusRingBox { id })).layout(tree, constraints)
+        }
+    }
+
+    #[derive(Debug)]
+    struct FocusRingBox {
+        id: FocusId,
+    }
+
+    impl Layout for FocusRingBox {
+        fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+            let focus = tree.focus().clone();
+            let borders = if focus.focused() == Some(self.id) {
+                Borders::all(Color::blue(), 2.0)
+            } else {
+                Borders::all(Color::transparent(), 2.0)
+            };
+            let id = self.id;
+            let widget = Listener {
+                on_pointer: PointerHandler::new(move |event| {
+                    if event.kind != PointerEventKind::Down {
+                        return EventStatus::Ignored;
+                    }
+                    focus.focus(id);
+                    EventStatus::Handled
+                }),
+                child: Box::new(Focus {
+                    id,
+                    on_key: None,
+                    child: Box::new(Container {
+                        width: Some(10.0),
+                        height: Some(10.0),
+                        borders,
+                        ..Container::default()
+                    }),
+                }),
+            };
+            widget.layout(tree, constraints)
+        }
+    }
+
+    #[test]
+    fn focus_order_is_tree_order() {
+        let tree = layout(&row(vec![focusable(1), focusable(2), focusable(3)]));
+        assert_eq!(vec![FocusId(1), FocusId(2), FocusId(3)], focus_order(&tree));
+    }
+
+    #[test]
+    fn focus_order_visits_parent_before_children() {
+        let widget = Focus {
+            id: FocusId(1),
+            on_key: None,
+            child: Box::new(row(vec![focusable(2)])),
+        };
+        let tree = layout(&widget);
+        assert_eq!(vec![FocusId(1), FocusId(2)], focus_order(&tree));
+    }
+
+    #[test]
+    fn tab_focuses_first_widget_when_nothing_is_focused() {
+        let tree = layout(&row(vec![focusable(1), focusable(2)]));
+        let mut focus = FocusManager::new();
+        let status = focus.dispatch(&tree, &key_down("Tab", Modifiers::none()));
+        assert_eq!(EventStatus::Handled, status);
+        assert_eq!(Some(FocusId(1)), focus.focused());
+    }
+
+    #[test]
+    fn tab_moves_focus_forward_and_wraps() {
+        let tree = layout(&row(vec![focusable(1), focusable(2)]));
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(1));
+        let _ = focus.dispatch(&tree, &key_down("Tab", Modifiers::none()));
+        assert_eq!(Some(FocusId(2)), focus.focused());
+        let _ = focus.dispatch(&tree, &key_down("Tab", Modifiers::none()));
+        assert_eq!(Some(FocusId(1)), focus.focused());
+    }
+
+    #[test]
+    fn shift_tab_moves_focus_backward_and_wraps() {
+        let tree = layout(&row(vec![focusable(1), focusable(2)]));
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(2));
+        let _ = focus.dispatch(&tree, &key_down("Tab", Modifiers::shift()));
+        assert_eq!(Some(FocusId(1)), focus.focused());
+        let _ = focus.dispatch(&tree, &key_down("Tab", Modifiers::shift()));
+        assert_eq!(Some(FocusId(2)), focus.focused());
+    }
+
+    #[test]
+    fn focus_next_without_focusable_widgets() {
+        let tree = layout(&Container::default());
+        let mut focus = FocusManager::new();
+        assert_eq!(None, focus.focus_next(&tree));
+        assert_eq!(None, focus.focus_previous(&tree));
+    }
+
+    #[test]
+    fn tab_is_ignored_without_focusable_widgets() {
+        let tree = layout(&Container::default());
+        let mut focus = FocusManager::new();
+        let status = focus.dispatch(&tree, &key_down("Tab", Modifiers::none()));
+        assert_eq!(EventStatus::Ignored, status);
+        let status = focus.dispatch(&tree, &key_down("Tab", Modifiers::shift()));
+        assert_eq!(EventStatus::Ignored, status);
+    }
+
+    #[test]
+    fn focus_at_focuses_innermost_focusable_widget() {
+        let widget = Focus {
+            id: FocusId(1),
+            on_key: None,
+            child: Box::new(row(vec![sized_focusable(2, 10.0)])),
+        };
+        let tree = layout(&widget);
+        let mut focus = FocusManager::new();
+
+        assert_eq!(
+            Some(FocusId(2)),
+            focus.focus_at(&tree, Vector2::new(5.0, 5.0))
+        );
+        assert_eq!(
+            Some(FocusId(1)),
+            focus.focus_at(&tree, Vector2::new(5.0, 50.0))
+        );
+        assert_eq!(Some(FocusId(1)), focus.focused());
+    }
+
+    #[test]
+    fn focus_at_blurs_outside_focusable_widgets() {
+        let tree = layout(&row(vec![sized_focusable(1, 10.0)]));
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(1));
+
+        assert_eq!(None, focus.focus_at(&tree, Vector2::new(50.0, 50.0)));
+        assert_eq!(None, focus.focused());
+    }
+
+    #[test]
+    fn clones_share_focus() {
+        let focus = FocusManager::new();
+        let handle = focus.clone();
+        handle.focus(FocusId(3));
+        assert_eq!(Some(FocusId(3)), focus.focused());
+        focus.blur();
+        assert_eq!(None, handle.focused());
+    }
+
+    #[test]
+    fn widgets_can_read_and_move_focus_during_layout() {
+        let focus = FocusManager::new();
+        let mut elements = ElementTree::new();
+        elements.set_focus(focus.clone());
+        let widget = || FocusRing { id: FocusId(1) };
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+
+        let tree = LayoutTree::from_root_with_elements(&widget(), &constraints, &mut elements);
+        assert!(
+            !tree.to_string().contains("top 2 rgba(0, 0, 255, 255)"),
+            "{tree}"
+        );
+
+        // The handler the widget attached moves focus through the shared
+        // manager, which lays the widget out again with its focus ring.
+        let status = tree.dispatch_pointer_event(&PointerEvent::new(
+            PointerEventKind::Down,
+            0,
+            Vector2::new(5.0, 5.0),
+        ));
+        assert_eq!(EventStatus::Handled, status);
+        assert_eq!(Some(FocusId(1)), focus.focused());
+        let tree = LayoutTree::from_root_with_elements(&widget(), &constraints, &mut elements);
+        assert!(
+            tree.to_string().contains("top 2 rgba(0, 0, 255, 255)"),
+            "{tree}"
+        );
+    }
+
+    #[test]
+    fn key_events_bubble_from_focused_widget_to_ancestors() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = KeyListener {
+            on_key: recorder("outer", &calls, EventStatus::Ignored),
+            child: Box::new(row(vec![
+                Box::new(Focus {
+                    id: FocusId(1),
+                    on_key: Some(recorder("first", &calls, EventStatus::Ignored)),
+                    child: Box::new(Container::default()),
+                }),
+                Box::new(Focus {
+                    id: FocusId(2),
+                    on_key: Some(recorder("second", &calls, EventStatus::Ignored)),
+                    child: Box::new(Container::default()),
+                }),
+            ])),
+        };
+        let tree = layout(&widget);
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(2));
+
+        let status = focus.dispatch(&tree, &key_down("a", Modifiers::none()));
+        assert_eq!(EventStatus::Ignored, status);
+        assert_eq!(vec!["second", "outer"], *calls.borrow());
+    }
+
+    #[test]
+    fn handled_key_events_stop_bubbling() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = KeyListener {
+            on_key: recorder("outer", &calls, EventStatus::Ignored),
+            child: Box::new(Focus {
+                id: FocusId(1),
+                on_key: Some(recorder("inner", &calls, EventStatus::Handled)),
+                child: Box::new(Container::default()),
+            }),
+        };
+        let tree = layout(&widget);
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(1));
+
+        let status = focus.dispatch(&tree, &KeyboardEvent::TextInput("a".to_string()));
+        assert_eq!(EventStatus::Handled, status);
+        assert_eq!(vec!["inner"], *calls.borrow());
+    }
+
+    #[test]
+    fn handled_tab_does_not_move_focus() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = row(vec![
+            Box::new(Focus {
+                id: FocusId(1),
+                on_key: Some(recorder("first", &calls, EventStatus::Handled)),
+                child: Box::new(Container::default()),
+            }),
+            focusable(2),
+        ]);
+        let tree = layout(&widget);
+        let mut focus = FocusManager::new();
+        focus.focus(FocusId(1));
+
+        let _ = focus.dispatch(&tree, &key_down("Tab", Modifiers::none()));
+        assert_eq!(Some(FocusId(1)), focus.focused());
+    }
+
+    #[test]
+    fn key_events_without_focus_are_ignored() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = KeyListener {
+            on_key: recorder("outer", &calls, EventStatus::Handled),
+            child: Box::new(Container::default()),
+        };
+        let tree = layout(&widget);
+        let mut focus = FocusManager::new();
+
+        let status = focus.dispatch(&tree, &key_down("a", Modifiers::none()));
+        assert_eq!(EventStatus::Ignored, status);
+        assert!(calls.borrow().is_empty());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn layout(widget: &dyn Layout) -> LayoutTree {
+        LayoutTree::from_root(
+            widget,
+            &BoxConstraints::from_max(Vector2::new(100.0, 100.0)),
+        )
+    }
+
+    fn row(children: Vec<Box<dyn FlexLayout>>) -> Flex {
+        Flex {
+            children,
+            ..Flex::default()
+        }
+    }
+
+    fn focusable(id: u64) -> Box<dyn FlexLayout> {
+        Box::new(Focus {
+            id: FocusId(id),
+            on_key: None,
+            child: Box::new(Container::default()),
+        })
+    }
+
+    fn sized_focusable(id: u64, size: f32) -> Box<dyn FlexLayout> {
+        Box::new(Focus {
+            id: FocusId(id),
+            on_key: None,
+            child: Box::new(Container {
+                width: Some(size),
+                height: Some(size),
+                ..Container::default()
+            }),
+        })
+    }
+
+    fn recorder(
+        name: &'static str,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        status: EventStatus,
+    ) -> KeyHandler {
+        let calls = Rc::clone(calls);
+        KeyHandler::new(move |_| {
+            calls.borrow_mut().push(name);
+            status
+        })
+    }
+
+    fn key_down(key: &str, modifiers: Modifiers) -> KeyboardEvent {
+        KeyboardEvent::Key(KeyEvent::new(KeyEventKind::Down, key, modifiers))
+    }
+}
@@ -0,0 +1,1178 @@
+use crate::flex::CrossAxisAlignment;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+use std::ops::Range;
+
+/// One end of a `TrackSize::MinMax`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum TrackBreadth {
+    // Exactly this many pixels
+    Fixed(f32),
+    // A share of the space left over once the other tracks are sized. As a
+    // minimum this behaves like `Auto`, like CSS.
+    Fraction(f32),
+    // The size of the largest child in the track
+    Auto,
+}
+
+/// The size of a row or column of a `Grid`. These match the CSS grid track
+/// sizes `100px`, `1fr`, `auto` and `minmax(min, max)`.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub enum TrackSize {
+    // Exactly this many pixels
+    Fixed(f32),
+    // A share of the space left over once the other tracks are sized. Like
+    // CSS, it is never smaller than its children, so this is the same as
+    // `MinMax(Auto, Fraction(fraction))`.
+    Fraction(f32),
+    // The size of the largest child in the track
+    #[default]
+    Auto,
+    // At least the first size, and grows up to the second size when there is
+    // space left over
+    MinMax(TrackBreadth, TrackBreadth),
+}
+
+impl TrackSize {
+    fn min(self) -> TrackBreadth {
+        match self {
+            TrackSize::Fixed(size) => TrackBreadth::Fixed(size),
+            TrackSize::Fraction(_)
+            | TrackSize::Auto
+            | TrackSize::MinMax(TrackBreadth::Fraction(_), _) => TrackBreadth::Auto,
+            TrackSize::MinMax(min, _) => min,
+        }
+    }
+
+    fn max(self) -> TrackBreadth {
+        match self {
+            TrackSize::Fixed(size) => TrackBreadth::Fixed(size),
+            TrackSize::Fraction(fraction) => TrackBreadth::Fraction(fraction),
+            TrackSize::Auto => TrackBreadth::Auto,
+            TrackSize::MinMax(_, max) => max,
+        }
+    }
+
+    // Whether the size of the track depends on its children
+    fn is_intrinsic(self) -> bool {
+        self.min() == TrackBreadth::Auto || self.max() == TrackBreadth::Auto
+    }
+}
+
+/// A child of a `Grid`, and the cells it covers. Rows and columns are counted
+/// from zero.
+#[derive(Debug)]
+pub struct GridItem {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    /// Overrides `Grid::justify_items` for this child.
+    pub justify_self: Option<CrossAxisAlignment>,
+    /// Overrides `Grid::align_items` for this child.
+    pub align_self: Option<CrossAxisAlignment>,
+    pub child: Box<dyn Layout>,
+}
+
+impl GridItem {
+    #[must_use]
+    pub fn new(row: usize, column: usize, child: Box<dyn Layout>) -> GridItem {
+        GridItem {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            justify_self: None,
+            align_self: None,
+            child,
+        }
+    }
+
+    #[must_use]
+    pub fn span(self, row_span: usize, column_span: usize) -> GridItem {
+        GridItem {
+            row_span,
+            column_span,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn justify_self(self, alignment: CrossAxisAlignment) -> GridItem {
+        GridItem {
+            justify_self: Some(alignment),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn align_self(self, alignment: CrossAxisAlignment) -> GridItem {
+        GridItem {
+            align_self: Some(alignment),
+            ..self
+        }
+    }
+
+    fn rows(&self) -> Range<usize> {
+        self.row..self.row + self.row_span.max(1)
+    }
+
+    fn columns(&self) -> Range<usize> {
+        self.column..self.column + self.column_span.max(1)
+    }
+}
+
+/// Lays out its children in rows and columns like a CSS grid, so cells line up
+/// across both rows and columns.
+///
+/// Columns are sized before rows, so the height of a row comes from its
+/// children laid out at the width of their columns. Children placed past the
+/// last row or column add `Auto` tracks. `justify_items` aligns children
+/// horizontally inside their cells and `align_items` aligns them vertically.
+/// Both stretch children to fill their cells by default.
+#[derive(Debug)]
+pub struct Grid {
+    pub columns: Vec<TrackSize>,
+    pub rows: Vec<TrackSize>,
+    pub column_gap: f32,
+    pub row_gap: f32,
+    pub justify_items: CrossAxisAlignment,
+    pub align_items: CrossAxisAlignment,
+    pub children: Vec<GridItem>,
+}
+
+impl Default for Grid {
+    fn default() -> Grid {
+        Grid {
+            columns: vec![],
+            rows: vec![],
+            column_gap: 0.0,
+            row_gap: 0.0,
+            justify_items: CrossAxisAlignment::Stretch,
+            align_items: CrossAxisAlignment::Stretch,
+            children: vec![],
+        }
+    }
+}
+
+impl Layout for Grid {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let columns = implicit_tracks(&self.columns, self.children.iter().map(GridItem::columns));
+        let rows = implicit_tracks(&self.rows, self.children.iter().map(GridItem::rows));
+
+        // Children are only measured when they are in a track that is sized by
+        // its children
+        let mut contents = vec![];
+        for item in &self.children {
+            if item.columns().any(|i| columns[i].is_intrinsic()) {
+                let unbounded = BoxConstraints::from_max((f32::INFINITY, f32::INFINITY));
+                let size = tree.measure(item.child.as_ref(), &unbounded);
+                contents.push((item.columns(), size.x));
+            }
+        }
+        let column_sizes = size_tracks(&columns, &contents, self.column_gap, constraints.max.x);
+
+        let mut contents = vec![];
+        for item in &self.children {
+            if item.rows().any(|i| rows[i].is_intrinsic()) {
+                let width = span_size(&column_sizes, item.columns(), self.column_gap);
+                let width = cell_constraint(item.justify_self.unwrap_or(self.justify_items), width);
+                let constraints = BoxConstraints {
+                    min: Vector2::new(width.x, 0.0),
+                    max: Vector2::new(width.y, f32::INFINITY),
+                };
+                let size = tree.measure(item.child.as_ref(), &constraints);
+                contents.push((item.rows(), size.y));
+            }
+        }
+        let row_sizes = size_tracks(&rows, &contents, self.row_gap, constraints.max.y);
+
+        let mut children = vec![];
+        for item in &self.children {
+            let cell_size = Vector2::new(
+                span_size(&column_sizes, item.columns(), self.column_gap),
+                span_size(&row_sizes, item.rows(), self.row_gap),
+            );
+            let justify = item.justify_self.unwrap_or(self.justify_items);
+            let align = item.align_self.unwrap_or(self.align_items);
+            let width = cell_constraint(justify, cell_size.x);
+            let height = cell_constraint(align, cell_size.y);
+            let constraints = BoxConstraints {
+                min: Vector2::new(width.x, height.x),
+                max: Vector2::new(width.y, height.y),
+            };
+            let sbox = item.child.layout(tree, &constraints);
+            let offset = Vector2::new(
+                align_in_cell(justify, cell_size.x, sbox.size.x),
+                align_in_cell(align, cell_size.y, sbox.size.y),
+            );
+            let pos = Vector2::new(
+                span_size(&column_sizes, 0..item.column, self.column_gap),
+                span_size(&row_sizes, 0..item.row, self.row_gap),
+            );
+            let gap = Vector2::new(
+                if item.column > 0 {
+                    self.column_gap
+                } else {
+                    0.0
+                },
+                if item.row > 0 { self.row_gap } else { 0.0 },
+            );
+            let lbox = LayoutBox::from_child(sbox, pos + gap + offset);
+            children.push(tree.insert(lbox));
+        }
+
+        let size = Vector2::new(
+            span_size(&column_sizes, 0..column_sizes.len(), self.column_gap),
+            span_size(&row_sizes, 0..row_sizes.len(), self.row_gap),
+        );
+        SizedLayoutBox {
+            size: size.clamp_between(constraints.min, constraints.max),
+            children,
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+// The minimum and maximum size of a child along one axis of its cell.
+fn cell_constraint(alignment: CrossAxisAlignment, cell_size: f32) -> Vector2 {
+    match alignment {
+        CrossAxisAlignment::Stretch => Vector2::new(cell_size, cell_size),
+        _ => Vector2::new(0.0, cell_size),
+    }
+}
+
+// The offset of a child inside its cell along one axis.
+fn align_in_cell(alignment: CrossAxisAlignment, cell_size: f32, child_size: f32) -> f32 {
+    match alignment {
+        CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => 0.0,
+        CrossAxisAlignment::End => cell_size - child_size,
+        CrossAxisAlignment::Center => (cell_size - child_size) / 2.0,
+    }
+}
+
+// The tracks that were defined, followed by `Auto` tracks for children placed
+// past the end of them.
+fn implicit_tracks<I>(tracks: &[TrackSize], spans: I) -> Vec<TrackSize>
+where
+    I: Iterator<Item = Range<usize>>,
+{
+    let count = spans.map(|span| span.end).fold(tracks.len(), usize::max);
+    let mut tracks = tracks.to_vec();
+    tracks.resize(count, TrackSize::Auto);
+    tracks
+}
+
+// The size of a run of tracks, including the gaps between them.
+#[allow(clippy::cast_precision_loss)]
+fn span_size(sizes: &[f32], span: Range<usize>, gap: f32) -> f32 {
+    let count = span.len();
+    sizes[span].iter().sum::<f32>() + gap * count.saturating_sub(1) as f32
+}
+
+/// Resolve the size of each track along one axis of a grid, like the CSS grid
+/// track sizing algorithm. `contents` are the sizes of the children in the
+/// tracks that are sized by their children, with the tracks they span, and
+/// `available` is the size of the grid along this axis, which may be infinite.
+///
+/// A child that spans several tracks grows the tracks sized by their children
+/// equally, when it doesn't already fit. When there is space left over the
+/// `MinMax` tracks grow toward their maximum, and then the remaining space is
+/// shared between the `Fraction` tracks.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn size_tracks(
+    tracks: &[TrackSize],
+    contents: &[(Range<usize>, f32)],
+    gap: f32,
+    available: f32,
+) -> Vec<f32> {
+    let gaps = gap * tracks.len().saturating_sub(1) as f32;
+
+    // Children that span fewer tracks are placed first, so the tracks they
+    // grow are already counted when a wider child is placed.
+    let mut contents: Vec<&(Range<usize>, f32)> = contents.iter().collect();
+    contents.sort_by_key(|(span, _)| span.len());
+    let mut content = vec![0.0_f32; tracks.len()];
+    for (span, size) in contents {
+        let provided = |content: &[f32], i: usize| match tracks[i].min() {
+            TrackBreadth::Fixed(size) => size.max(content[i]),
+            _ => content[i],
+        };
+        let spanned: f32 = span.clone().map(|i| provided(&content, i)).sum::<f32>()
+            + gap * span.len().saturating_sub(1) as f32;
+        let growable: Vec<usize> = span.clone().filter(|i| tracks[*i].is_intrinsic()).collect();
+        if spanned >= *size || growable.is_empty() {
+            continue;
+        }
+        let share = (size - spanned) / growable.len() as f32;
+        for i in growable {
+            content[i] = provided(&content, i) + share;
+        }
+    }
+
+    let mut sizes: Vec<f32> = vec![];
+    let mut limits: Vec<f32> = vec![];
+    for (i, track) in tracks.iter().enumerate() {
+        let base = match track.min() {
+            TrackBreadth::Fixed(size) => size,
+            _ => content[i],
+        };
+        let limit = match track.max() {
+            TrackBreadth::Fixed(size) => size.max(base),
+            TrackBreadth::Auto => content[i].max(base),
+            TrackBreadth::Fraction(_) => base,
+        };
+        sizes.push(base);
+        limits.push(limit);
+    }
+
+    // Grow the tracks toward their limits, sharing the free space equally.
+    // Each pass either uses up the free space or takes a track to its limit.
+    if available.is_finite() {
+        let mut free = available - gaps - sizes.iter().sum::<f32>();
+        for _ in 0..tracks.len() {
+            let growable: Vec<usize> = (0..tracks.len())
+                .filter(|i| sizes[*i] < limits[*i])
+                .collect();
+            if free <= 0.0 || growable.is_empty() {
+                break;
+            }
+            let share = free / growable.len() as f32;
+            for i in growable {
+                let grow = share.min(limits[i] - sizes[i]);
+                sizes[i] += grow;
+                free -= grow;
+            }
+        }
+    }
+
+    // Share what is left between the fraction tracks. A track whose children
+    // are larger than its share keeps its size, and the others share the rest.
+    let fraction = |i: usize| match tracks[i].max() {
+        TrackBreadth::Fraction(fraction) => Some(fraction.max(0.0)),
+        _ => None,
+    };
+    let mut flexible: Vec<usize> = (0..tracks.len())
+        .filter(|i| fraction(*i).is_some())
+        .collect();
+    if flexible.is_empty() {
+        return sizes;
+    }
+    let size_per_fraction = if available.is_finite() {
+        loop {
+            let inflexible: f32 = (0..tracks.len())
+                .filter(|i| !flexible.contains(i))
+                .map(|i| sizes[i])
+                .sum();
+            let leftover = (available - gaps - inflexible).max(0.0);
+            // Like CSS, fractions that add up to less than one only take that
+            // part of the leftover space.
+            let fractions: f32 = flexible.iter().filter_map(|i| fraction(*i)).sum();
+            let size_per_fraction = leftover / fractions.max(1.0);
+            let fits = |i: &usize| fraction(*i).unwrap_or(0.0) * size_per_fraction >= sizes[*i];
+            if flexible.iter().all(fits) {
+                break size_per_fraction;
+            }
+            flexible.retain(fits);
+        }
+    } else {
+        // Without a limit, the fraction tracks are as small as they can be
+        // while still fitting their children.
+        flexible
+            .iter()
+            .filter_map(|i| {
+                fraction(*i)
+                    .filter(|f| *f > 0.0)
+                    .map(|f| sizes[*i] / f.max(1.0))
+            })
+            .fold(0.0, f32::max)
+    };
+    for i in flexible {
+        sizes[i] = sizes[i].max(fraction(i).unwrap_or(0.0) * size_per_fraction);
+    }
+    sizes
+}
+
+/// Like `flex.rs`, each feature has its own section. The track sizing tests
+/// call `size_tracks` directly, and the layout tests compare snapshots of the
+/// laid out grid.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animated_container::AnimatedContainer;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use crate::element::{ElementTree, State, StatefulWidget};
+    use crate::reactive::{Reactive, Signal};
+    use crate::scroll::{ScrollController, ScrollView};
+    use crate::wrap::Wrap;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_util::{assert_slice_eq, assert_snapshot_eq};
+
+    // --------------------------------------------------
+    // Fixed tracks
+    // --------------------------------------------------
+
+    #[test]
+    fn fixed_tracks_ignore_their_children() {
+        let tracks = [TrackSize::Fixed(10.0), TrackSize::Fixed(20.0)];
+        let sizes = size_tracks(&tracks, &[(0..1, 50.0)], 0.0, 100.0);
+        assert_slice_eq(&[10.0, 20.0], &sizes);
+    }
+
+    #[test]
+    fn fixed_tracks_ignore_available_space() {
+        let tracks = [TrackSize::Fixed(60.0), TrackSize::Fixed(60.0)];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[60.0, 60.0], &sizes);
+    }
+
+    #[test]
+    fn grid_places_children_in_their_cells() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(20.0), TrackSize::Fixed(30.0)],
+            rows: vec![TrackSize::Fixed(10.0), TrackSize::Fixed(15.0)],
+            children: four_cells(),
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 25]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [20, 0, 50, 10] fill rgba(0, 255, 0, 255)
+              [0, 10, 20, 25] fill rgba(0, 0, 255, 255)
+              [20, 10, 50, 25] fill rgba(255, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Fraction tracks
+    // --------------------------------------------------
+
+    #[test]
+    fn fractions_share_available_space() {
+        let tracks = [TrackSize::Fraction(1.0), TrackSize::Fraction(3.0)];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[25.0, 75.0], &sizes);
+    }
+
+    #[test]
+    fn fractions_share_space_left_by_other_tracks() {
+        let tracks = [
+            TrackSize::Fixed(20.0),
+            TrackSize::Fraction(1.0),
+            TrackSize::Fraction(1.0),
+        ];
+        let sizes = size_tracks(&tracks, &[], 10.0, 100.0);
+        assert_slice_eq(&[20.0, 30.0, 30.0], &sizes);
+    }
+
+    #[test]
+    fn fraction_is_never_smaller_than_its_children() {
+        let tracks = [TrackSize::Fraction(1.0), TrackSize::Fraction(1.0)];
+        let sizes = size_tracks(&tracks, &[(0..1, 70.0)], 0.0, 100.0);
+        assert_slice_eq(&[70.0, 30.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_zero_fraction_can_be_smaller_than_its_children() {
+        let track = TrackSize::MinMax(TrackBreadth::Fixed(0.0), TrackBreadth::Fraction(1.0));
+        let sizes = size_tracks(&[track, track], &[(0..1, 70.0)], 0.0, 100.0);
+        assert_slice_eq(&[50.0, 50.0], &sizes);
+    }
+
+    #[test]
+    fn fractions_with_unbounded_space_fit_their_children() {
+        let tracks = [TrackSize::Fraction(1.0), TrackSize::Fraction(2.0)];
+        let contents = [(0..1, 30.0), (1..2, 20.0)];
+        let sizes = size_tracks(&tracks, &contents, 0.0, f32::INFINITY);
+        assert_slice_eq(&[30.0, 60.0], &sizes);
+    }
+
+    #[test]
+    fn fractions_that_add_up_to_less_than_one_leave_space() {
+        let tracks = [TrackSize::Fraction(0.25), TrackSize::Fraction(0.25)];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[25.0, 25.0], &sizes);
+    }
+
+    #[test]
+    fn fraction_columns_fill_the_grid() {
+        let grid = Grid {
+            columns: vec![
+                TrackSize::Fixed(20.0),
+                TrackSize::Fraction(1.0),
+                TrackSize::Fraction(2.0),
+            ],
+            rows: vec![TrackSize::Fixed(10.0)],
+            children: vec![
+                GridItem::new(0, 0, cell(Color::red())),
+                GridItem::new(0, 1, cell(Color::green())),
+                GridItem::new(0, 2, cell(Color::blue())),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 110, 10]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [20, 0, 50, 10] fill rgba(0, 255, 0, 255)
+              [50, 0, 110, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&grid, Vector2::new(110.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn fraction_rows_fill_the_grid() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(10.0)],
+            rows: vec![TrackSize::Fraction(1.0), TrackSize::Fraction(1.0)],
+            row_gap: 4.0,
+            children: vec![
+                GridItem::new(0, 0, cell(Color::red())),
+                GridItem::new(1, 0, cell(Color::green())),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 10, 40]
+              [0, 0, 10, 18] fill rgba(255, 0, 0, 255)
+              [0, 22, 10, 40] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 40.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Auto tracks
+    // --------------------------------------------------
+
+    #[test]
+    fn auto_track_fits_its_largest_child() {
+        let tracks = [TrackSize::Auto, TrackSize::Auto];
+        let contents = [(0..1, 10.0), (0..1, 30.0), (1..2, 5.0)];
+        let sizes = size_tracks(&tracks, &contents, 0.0, 100.0);
+        assert_slice_eq(&[30.0, 5.0], &sizes);
+    }
+
+    #[test]
+    fn auto_track_without_children_is_empty() {
+        let tracks = [TrackSize::Auto, TrackSize::Fixed(10.0)];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[0.0, 10.0], &sizes);
+    }
+
+    #[test]
+    fn auto_columns_fit_their_children() {
+        let grid = Grid {
+            columns: vec![TrackSize::Auto, TrackSize::Auto],
+            rows: vec![TrackSize::Fixed(10.0)],
+            children: vec![
+                GridItem::new(0, 0, sized(15.0, 5.0)),
+                GridItem::new(0, 1, sized(25.0, 5.0)),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 10]
+              [0, 0, 15, 10] fill rgba(255, 0, 0, 255)
+              [15, 0, 40, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn auto_rows_fit_children_at_their_column_width() {
+        // The wrap only fits one chip on each run at the width of the column,
+        // so the row is as tall as two chips.
+        let chips = Wrap {
+            children: vec![sized(20.0, 10.0), sized(20.0, 10.0)],
+            ..Wrap::default()
+        };
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(30.0)],
+            rows: vec![TrackSize::Auto],
+            children: vec![GridItem::new(0, 0, Box::new(chips))],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 30, 20]
+              [0, 0, 30, 20]
+                [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+                [0, 10, 20, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn stateful_child_in_auto_track_is_built_once_per_frame() {
+        let builds = Rc::new(Cell::new(0));
+        let grid = Grid {
+            columns: vec![TrackSize::Auto],
+            rows: vec![TrackSize::Auto],
+            children: vec![GridItem::new(0, 0, Box::new(Built(Rc::clone(&builds))))],
+            ..Grid::default()
+        };
+        let mut elements = ElementTree::new();
+        for time in [0.0, 100.0] {
+            let _ = frame(&grid, time, &mut elements);
+        }
+        assert_eq!(2, builds.get());
+    }
+
+    #[test]
+    fn animated_child_in_auto_track_animates_once_per_frame() {
+        let width = Signal::new(10.0);
+        let layouts = Rc::new(Cell::new(0));
+        let grid = Grid {
+            columns: vec![TrackSize::Auto],
+            rows: vec![TrackSize::Fixed(10.0)],
+            children: vec![GridItem::new(0, 0, animated(&width, &layouts))],
+            ..Grid::default()
+        };
+        let mut elements = ElementTree::new();
+        let _ = frame(&grid, 0.0, &mut elements);
+        width.set(30.0);
+        let widths: Vec<f32> = [100.0, 150.0, 200.0]
+            .into_iter()
+            .map(|time| frame(&grid, time, &mut elements).x)
+            .collect();
+        assert_eq!(vec![10.0, 20.0, 30.0], widths);
+        assert!(!elements.is_animating());
+
+        // The layout is reused from the last frame, so the child is only laid
+        // out again to be measured
+        layouts.set(0);
+        let _ = frame(&grid, 250.0, &mut elements);
+        assert_eq!(1, layouts.get());
+    }
+
+    #[test]
+    fn scrolled_view_in_auto_row_keeps_its_offset() {
+        // The row is measured with an unbounded height, but only gets 100
+        // pixels of the 300 pixel tall content, so the view can scroll.
+        let controller = ScrollController::new();
+        controller.set_offset(80.0);
+        let view = ScrollView::new(sized(50.0, 300.0)).controller(controller.clone());
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(50.0)],
+            rows: vec![TrackSize::MinMax(
+                TrackBreadth::Fixed(40.0),
+                TrackBreadth::Auto,
+            )],
+            children: vec![GridItem::new(0, 0, Box::new(view))],
+            ..Grid::default()
+        };
+        let mut elements = ElementTree::new();
+        for time in [0.0, 16.0] {
+            let _ = frame(&grid, time, &mut elements);
+            assert!((controller.offset() - 80.0).abs() < 1e-6);
+        }
+    }
+
+    // --------------------------------------------------
+    // MinMax tracks
+    // --------------------------------------------------
+
+    #[test]
+    fn minmax_grows_to_its_maximum_when_there_is_space() {
+        let track = TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fixed(40.0));
+        let sizes = size_tracks(&[track], &[], 0.0, 100.0);
+        assert_slice_eq(&[40.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_tracks_share_space_up_to_their_maximum() {
+        let tracks = [
+            TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fixed(20.0)),
+            TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fixed(100.0)),
+        ];
+        let sizes = size_tracks(&tracks, &[], 0.0, 70.0);
+        assert_slice_eq(&[20.0, 50.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_keeps_its_minimum_without_space() {
+        let tracks = [
+            TrackSize::MinMax(TrackBreadth::Fixed(30.0), TrackBreadth::Fixed(60.0)),
+            TrackSize::Fixed(80.0),
+        ];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[30.0, 80.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_auto_maximum_grows_to_fit_children() {
+        let track = TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Auto);
+        let contents = [(0..1, 50.0)];
+        assert_slice_eq(&[50.0], &size_tracks(&[track], &contents, 0.0, 100.0));
+        assert_slice_eq(&[30.0], &size_tracks(&[track], &contents, 0.0, 30.0));
+    }
+
+    #[test]
+    fn minmax_auto_minimum_fits_children() {
+        let track = TrackSize::MinMax(TrackBreadth::Auto, TrackBreadth::Fixed(20.0));
+        let sizes = size_tracks(&[track], &[(0..1, 50.0)], 0.0, 30.0);
+        assert_slice_eq(&[50.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_grows_before_fractions() {
+        let tracks = [
+            TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fixed(30.0)),
+            TrackSize::Fraction(1.0),
+        ];
+        let sizes = size_tracks(&tracks, &[], 0.0, 100.0);
+        assert_slice_eq(&[30.0, 70.0], &sizes);
+    }
+
+    #[test]
+    fn minmax_columns_in_grid() {
+        let grid = Grid {
+            columns: vec![
+                TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fixed(30.0)),
+                TrackSize::MinMax(TrackBreadth::Fixed(10.0), TrackBreadth::Fraction(1.0)),
+            ],
+            rows: vec![TrackSize::Fixed(10.0)],
+            children: vec![
+                GridItem::new(0, 0, cell(Color::red())),
+                GridItem::new(0, 1, cell(Color::green())),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 80, 10]
+              [0, 0, 30, 10] fill rgba(255, 0, 0, 255)
+              [30, 0, 80, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(80.0, 100.0)),
+        );
+        assert_snapshot_eq(
+            "
+            [0, 0, 15, 10]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [10, 0, 20, 10] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(15.0, 100.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Spans
+    // --------------------------------------------------
+
+    #[test]
+    fn spanning_child_grows_auto_tracks_equally() {
+        let tracks = [TrackSize::Auto, TrackSize::Auto];
+        let contents = [(0..2, 40.0), (0..1, 10.0)];
+        let sizes = size_tracks(&tracks, &contents, 4.0, 100.0);
+        assert_slice_eq(&[23.0, 13.0], &sizes);
+    }
+
+    #[test]
+    fn spanning_child_that_fits_doesnt_grow_tracks() {
+        let tracks = [TrackSize::Auto, TrackSize::Auto];
+        let contents = [(0..1, 20.0), (1..2, 20.0), (0..2, 30.0)];
+        let sizes = size_tracks(&tracks, &contents, 0.0, 100.0);
+        assert_slice_eq(&[20.0, 20.0], &sizes);
+    }
+
+    #[test]
+    fn spanning_child_only_grows_auto_tracks() {
+        let tracks = [TrackSize::Fixed(10.0), TrackSize::Auto];
+        let sizes = size_tracks(&tracks, &[(0..2, 50.0)], 0.0, 100.0);
+        assert_slice_eq(&[10.0, 40.0], &sizes);
+    }
+
+    #[test]
+    fn children_span_rows_and_columns() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(10.0); 3],
+            rows: vec![TrackSize::Fixed(10.0); 2],
+            column_gap: 2.0,
+            row_gap: 2.0,
+            children: vec![
+                GridItem::new(0, 0, cell(Color::red())).span(2, 1),
+                GridItem::new(0, 1, cell(Color::green())).span(1, 2),
+                GridItem::new(1, 1, cell(Color::blue())),
+                GridItem::new(1, 2, cell(Color::yellow())),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 34, 22]
+              [0, 0, 10, 22] fill rgba(255, 0, 0, 255)
+              [12, 0, 34, 10] fill rgba(0, 255, 0, 255)
+              [12, 12, 22, 22] fill rgba(0, 0, 255, 255)
+              [24, 12, 34, 22] fill rgba(255, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn spanning_child_grows_auto_rows() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(10.0); 2],
+            rows: vec![TrackSize::Auto; 2],
+            children: vec![
+                GridItem::new(0, 0, sized(10.0, 10.0)),
+                GridItem::new(0, 1, sized(10.0, 30.0)).span(2, 1),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 30]
+              [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+              [10, 0, 20, 30] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Gaps
+    // --------------------------------------------------
+
+    #[test]
+    fn gaps_are_taken_from_available_space() {
+        let tracks = [TrackSize::Fraction(1.0); 3];
+        let sizes = size_tracks(&tracks, &[], 5.0, 100.0);
+        assert_slice_eq(&[30.0, 30.0, 30.0], &sizes);
+    }
+
+    #[test]
+    fn gap_counts_toward_spanning_child() {
+        let tracks = [TrackSize::Auto, TrackSize::Auto];
+        let contents = [(0..1, 10.0), (1..2, 10.0), (0..2, 25.0)];
+        let sizes = size_tracks(&tracks, &contents, 5.0, 100.0);
+        assert_slice_eq(&[10.0, 10.0], &sizes);
+    }
+
+    #[test]
+    fn gaps_separate_rows_and_columns() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(20.0), TrackSize::Fixed(30.0)],
+            rows: vec![TrackSize::Fixed(10.0), TrackSize::Fixed(15.0)],
+            column_gap: 4.0,
+            row_gap: 2.0,
+            children: four_cells(),
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 54, 27]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [24, 0, 54, 10] fill rgba(0, 255, 0, 255)
+              [0, 12, 20, 27] fill rgba(0, 0, 255, 255)
+              [24, 12, 54, 27] fill rgba(255, 255, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Alignment
+    // --------------------------------------------------
+
+    #[test]
+    fn children_stretch_to_fill_their_cells_by_default() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [0, 0, 40, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(
+                CrossAxisAlignment::Stretch,
+                CrossAxisAlignment::Stretch,
+                None,
+            ),
+        );
+    }
+
+    #[test]
+    fn justify_items_start() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(CrossAxisAlignment::Start, CrossAxisAlignment::Stretch, None),
+        );
+    }
+
+    #[test]
+    fn justify_items_end() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [30, 0, 40, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(CrossAxisAlignment::End, CrossAxisAlignment::Stretch, None),
+        );
+    }
+
+    #[test]
+    fn justify_items_center() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [15, 0, 25, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(
+                CrossAxisAlignment::Center,
+                CrossAxisAlignment::Stretch,
+                None,
+            ),
+        );
+    }
+
+    #[test]
+    fn align_items_start() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [0, 0, 40, 6] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(CrossAxisAlignment::Stretch, CrossAxisAlignment::Start, None),
+        );
+    }
+
+    #[test]
+    fn align_items_end() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [0, 14, 40, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(CrossAxisAlignment::Stretch, CrossAxisAlignment::End, None),
+        );
+    }
+
+    #[test]
+    fn align_items_center() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [0, 7, 40, 13] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(
+                CrossAxisAlignment::Stretch,
+                CrossAxisAlignment::Center,
+                None,
+            ),
+        );
+    }
+
+    #[test]
+    fn item_alignment_overrides_grid_alignment() {
+        let overrides = (CrossAxisAlignment::End, CrossAxisAlignment::Center);
+        assert_snapshot_eq(
+            "
+            [0, 0, 40, 20]
+              [30, 7, 40, 13] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned(
+                CrossAxisAlignment::Start,
+                CrossAxisAlignment::Start,
+                Some(overrides),
+            ),
+        );
+    }
+
+    // --------------------------------------------------
+    // Grid size
+    // --------------------------------------------------
+
+    #[test]
+    fn children_past_the_last_track_add_auto_tracks() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(10.0)],
+            children: vec![
+                GridItem::new(0, 0, sized(10.0, 10.0)),
+                GridItem::new(2, 1, sized(20.0, 5.0)),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 30, 15]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [10, 10, 30, 15] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn unbounded_grid_is_sized_by_its_children() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fraction(1.0), TrackSize::Fraction(1.0)],
+            rows: vec![TrackSize::Auto],
+            children: vec![
+                GridItem::new(0, 0, sized(10.0, 10.0)),
+                GridItem::new(0, 1, sized(30.0, 5.0)),
+            ],
+            ..Grid::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 60, 10]
+              [0, 0, 30, 10] fill rgba(255, 0, 0, 255)
+              [30, 0, 60, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&grid, Vector2::new(f32::INFINITY, f32::INFINITY)),
+        );
+    }
+
+    #[test]
+    fn grid_is_at_least_its_minimum_constraints() {
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(10.0)],
+            rows: vec![TrackSize::Fixed(10.0)],
+            children: vec![GridItem::new(0, 0, cell(Color::red()))],
+            ..Grid::default()
+        };
+        let constraints = BoxConstraints {
+            min: Vector2::new(50.0, 40.0),
+            max: Vector2::new(100.0, 100.0),
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 40]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &LayoutTree::from_root(&grid, &constraints).to_string(),
+        );
+    }
+
+    #[test]
+    fn empty_grid_is_empty() {
+        assert_snapshot_eq(
+            "[0, 0, 0, 0]",
+            &dump(&Grid::default(), Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn dump(widget: &dyn Layout, max: Vector2) -> String {
+        LayoutTree::from_root(widget, &BoxConstraints::from_max(max)).to_string()
+    }
+
+    // A 10x6 child in a 40x20 cell
+    fn aligned(
+        justify_items: CrossAxisAlignment,
+        align_items: CrossAxisAlignment,
+        overrides: Option<(CrossAxisAlignment, CrossAxisAlignment)>,
+    ) -> String {
+        let mut item = GridItem::new(0, 0, sized(10.0, 6.0));
+        if let Some((justify, align)) = overrides {
+            item = item.justify_self(justify).align_self(align);
+        }
+        let grid = Grid {
+            columns: vec![TrackSize::Fixed(40.0)],
+            rows: vec![TrackSize::Fixed(20.0)],
+            justify_items,
+            align_items,
+            children: vec![item],
+            ..Grid::default()
+        };
+        dump(&grid, Vector2::new(100.0, 100.0))
+    }
+
+    fn four_cells() -> Vec<GridItem> {
+        vec![
+            GridItem::new(0, 0, cell(Color::red())),
+            GridItem::new(0, 1, cell(Color::green())),
+            GridItem::new(1, 0, cell(Color::blue())),
+            GridItem::new(1, 1, cell(Color::yellow())),
+        ]
+    }
+
+    // A stateful widget that counts how many times it is built
+    #[derive(Debug)]
+    struct Built(Rc<Cell<u32>>);
+
+    impl StatefulWidget for Built {
+        type State = ();
+
+        fn create_state(&self) {}
+
+        fn build(&self, _: &State<()>) -> Box<dyn Layout> {
+            self.0.set(self.0.get() + 1);
+            sized(15.0, 5.0)
+        }
+    }
+
+    // Counts how many times its child is laid out
+    #[derive(Debug)]
+    struct Counted {
+        layouts: Rc<Cell<u32>>,
+        child: Box<dyn Layout>,
+    }
+
+    impl Layout for Counted {
+        fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+            self.layouts.set(self.layouts.get() + 1);
+            self.child.layout(tree, constraints)
+        }
+    }
+
+    // A 10 pixel tall container in a `Reactive`, which animates to `width`
+    // over 100 milliseconds
+    fn animated(width: &Signal<f32>, layouts: &Rc<Cell<u32>>) -> Box<dyn Layout> {
+        let (width, layouts) = (width.clone(), Rc::clone(layouts));
+        Box::new(Reactive::new(move || {
+            Box::new(Counted {
+                layouts: Rc::clone(&layouts),
+                child: Box::new(AnimatedContainer {
+                    duration: 100.0,
+                    width: Some(width.get()),
+                    height: Some(10.0),
+                    ..AnimatedContainer::default()
+                }),
+            })
+        }))
+    }
+
+    // Lay out a frame at `time`, and return the size of the root
+    fn frame(widget: &dyn Layout, time: f32, elements: &mut ElementTree) -> Vector2 {
+        elements.set_time(time);
+        let constraints = BoxConstraints::from_max((100.0, 100.0));
+        let tree = LayoutTree::from_root_with_elements(widget, &constraints, elements);
+        tree.get(tree.root.unwrap()).unwrap().bounds.size()
+    }
+
+    // A child that fills whatever it is given
+    fn cell(color: Color) -> Box<dyn Layout> {
+        Box::new(Container {
+            color,
+            ..Container::default()
+        })
+    }
+
+    fn sized(width: f32, height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            width: Some(width),
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+}
@@ -0,0 +1,479 @@
+use crate::event::EventStatus;
+use crate::pointer::{PointerEvent, PointerEventKind, PointerHandler};
+use crate::tree::{LayoutBoxId, LayoutTree};
+use math::{Rect, Vector2};
+use std::collections::HashMap;
+
+/// A box that contains the point being tested.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct HitTestEntry {
+    pub id: LayoutBoxId,
+    /// The absolute bounds of the box, excluding its margins.
+    pub bounds: Rect,
+}
+
+impl LayoutTree {
+    /// Find the boxes under `point`, ordered from the deepest box up to the
+    /// root. This is the order events bubble in.
+    ///
+    /// Children are tested before their parent, and later children before
+    /// earlier ones because they are painted on top. Only the topmost branch is
+    /// returned, so overlapping siblings are never both hit. A parent is part
+    /// of the path whenever one of its children is hit, even if the child
+    /// overflows the parent, unless the parent clips its children.
+    #[must_use]
+    pub fn hit_test(&self, point: Vector2) -> Vec<HitTestEntry> {
+        let mut path = vec![];
+        if let Some(root) = self.root {
+            self.hit_test_box(root, Vector2::zero(), point, &mut path);
+        }
+        path
+    }
+
+    fn hit_test_box(
+        &self,
+        id: LayoutBoxId,
+        parent_offset: Vector2,
+        point: Vector2,
+        path: &mut Vec<HitTestEntry>,
+    ) -> bool {
+        let Some(lbox) = self.get(id) else {
+            return false;
+        };
+        let bounds = Rect::new(
+            lbox.bounds.min + parent_offset + lbox.margin.min(),
+            lbox.bounds.max + parent_offset - lbox.margin.max(),
+        );
+        let offset = lbox.bounds.min + parent_offset;
+        let clipped = lbox
+            .clip
+            .is_some_and(|clip| !lbox.clip_radius.contains(clip.translate(offset), point));
+        let child_hit = !clipped
+            && lbox
+                .children
+                .iter()
+                .rev()
+                .any(|child| self.hit_test_box(*child, offset, point, path));
+        if child_hit || bounds.intersects(point) {
+            path.push(HitTestEntry { id, bounds });
+            return true;
+        }
+        false
+    }
+
+    /// Deliver a pointer event to the handlers of the boxes under it. The
+    /// event starts at the deepest box and bubbles up towards the root until a
+    /// handler returns `EventStatus::Handled`.
+    #[must_use]
+    pub fn dispatch_pointer_event(&self, event: &PointerEvent) -> EventStatus {
+        dispatch_to(&self.pointer_targets(event.position), event)
+    }
+
+    // The handlers of the boxes under `point`, in the order events bubble in.
+    fn pointer_targets(&self, point: Vector2) -> Vec<PointerTarget> {
+        self.hit_test(point)
+            .into_iter()
+            .filter_map(|entry| {
+                let lbox = self.get(entry.id)?;
+                let handler = lbox.pointer_handler.clone()?;
+                Some(PointerTarget {
+                    handler,
+                    bounds: entry.bounds,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Delivers pointer events like `LayoutTree::dispatch_pointer_event`, but
+/// captures each pointer while it is pressed.
+///
+/// The handlers under a pointer when it goes down receive every `Move`, `Up`
+/// and `Cancel` event of that pointer until it is released, even when it has
+/// left their boxes, so a drag that ends outside of the widget that started it
+/// still finishes. Other events are hit-tested as usual. Like `FocusManager`,
+/// it is kept for the lifetime of the app, and the captured handlers stay the
+/// same while new trees are laid out. Their local positions are relative to
+/// where their boxes were when the pointer went down.
+#[derive(Default, Debug)]
+pub struct PointerRouter {
+    captured: HashMap<i32, Vec<PointerTarget>>,
+}
+
+#[derive(Clone, Debug)]
+struct PointerTarget {
+    handler: PointerHandler,
+    bounds: Rect,
+}
+
+impl PointerRouter {
+    #[must_use]
+    pub fn new() -> PointerRouter {
+        PointerRouter::default()
+    }
+
+    /// Whether `pointer_id` is pressed, so its events go to the handlers that
+    /// it went down on.
+    #[must_use]
+    pub fn is_captured(&self, pointer_id: i32) -> bool {
+        self.captured.contains_key(&pointer_id)
+    }
+
+    /// Send a pointer event to the handlers that captured its pointer, or to
+    /// the handlers under it in `tree` when it isn't captured.
+    pub fn dispatch(&mut self, tree: &LayoutTree, event: &PointerEvent) -> EventStatus {
+        match event.kind {
+            PointerEventKind::Down => {
+                let targets = tree.pointer_targets(event.position);
+                let status = dispatch_to(&targets, event);
+                self.captured.insert(event.pointer_id, targets);
+                status
+            }
+            PointerEventKind::Move => match self.captured.get(&event.pointer_id) {
+                Some(targets) => dispatch_to(targets, event),
+                None => tree.dispatch_pointer_event(event),
+            },
+            PointerEventKind::Up | PointerEventKind::Cancel => {
+                match self.captured.remove(&event.pointer_id) {
+                    Some(targets) => dispatch_to(&targets, event),
+                    None => tree.dispatch_pointer_event(event),
+                }
+            }
+            PointerEventKind::Wheel => tree.dispatch_pointer_event(event),
+        }
+    }
+}
+
+// Bubble the event through `targets` until a handler returns
+// `EventStatus::Handled`.
+fn dispatch_to(targets: &[PointerTarget], event: &PointerEvent) -> EventStatus {
+    for target in targets {
+        let event = PointerEvent {
+            local_position: event.position - target.bounds.min,
+            ..*event
+        };
+        if target.handler.handle(&event) == EventStatus::Handled {
+            return EventStatus::Handled;
+        }
+    }
+    EventStatus::Ignored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::EdgeInsets;
+    use crate::container::Container;
+    use crate::flex::{Axis, Flex};
+    use crate::pointer::Listener;
+    use crate::tree::{BoxConstraints, Layout, LayoutBox};
+    use crate::widget::{Positioned, Stack};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn hit_test_returns_path_from_deepest_box_to_root() {
+        let tree = nested_tree();
+        let ids: Vec<LayoutBoxId> = tree
+            .hit_test(Vector2::new(15.0, 15.0))
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(vec![0, 1, 2], ids);
+    }
+
+    #[test]
+    fn hit_test_uses_absolute_bounds() {
+        let tree = nested_tree();
+        let path = tree.hit_test(Vector2::new(15.0, 15.0));
+        assert_eq!(Rect::from_pos((11.0, 11.0), (10.0, 10.0)), path[0].bounds);
+    }
+
+    #[test]
+    fn hit_test_skips_child_that_does_not_contain_point() {
+        let tree = nested_tree();
+        let ids: Vec<LayoutBoxId> = tree
+            .hit_test(Vector2::new(40.0, 40.0))
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(vec![1, 2], ids);
+    }
+
+    #[test]
+    fn hit_test_outside_root_is_empty() {
+        let tree = nested_tree();
+        assert!(tree.hit_test(Vector2::new(500.0, 500.0)).is_empty());
+    }
+
+    #[test]
+    fn hit_test_excludes_margin() {
+        let container = Container {
+            width: Some(10.0),
+            height: Some(10.0),
+            margin: EdgeInsets::all(10.0),
+            ..Container::default()
+        };
+        let tree = layout(&container);
+        assert!(tree.hit_test(Vector2::new(5.0, 5.0)).is_empty());
+        assert_eq!(1, tree.hit_test(Vector2::new(15.0, 15.0)).len());
+    }
+
+    #[test]
+    fn hit_test_prefers_last_overlapping_sibling() {
+        let mut tree = LayoutTree::new();
+        let first = tree.insert(lbox(Rect::from_size((10.0, 10.0)), vec![]));
+        let second = tree.insert(lbox(Rect::from_size((10.0, 10.0)), vec![]));
+        let root = tree.insert(lbox(Rect::from_size((10.0, 10.0)), vec![first, second]));
+        tree.set_root(Some(root));
+
+        let ids: Vec<LayoutBoxId> = tree
+            .hit_test(Vector2::new(5.0, 5.0))
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(vec![second, root], ids);
+    }
+
+    #[test]
+    fn hit_test_skips_children_outside_clipping_parent() {
+        let mut tree = LayoutTree::new();
+        let leaf = tree.insert(lbox(Rect::from_pos((5.0, 5.0), (20.0, 20.0)), vec![]));
+        let clip = tree.insert(LayoutBox {
+            clip: Some(Rect::from_size((10.0, 10.0))),
+            ..lbox(Rect::from_size((10.0, 10.0)), vec![leaf])
+        });
+        let root = tree.insert(lbox(Rect::from_size((100.0, 100.0)), vec![clip]));
+        tree.set_root(Some(root));
+
+        let ids = |x, y| -> Vec<LayoutBoxId> {
+            tree.hit_test(Vector2::new(x, y))
+                .iter()
+                .map(|entry| entry.id)
+                .collect()
+        };
+        assert_eq!(vec![leaf, clip, root], ids(8.0, 8.0));
+        assert_eq!(vec![root], ids(20.0, 20.0));
+    }
+
+    #[test]
+    fn dispatch_bubbles_from_deepest_handler_to_root() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = listener(
+            "outer",
+            &calls,
+            EventStatus::Ignored,
+            listener("inner", &calls, EventStatus::Ignored, sized(20.0)),
+        );
+        let tree = layout(&widget);
+
+        let status = tree.dispatch_pointer_event(&down(5.0, 5.0));
+        assert_eq!(EventStatus::Ignored, status);
+        assert_eq!(vec!["inner", "outer"], *calls.borrow());
+    }
+
+    #[test]
+    fn dispatch_stops_bubbling_when_handled() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = listener(
+            "outer",
+            &calls,
+            EventStatus::Ignored,
+            listener("inner", &calls, EventStatus::Handled, sized(20.0)),
+        );
+        let tree = layout(&widget);
+
+        let status = tree.dispatch_pointer_event(&down(5.0, 5.0));
+        assert_eq!(EventStatus::Handled, status);
+        assert_eq!(vec!["inner"], *calls.borrow());
+    }
+
+    #[test]
+    fn dispatch_misses_handlers_outside_point() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = listener("inner", &calls, EventStatus::Handled, sized(20.0));
+        let tree = layout(&widget);
+
+        let status = tree.dispatch_pointer_event(&down(50.0, 50.0));
+        assert_eq!(EventStatus::Ignored, status);
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn dispatch_sets_local_position() {
+        let local = Rc::new(RefCell::new(None));
+        let recorded = Rc::clone(&local);
+        let widget = Stack {
+            children: vec![Positioned {
+                position: Vector2::new(30.0, 40.0),
+                child: Box::new(Listener {
+                    on_pointer: PointerHandler::new(move |event| {
+                        *recorded.borrow_mut() = Some(event.local_position);
+                        EventStatus::Handled
+                    }),
+                    child: Box::new(sized(20.0)),
+                }),
+            }],
+        };
+        let tree = layout(&widget);
+
+        let _ = tree.dispatch_pointer_event(&down(35.0, 50.0));
+        assert_eq!(Some(Vector2::new(5.0, 10.0)), *local.borrow());
+    }
+
+    #[test]
+    fn router_sends_events_of_pressed_pointer_to_its_down_target() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = listener("inner", &calls, EventStatus::Ignored, sized(20.0));
+        let tree = layout(&widget);
+        let mut router = PointerRouter::new();
+
+        let _ = router.dispatch(&tree, &down(5.0, 5.0));
+        assert!(router.is_captured(0));
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Move, 50.0, 50.0));
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Up, 50.0, 50.0));
+        assert!(!router.is_captured(0));
+        assert_eq!(vec!["inner", "inner", "inner"], *calls.borrow());
+
+        // Released, so moving outside the box misses it again
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Move, 50.0, 50.0));
+        assert_eq!(3, calls.borrow().len());
+    }
+
+    #[test]
+    fn router_hit_tests_pointers_that_are_not_pressed() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = listener("inner", &calls, EventStatus::Ignored, sized(20.0));
+        let tree = layout(&widget);
+        let mut router = PointerRouter::new();
+
+        let _ = router.dispatch(&tree, &down(50.0, 50.0));
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Move, 5.0, 5.0));
+        assert!(calls.borrow().is_empty());
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Up, 5.0, 5.0));
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Move, 5.0, 5.0));
+        assert_eq!(vec!["inner"], *calls.borrow());
+    }
+
+    #[test]
+    fn router_captures_each_pointer_separately() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let widget = Flex {
+            axis: Axis::Horizontal,
+            children: vec![
+                Box::new(listener("first", &calls, EventStatus::Ignored, sized(20.0))),
+                Box::new(listener(
+                    "second",
+                    &calls,
+                    EventStatus::Ignored,
+                    sized(20.0),
+                )),
+            ],
+            ..Flex::default()
+        };
+        let tree = layout(&widget);
+        let mut router = PointerRouter::new();
+
+        let _ = router.dispatch(&tree, &down(5.0, 5.0));
+        let _ = router.dispatch(
+            &tree,
+            &PointerEvent::new(PointerEventKind::Down, 1, Vector2::new(25.0, 5.0)),
+        );
+        calls.borrow_mut().clear();
+        let _ = router.dispatch(
+            &tree,
+            &PointerEvent::new(PointerEventKind::Move, 1, Vector2::new(5.0, 5.0)),
+        );
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Move, 25.0, 5.0));
+        assert_eq!(vec!["second", "first"], *calls.borrow());
+    }
+
+    #[test]
+    fn captured_events_use_local_position_from_down() {
+        let local = Rc::new(RefCell::new(vec![]));
+        let recorded = Rc::clone(&local);
+        let widget = Stack {
+            children: vec![Positioned {
+                position: Vector2::new(30.0, 40.0),
+                child: Box::new(Listener {
+                    on_pointer: PointerHandler::new(move |event| {
+                        recorded.borrow_mut().push(event.local_position);
+                        EventStatus::Handled
+                    }),
+                    child: Box::new(sized(20.0)),
+                }),
+            }],
+        };
+        let tree = layout(&widget);
+        let mut router = PointerRouter::new();
+
+        let _ = router.dispatch(&tree, &down(35.0, 50.0));
+        let _ = router.dispatch(&tree, &event(PointerEventKind::Up, 90.0, 0.0));
+        assert_eq!(
+            vec![Vector2::new(5.0, 10.0), Vector2::new(60.0, -40.0)],
+            *local.borrow()
+        );
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    // A 100x100 root, containing a 50x50 box at (1, 1), containing a 10x10 box
+    // at (10, 10).
+    fn nested_tree() -> LayoutTree {
+        let mut tree = LayoutTree::new();
+        let leaf = tree.insert(lbox(Rect::from_pos((10.0, 10.0), (10.0, 10.0)), vec![]));
+        let middle = tree.insert(lbox(Rect::from_pos((1.0, 1.0), (50.0, 50.0)), vec![leaf]));
+        let root = tree.insert(lbox(Rect::from_size((100.0, 100.0)), vec![middle]));
+        tree.set_root(Some(root));
+        tree
+    }
+
+    fn lbox(bounds: Rect, children: Vec<LayoutBoxId>) -> LayoutBox {
+        LayoutBox {
+            bounds,
+            children,
+            ..LayoutBox::default()
+        }
+    }
+
+    fn layout(widget: &dyn Layout) -> LayoutTree {
+        LayoutTree::from_root(
+            widget,
+            &BoxConstraints::from_max(Vector2::new(100.0, 100.0)),
+        )
+    }
+
+    fn sized(size: f32) -> Container {
+        Container {
+            width: Some(size),
+            height: Some(size),
+            ..Container::default()
+        }
+    }
+
+    fn listener(
+        name: &'static str,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        status: EventStatus,
+        child: impl Layout + 'static,
+    ) -> Listener {
+        let calls = Rc::clone(calls);
+        Listener {
+            on_pointer: PointerHandler::new(move |_| {
+                calls.borrow_mut().push(name);
+                status
+            }),
+            child: Box::new(child),
+        }
+    }
+
+    fn down(x: f32, y: f32) -> PointerEvent {
+        event(PointerEventKind::Down, x, y)
+    }
+
+    fn event(kind: PointerEventKind, x: f32, y: f32) -> PointerEvent {
+        PointerEvent::new(kind, 0, Vector2::new(x, y))
+    }
+}
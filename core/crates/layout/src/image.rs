@@ -0,0 +1,454 @@
+use crate::base::Alignment;
+use crate::tree::{BoxConstraints, Layout, LayoutTree, SizedLayoutBox};
+use math::{Rect, Vector2};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+/// How an image is resized to fit inside a box. These match CSS `object-fit`.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum BoxFit {
+    /// Stretch the image to fill the box, ignoring its aspect ratio.
+    Fill,
+    /// Scale the image to be as large as possible while still fitting entirely
+    /// inside the box.
+    #[default]
+    Contain,
+    /// Scale the image to be as small as possible while still covering the
+    /// whole box, cropping whatever overflows.
+    Cover,
+    /// Don't scale the image, and crop whatever overflows.
+    None,
+    /// Like `Contain` when the image is larger than the box, otherwise like
+    /// `None`.
+    ScaleDown,
+}
+
+/// Formats the fit like CSS, e.g. `scale-down`.
+impl fmt::Display for BoxFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BoxFit::Fill => "fill",
+            BoxFit::Contain => "contain",
+            BoxFit::Cover => "cover",
+            BoxFit::None => "none",
+            BoxFit::ScaleDown => "scale-down",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The result of fitting an image into a box. `source` is the size of the
+/// part of the image that is shown, and `destination` is the size it is drawn
+/// at inside the box.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct FittedSizes {
+    pub source: Vector2,
+    pub destination: Vector2,
+}
+
+impl BoxFit {
+    /// Fit an image of size `input` into a box of size `output`.
+    #[must_use]
+    pub fn apply(self, input: Vector2, output: Vector2) -> FittedSizes {
+        if input.x <= 0.0 || input.y <= 0.0 || output.x <= 0.0 || output.y <= 0.0 {
+            return FittedSizes::default();
+        }
+        // Whether the box is wider than the image, relative to their heights
+        let wider = output.x / output.y > input.x / input.y;
+        let contain = if wider {
+            Vector2::new(input.x * output.y / input.y, output.y)
+        } else {
+            Vector2::new(output.x, input.y * output.x / input.x)
+        };
+        match self {
+            BoxFit::Fill => FittedSizes {
+                source: input,
+                destination: output,
+            },
+            BoxFit::Contain => FittedSizes {
+                source: input,
+                destination: contain,
+            },
+            BoxFit::Cover => {
+                let source = if wider {
+                    Vector2::new(input.x, input.x * output.y / output.x)
+                } else {
+                    Vector2::new(input.y * output.x / output.y, input.y)
+                };
+                FittedSizes {
+                    source,
+                    destination: output,
+                }
+            }
+            BoxFit::None => {
+                let size = Vector2::new(input.x.min(output.x), input.y.min(output.y));
+                FittedSizes {
+                    source: size,
+                    destination: size,
+                }
+            }
+            BoxFit::ScaleDown => {
+                let destination = if input.x > output.x || input.y > output.y {
+                    contain
+                } else {
+                    input
+                };
+                FittedSizes {
+                    source: input,
+                    destination,
+                }
+            }
+        }
+    }
+}
+
+/// Whether an image is tiled across the parts of its box that it doesn't
+/// cover.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum ImageRepeat {
+    #[default]
+    NoRepeat,
+    Repeat,
+    RepeatX,
+    RepeatY,
+}
+
+impl ImageRepeat {
+    #[must_use]
+    pub fn repeats_x(self) -> bool {
+        matches!(self, ImageRepeat::Repeat | ImageRepeat::RepeatX)
+    }
+
+    #[must_use]
+    pub fn repeats_y(self) -> bool {
+        matches!(self, ImageRepeat::Repeat | ImageRepeat::RepeatY)
+    }
+}
+
+/// An image to paint inside a `LayoutBox`. `source` identifies the image to
+/// the backend, e.g. a URL in the browser.
+///
+/// The size of the image isn't stored here, because it is only known once the
+/// backend has loaded it. Backends use `placement` or `sample` to work out
+/// where the image goes, so every backend fits images the same way.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct ImagePaint {
+    pub source: String,
+    pub fit: BoxFit,
+    pub alignment: Alignment,
+    pub repeat: ImageRepeat,
+}
+
+/// Formats the image as its source and fit, followed by how it repeats, e.g.
+/// `"icon.png" contain repeat-x`.
+impl fmt::Display for ImagePaint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.source, self.fit)?;
+        match self.repeat {
+            ImageRepeat::NoRepeat => Ok(()),
+            ImageRepeat::Repeat => write!(f, " repeat"),
+            ImageRepeat::RepeatX => write!(f, " repeat-x"),
+            ImageRepeat::RepeatY => write!(f, " repeat-y"),
+        }
+    }
+}
+
+/// Where an image is drawn. The `source` rect of the image, in pixels, is
+/// stretched over the `destination` rect.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct ImagePlacement {
+    pub source: Rect,
+    pub destination: Rect,
+}
+
+impl ImagePaint {
+    /// Fit an image of `image_size` pixels into `rect`. Both the part of the
+    /// image that is shown and where it is drawn are positioned by
+    /// `alignment`, so a cropped image keeps the aligned edge.
+    #[must_use]
+    pub fn placement(&self, image_size: Vector2, rect: Rect) -> ImagePlacement {
+        let fitted = self.fit.apply(image_size, rect.size());
+        let alignment = Vector2::new(self.alignment.x, self.alignment.y);
+        let destination = rect.min + (rect.size() - fitted.destination) * alignment;
+        let source = (image_size - fitted.source) * alignment;
+        ImagePlacement {
+            source: Rect::new(source, source + fitted.source),
+            destination: Rect::new(destination, destination + fitted.destination),
+        }
+    }
+
+    /// The position in an image of `image_size` pixels that is drawn at
+    /// `point`, when the image is painted in `rect`. This is `None` outside of
+    /// `rect`, and outside of the image where it doesn't repeat.
+    #[must_use]
+    pub fn sample(&self, image_size: Vector2, point: Vector2, rect: Rect) -> Option<Vector2> {
+        if !rect.intersects(point) {
+            return None;
+        }
+        let placement = self.placement(image_size, rect);
+        let size = placement.destination.size();
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return None;
+        }
+        let tile = (point - placement.destination.min) / size;
+        let wrap = |t: f32, repeats: bool| {
+            if repeats {
+                Some(t.rem_euclid(1.0))
+            } else {
+                (0.0..1.0).contains(&t).then_some(t)
+            }
+        };
+        let x = wrap(tile.x, self.repeat.repeats_x())?;
+        let y = wrap(tile.y, self.repeat.repeats_y())?;
+        Some(placement.source.min + placement.source.size() * Vector2::new(x, y))
+    }
+}
+
+/// Looks up the size of images during layout.
+///
+/// Images are loaded by the platform, so layout can't know their size by
+/// itself. The platform (or a test) provides the measurer, like a
+/// `TextMeasurer` for text.
+pub trait ImageMeasurer: Debug {
+    /// The size of the image in pixels, or `None` if it isn't known, e.g.
+    /// because it hasn't loaded yet.
+    fn measure(&self, source: &str) -> Option<Vector2>;
+}
+
+/// Image sizes that are filled in as images are loaded. Clones share the same
+/// sizes, so the platform can keep one to update while widgets measure with
+/// another.
+#[derive(Clone, Default, Debug)]
+pub struct ImageSizes {
+    sizes: Rc<RefCell<HashMap<String, Vector2>>>,
+}
+
+impl ImageSizes {
+    #[must_use]
+    pub fn new() -> ImageSizes {
+        ImageSizes::default()
+    }
+
+    pub fn insert<S: Into<String>>(&self, source: S, size: Vector2) {
+        self.sizes.borrow_mut().insert(source.into(), size);
+    }
+}
+
+impl ImageMeasurer for ImageSizes {
+    fn measure(&self, source: &str) -> Option<Vector2> {
+        self.sizes.borrow().get(source).copied()
+    }
+}
+
+/// Displays an image, fit inside its box according to `fit`.
+///
+/// The box is `width` by `height` when both are set. When only one is set,
+/// the other follows the aspect ratio of the image, and when neither is set
+/// the box is the size of the image, scaled down to fit the constraints. The
+/// size of the image comes from `measurer`, and is zero until it is known.
+#[derive(Debug)]
+pub struct Image {
+    pub source: String,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub fit: BoxFit,
+    pub alignment: Alignment,
+    pub repeat: ImageRepeat,
+    pub measurer: Rc<dyn ImageMeasurer>,
+}
+
+impl Default for Image {
+    fn default() -> Image {
+        Image {
+            source: String::new(),
+            width: None,
+            height: None,
+            fit: BoxFit::default(),
+            alignment: Alignment::center(),
+            repeat: ImageRepeat::default(),
+            measurer: Rc::new(ImageSizes::default()),
+        }
+    }
+}
+
+impl Layout for Image {
+    fn layout(&self, _: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let intrinsic = self.measurer.measure(&self.source);
+        SizedLayoutBox {
+            size: self.size(intrinsic, constraints),
+            image: Some(ImagePaint {
+                source: self.source.clone(),
+                fit: self.fit,
+                alignment: self.alignment,
+                repeat: self.repeat,
+            }),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+impl Image {
+    fn size(&self, intrinsic: Option<Vector2>, constraints: &BoxConstraints) -> Vector2 {
+        let size = match (self.width, self.height, intrinsic) {
+            (Some(width), Some(height), _) => Vector2::new(width, height),
+            (Some(width), None, Some(image)) if image.x > 0.0 => {
+                Vector2::new(width, width * image.y / image.x)
+            }
+            (None, Some(height), Some(image)) if image.y > 0.0 => {
+                Vector2::new(height * image.x / image.y, height)
+            }
+            (None, None, Some(image)) => {
+                let scale = (constraints.max.x / image.x)
+                    .min(constraints.max.y / image.y)
+                    .min(1.0);
+                image * scale
+            }
+            (width, height, _) => Vector2::new(width.unwrap_or(0.0), height.unwrap_or(0.0)),
+        };
+        size.clamp_between(constraints.min, constraints.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_stretches_image() {
+        let fitted = BoxFit::Fill.apply(Vector2::new(10.0, 20.0), Vector2::new(40.0, 40.0));
+        assert_eq!(Vector2::new(10.0, 20.0), fitted.source);
+        assert_eq!(Vector2::new(40.0, 40.0), fitted.destination);
+    }
+
+    #[test]
+    fn contain_keeps_aspect_ratio_inside_box() {
+        let fitted = BoxFit::Contain.apply(Vector2::new(10.0, 20.0), Vector2::new(40.0, 20.0));
+        assert_eq!(Vector2::new(10.0, 20.0), fitted.source);
+        assert_eq!(Vector2::new(10.0, 20.0), fitted.destination);
+
+        let fitted = BoxFit::Contain.apply(Vector2::new(20.0, 10.0), Vector2::new(40.0, 40.0));
+        assert_eq!(Vector2::new(40.0, 20.0), fitted.destination);
+    }
+
+    #[test]
+    fn cover_crops_image_to_box_aspect_ratio() {
+        let fitted = BoxFit::Cover.apply(Vector2::new(20.0, 10.0), Vector2::new(40.0, 40.0));
+        assert_eq!(Vector2::new(10.0, 10.0), fitted.source);
+        assert_eq!(Vector2::new(40.0, 40.0), fitted.destination);
+
+        let fitted = BoxFit::Cover.apply(Vector2::new(10.0, 40.0), Vector2::new(20.0, 10.0));
+        assert_eq!(Vector2::new(10.0, 5.0), fitted.source);
+    }
+
+    #[test]
+    fn none_crops_without_scaling() {
+        let fitted = BoxFit::None.apply(Vector2::new(30.0, 10.0), Vector2::new(20.0, 20.0));
+        assert_eq!(Vector2::new(20.0, 10.0), fitted.source);
+        assert_eq!(Vector2::new(20.0, 10.0), fitted.destination);
+    }
+
+    #[test]
+    fn scale_down_only_shrinks() {
+        let small = BoxFit::ScaleDown.apply(Vector2::new(10.0, 5.0), Vector2::new(40.0, 40.0));
+        assert_eq!(Vector2::new(10.0, 5.0), small.destination);
+        let large = BoxFit::ScaleDown.apply(Vector2::new(80.0, 40.0), Vector2::new(40.0, 40.0));
+        assert_eq!(Vector2::new(40.0, 20.0), large.destination);
+    }
+
+    #[test]
+    fn empty_image_fits_to_nothing() {
+        let fitted = BoxFit::Cover.apply(Vector2::zero(), Vector2::new(40.0, 40.0));
+        assert_eq!(FittedSizes::default(), fitted);
+    }
+
+    #[test]
+    fn placement_aligns_destination_and_source() {
+        let paint = ImagePaint {
+            fit: BoxFit::Contain,
+            alignment: Alignment::new(1.0, 0.5),
+            ..ImagePaint::default()
+        };
+        let placement = paint.placement(
+            Vector2::new(10.0, 10.0),
+            Rect::from_pos((10.0, 0.0), (40.0, 20.0)),
+        );
+        assert_eq!(
+            Rect::from_pos((30.0, 0.0), (20.0, 20.0)),
+            placement.destination
+        );
+
+        let paint = ImagePaint {
+            fit: BoxFit::Cover,
+            alignment: Alignment::new(0.0, 1.0),
+            ..ImagePaint::default()
+        };
+        let placement = paint.placement(Vector2::new(10.0, 40.0), Rect::from_size((20.0, 20.0)));
+        assert_eq!(Rect::from_pos((0.0, 30.0), (10.0, 10.0)), placement.source);
+    }
+
+    #[test]
+    fn sample_is_empty_outside_image_unless_repeated() {
+        let rect = Rect::from_size((40.0, 10.0));
+        let paint = ImagePaint {
+            fit: BoxFit::None,
+            alignment: Alignment::top_left(),
+            ..ImagePaint::default()
+        };
+        let size = Vector2::new(10.0, 10.0);
+        assert_eq!(
+            Some(Vector2::new(2.5, 5.0)),
+            paint.sample(size, Vector2::new(2.5, 5.0), rect)
+        );
+        assert_eq!(None, paint.sample(size, Vector2::new(12.5, 5.0), rect));
+
+        let paint = ImagePaint {
+            repeat: ImageRepeat::RepeatX,
+            ..paint
+        };
+        assert_eq!(
+            Some(Vector2::new(2.5, 5.0)),
+            paint.sample(size, Vector2::new(12.5, 5.0), rect)
+        );
+        assert_eq!(None, paint.sample(size, Vector2::new(42.5, 5.0), rect));
+    }
+
+    #[test]
+    fn image_without_size_uses_intrinsic_size() {
+        let sizes = ImageSizes::new();
+        sizes.insert("photo.png", Vector2::new(80.0, 40.0));
+        let image = Image {
+            source: "photo.png".to_string(),
+            measurer: Rc::new(sizes),
+            ..Image::default()
+        };
+        let constraints = BoxConstraints::from_max((200.0, 200.0));
+        assert_eq!(
+            Vector2::new(80.0, 40.0),
+            image.size(Some(Vector2::new(80.0, 40.0)), &constraints)
+        );
+        let sbox = image.layout(&mut LayoutTree::new(), &constraints);
+        assert_eq!(Vector2::new(80.0, 40.0), sbox.size);
+
+        // Large images are scaled down to fit, keeping their aspect ratio
+        let constraints = BoxConstraints::from_max((40.0, 200.0));
+        let sbox = image.layout(&mut LayoutTree::new(), &constraints);
+        assert_eq!(Vector2::new(40.0, 20.0), sbox.size);
+    }
+
+    #[test]
+    fn image_with_one_dimension_keeps_aspect_ratio() {
+        let image = Image {
+            width: Some(20.0),
+            ..Image::default()
+        };
+        let constraints = BoxConstraints::from_max((200.0, 200.0));
+        assert_eq!(
+            Vector2::new(20.0, 10.0),
+            image.size(Some(Vector2::new(80.0, 40.0)), &constraints)
+        );
+        // Until the image loads, only the known dimension is used
+        assert_eq!(Vector2::new(20.0, 0.0), image.size(None, &constraints));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::event::EventHandler;
+use crate::focus::FocusId;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+
+// Whether a key was pressed or released
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum KeyEventKind {
+    Down,
+    Up,
+}
+
+/// The modifier keys that were held while a key event happened.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Eq, PartialEq, Copy, Clone, Default, Debug)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    #[must_use]
+    pub fn none() -> Modifiers {
+        Modifiers::default()
+    }
+
+    #[must_use]
+    pub fn shift() -> Modifiers {
+        Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct KeyEvent {
+    pub kind: KeyEventKind,
+    /// The key that was pressed, using the same names as the browser's
+    /// `KeyboardEvent.key`, e.g. `"a"`, `"Enter"` or `"Tab"`.
+    pub key: String,
+    pub modifiers: Modifiers,
+    /// Whether the key is being held down and this event was repeated.
+    pub repeat: bool,
+}
+
+impl KeyEvent {
+    #[must_use]
+    pub fn new(kind: KeyEventKind, key: &str, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent {
+            kind,
+            key: key.to_string(),
+            modifiers,
+            repeat: false,
+        }
+    }
+
+    #[must_use]
+    pub fn is_down(&self, key: &str) -> bool {
+        self.kind == KeyEventKind::Down && self.key == key
+    }
+}
+
+// Events that are delivered to the focused widget.
+//
+// Key events describe the physical keys, and should be used for shortcuts and
+// navigation. Text input events contain the characters that the keys produced,
+// and should be used for editing text.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum KeyboardEvent {
+    Key(KeyEvent),
+    TextInput(String),
+}
+
+/// A callback for keyboard events that is attached to a `LayoutBox`.
+pub type KeyHandler = EventHandler<KeyboardEvent>;
+
+/// Makes its child focusable. While it has focus, keyboard events are sent to
+/// `on_key`, and then bubble up to the handlers of its ancestors. It gets focus
+/// from Tab, or when it is clicked, see `FocusManager::focus_at`.
+///
+/// The `id` identifies the widget between frames, so it must be the same every
+/// time the widget is built, and unique within the tree.
+#[derive(Debug)]
+pub struct Focus {
+    pub id: FocusId,
+    pub on_key: Option<KeyHandler>,
+    pub child: Box<dyn Layout>,
+}
+
+impl Layout for Focus {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let sbox = self.child.layout(tree, constraints);
+        let size = sbox.size;
+        let id = tree.insert(LayoutBox::from_child(sbox, Vector2::zero()));
+        SizedLayoutBox {
+            size,
+            children: vec![id],
+            focus_id: Some(self.id),
+            key_handler: self.on_key.clone(),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+/// Calls `on_key` for keyboard events that bubble up from a focused
+/// descendant. It can't be focused itself, and doesn't change the layout of
+/// the child.
+#[derive(Debug)]
+pub struct KeyListener {
+    pub on_key: KeyHandler,
+    pub child: Box<dyn Layout>,
+}
+
+impl Layout for KeyListener {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let sbox = self.child.layout(tree, constraints);
+        let size = sbox.size;
+        let id = tree.insert(LayoutBox::from_child(sbox, Vector2::zero()));
+        SizedLayoutBox {
+            size,
+            children: vec![id],
+            key_handler: Some(self.on_key.clone()),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
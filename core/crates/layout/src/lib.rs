@@ -16,5 +16,56 @@ pub use tree::*;
 mod widget;
 pub use widget::*;
 
+mod element;
+pub use element::*;
+
+mod reactive;
+pub use reactive::*;
+
 mod flex;
 pub use flex::*;
+
+mod wrap;
+pub use wrap::*;
+
+mod grid;
+pub use grid::*;
+
+mod table;
+pub use table::*;
+
+mod text;
+pub use text::*;
+
+mod paragraph;
+pub use paragraph::*;
+
+mod event;
+pub use event::*;
+
+mod pointer;
+pub use pointer::*;
+
+mod hit_test;
+pub use hit_test::*;
+
+mod keyboard;
+pub use keyboard::*;
+
+mod focus;
+pub use focus::*;
+
+mod animation;
+pub use animation::*;
+
+mod animated_container;
+pub use animated_container::*;
+
+mod scroll;
+pub use scroll::*;
+
+mod list_view;
+pub use list_view::*;
+
+mod image;
+pub use image::*;
@@ -0,0 +1,559 @@
+use crate::element::Key;
+use crate::flex::Axis;
+use crate::pointer::PointerHandler;
+use crate::scroll::ScrollController;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::{Rect, Vector2};
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+// How far past each edge of the viewport rows are built by default, in pixels,
+// so they are ready before they scroll into view.
+const DEFAULT_CACHE_EXTENT: f32 = 250.0;
+
+/// The height of the rows of a `ListView`.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ItemExtent {
+    // Every row is exactly this tall
+    Fixed(f32),
+    // Rows size themselves, and this is the guess for rows that haven't been
+    // built yet
+    Estimated(f32),
+}
+
+/// A vertical scrolling list that only builds the rows in or near its
+/// viewport.
+///
+/// Rows are built by calling the builder with their index. With
+/// `ItemExtent::Fixed`, the position of any row is known up front, so the cost
+/// of a frame doesn't depend on how many rows there are. With
+/// `ItemExtent::Estimated`, each row is measured when it's built and the
+/// estimate is used for the rest. Only the measured rows are stored, so the
+/// cost grows with how many rows have been built, not with the length of the
+/// list. When rows above the viewport turn out to be a different size, the
+/// offset is corrected so the rows in the viewport stay where they are.
+///
+/// Each row keeps its own element, keyed by its index, so stateful widgets in
+/// a row keep their state while it stays near the viewport.
+pub struct ListView {
+    pub key: Option<Key>,
+    pub controller: Option<ScrollController>,
+    pub item_count: usize,
+    pub item_extent: ItemExtent,
+    /// How far past each edge of the viewport to build rows, in pixels.
+    pub cache_extent: f32,
+    builder: Rc<dyn Fn(usize) -> Box<dyn Layout>>,
+}
+
+impl ListView {
+    pub fn builder<F>(item_count: usize, item_extent: ItemExtent, builder: F) -> ListView
+    where
+        F: Fn(usize) -> Box<dyn Layout> + 'static,
+    {
+        ListView {
+            key: None,
+            controller: None,
+            item_count,
+            item_extent,
+            cache_extent: DEFAULT_CACHE_EXTENT,
+            builder: Rc::new(builder),
+        }
+    }
+
+    #[must_use]
+    pub fn key<K: Into<Key>>(self, key: K) -> ListView {
+        ListView {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn controller(self, controller: ScrollController) -> ListView {
+        ListView {
+            controller: Some(controller),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn cache_extent(self, cache_extent: f32) -> ListView {
+        ListView {
+            cache_extent,
+            ..self
+        }
+    }
+}
+
+impl fmt::Debug for ListView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListView")
+            .field("key", &self.key)
+            .field("item_count", &self.item_count)
+            .field("item_extent", &self.item_extent)
+            .field("cache_extent", &self.cache_extent)
+            .finish_non_exhaustive()
+    }
+}
+
+struct ListState {
+    controller: ScrollController,
+    extents: Extents,
+}
+
+// The measured heights of the rows that have been built with
+// `ItemExtent::Estimated`, keyed by their index, and their sum. Rows that
+// haven't been built aren't stored, so long lists stay cheap.
+#[derive(Default)]
+struct Extents {
+    measured: BTreeMap<usize, f32>,
+    total: f32,
+}
+
+impl Extents {
+    fn insert(&mut self, index: usize, extent: f32) {
+        if let Some(previous) = self.measured.insert(index, extent) {
+            self.total -= previous;
+        }
+        self.total += extent;
+    }
+
+    // Forget the rows from `len` onwards, when the list got shorter
+    fn truncate(&mut self, len: usize) {
+        let removed = self.measured.split_off(&len);
+        if !removed.is_empty() {
+            self.total = self.measured.values().sum();
+        }
+    }
+}
+
+// The element that each row is built in
+struct ListItem;
+
+impl ListView {
+    // The distance from the top of the list to the top of row `end`
+    #[allow(clippy::cast_precision_loss)]
+    fn start(&self, extents: &Extents, end: usize) -> f32 {
+        match self.item_extent {
+            ItemExtent::Fixed(extent) => end as f32 * extent,
+            ItemExtent::Estimated(estimate) => {
+                let (count, total) = if end >= self.item_count {
+                    (extents.measured.len(), extents.total)
+                } else {
+                    extents
+                        .measured
+                        .range(..end)
+                        .fold((0, 0.0), |(count, total), (_, extent)| {
+                            (count + 1, total + extent)
+                        })
+                };
+                total + (end - count) as f32 * estimate
+            }
+        }
+    }
+
+    // The row at `offset` from the top of the list, and the distance to its
+    // top. Offsets past the end are in the last row.
+    #[allow(clippy::cast_precision_loss)]
+    fn row_at(&self, extents: &Extents, offset: f32) -> (usize, f32) {
+        let last = self.item_count.saturating_sub(1);
+        // The row in a run of `estimate` tall rows that starts with row
+        // `index` at `start`
+        let row_in_run = |index: usize, start: f32, estimate: f32| {
+            if estimate <= 0.0 {
+                return (last, start + (last - index) as f32 * estimate);
+            }
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let skipped = ((offset - start) / estimate).max(0.0) as usize;
+            let row = index.saturating_add(skipped).min(last);
+            (row, start + (row - index) as f32 * estimate)
+        };
+        match self.item_extent {
+            ItemExtent::Fixed(extent) => row_in_run(0, 0.0, extent),
+            ItemExtent::Estimated(estimate) => {
+                let mut index = 0;
+                let mut start = 0.0;
+                for (&measured, &extent) in &extents.measured {
+                    // The estimated rows before this one
+                    let gap = (measured - index) as f32 * estimate;
+                    if start + gap > offset {
+                        break;
+                    }
+                    start += gap;
+                    if start + extent > offset || measured == last {
+                        return (measured, start);
+                    }
+                    start += extent;
+                    index = measured + 1;
+                }
+                row_in_run(index, start, estimate)
+            }
+        }
+    }
+
+    fn layout_item(
+        &self,
+        tree: &mut LayoutTree,
+        index: usize,
+        width: Vector2,
+        extents: &mut Extents,
+    ) -> SizedLayoutBox {
+        let height = match self.item_extent {
+            ItemExtent::Fixed(extent) => Vector2::new(extent, extent),
+            ItemExtent::Estimated(_) => Vector2::new(0.0, f32::INFINITY),
+        };
+        let constraints = BoxConstraints {
+            min: Vector2::new(width.x, height.x),
+            max: Vector2::new(width.y, height.y),
+        };
+        let _ = tree.elements.enter(
+            TypeId::of::<ListItem>(),
+            Some(Key::Int(index as u64)),
+            || (),
+        );
+        let sbox = (self.builder)(index).layout(tree, &constraints);
+        tree.elements.exit();
+        if let ItemExtent::Estimated(_) = self.item_extent {
+            extents.insert(index, sbox.size.y);
+        }
+        sbox
+    }
+}
+
+impl Layout for ListView {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let state = tree
+            .elements
+            .enter(TypeId::of::<ListView>(), self.key.clone(), || ListState {
+                controller: ScrollController::new(),
+                extents: Extents::default(),
+            });
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.untracked().controller.clone(),
+        };
+        let mut extents = state.update_untracked(|state| std::mem::take(&mut state.extents));
+        extents.truncate(self.item_count);
+
+        // Without a bounded height every row is built, like a `Flex`.
+        let viewport = constraints.max.y;
+        let width = if constraints.max.x.is_finite() {
+            Vector2::new(constraints.max.x, constraints.max.x)
+        } else {
+            Vector2::new(constraints.min.x, f32::INFINITY)
+        };
+        let content = self.start(&extents, self.item_count);
+        // Like `ScrollView`, the position is only updated for the real layout
+        let measuring = tree.elements.is_measuring();
+        let offset = if measuring {
+            controller.offset()
+        } else {
+            let (offset, animating) = controller.layout(tree.elements.time(), viewport, content);
+            if animating {
+                tree.request_frame();
+            }
+            offset
+        };
+
+        // The row at the top of the viewport anchors the layout. Rows are
+        // positioned relative to its top, so they stay put when the rows above
+        // it are measured.
+        let (anchor, anchor_start) = self.row_at(&extents, offset);
+        let mut within = offset - anchor_start;
+
+        let mut rows = vec![];
+        let mut bottom = 0.0;
+        let mut index = anchor;
+        while index < self.item_count && bottom < within + viewport + self.cache_extent {
+            let sbox = self.layout_item(tree, index, width, &mut extents);
+            let height = sbox.size.y;
+            rows.push((bottom, sbox));
+            bottom += height;
+            index += 1;
+        }
+        // Scrolled past the last row, so the viewport will be clamped to it.
+        if index == self.item_count && viewport.is_finite() {
+            within = within.min(bottom - viewport);
+        }
+
+        let mut above = vec![];
+        let mut top = 0.0;
+        let mut index = anchor;
+        while index > 0 && top > within - self.cache_extent {
+            index -= 1;
+            let sbox = self.layout_item(tree, index, width, &mut extents);
+            top -= sbox.size.y;
+            above.push((top, sbox));
+        }
+
+        let anchor_start = self.start(&extents, anchor);
+        let content = self.start(&extents, self.item_count);
+        let offset = if measuring {
+            anchor_start + within
+        } else {
+            controller.correct(anchor_start + within, viewport, content)
+        };
+        let shift = anchor_start - offset;
+
+        let mut list_width: f32 = 0.0;
+        let mut children = vec![];
+        for (top, sbox) in above.into_iter().rev().chain(rows) {
+            list_width = list_width.max(sbox.size.x);
+            children.push(tree.insert(LayoutBox::from_child(sbox, (0.0, top + shift))));
+        }
+        state.update_untracked(|state| state.extents = extents);
+        tree.elements.exit();
+
+        let size = Vector2::new(
+            if width.y.is_finite() {
+                width.y
+            } else {
+                list_width
+            },
+            if viewport.is_finite() {
+                viewport
+            } else {
+                content.max(constraints.min.y)
+            },
+        );
+        SizedLayoutBox {
+            size,
+            children,
+            pointer_handler: Some(PointerHandler::new(move |event| {
+                controller.handle_pointer(event, Axis::Vertical)
+            })),
+            clip: Some(Rect::from_size(size)),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use crate::element::ElementTree;
+    use crate::event::EventStatus;
+    use crate::pointer::PointerEvent;
+    use std::cell::RefCell;
+
+    #[test]
+    fn only_builds_rows_near_viewport() {
+        let built = Rc::new(RefCell::new(vec![]));
+        let list = recording_list(50_000, &built).cache_extent(0.0);
+        let _ = layout(&list, &mut ElementTree::new());
+        assert_eq!(vec![0, 1, 2, 3, 4], *built.borrow());
+
+        built.borrow_mut().clear();
+        let list = recording_list(50_000, &built).cache_extent(30.0);
+        let _ = layout(&list, &mut ElementTree::new());
+        assert_eq!(vec![0, 1, 2, 3, 4, 5, 6], *built.borrow());
+    }
+
+    #[test]
+    fn rows_are_positioned_by_offset() {
+        let controller = ScrollController::new();
+        let list = ListView::builder(20, ItemExtent::Fixed(40.0), |_| row(10.0))
+            .cache_extent(0.0)
+            .controller(controller.clone());
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        controller.set_offset(50.0);
+        let tree = layout(&list, &mut elements);
+        assert_eq!(
+            "\
+[0, 0, 100, 100] clip [0, 0, 100, 100]
+  [0, -10, 100, 30] fill rgba(255, 0, 0, 255)
+  [0, 30, 100, 70] fill rgba(255, 0, 0, 255)
+  [0, 70, 100, 110] fill rgba(255, 0, 0, 255)
+",
+            tree.to_string()
+        );
+        assert_close(700.0, controller.max_offset());
+    }
+
+    #[test]
+    fn estimated_rows_are_measured_when_built() {
+        let controller = ScrollController::new();
+        let list = ListView::builder(10, ItemExtent::Estimated(20.0), |_| row(30.0))
+            .cache_extent(0.0)
+            .controller(controller.clone());
+        let _ = layout(&list, &mut ElementTree::new());
+        // 4 rows were measured at 30, the other 6 are still estimated at 20
+        assert_close(4.0 * 30.0 + 6.0 * 20.0 - 100.0, controller.max_offset());
+    }
+
+    #[test]
+    fn scrolling_past_the_end_is_clamped_to_last_row() {
+        let controller = ScrollController::new();
+        let list = ListView::builder(10, ItemExtent::Estimated(30.0), |_| row(20.0))
+            .cache_extent(0.0)
+            .controller(controller.clone());
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        controller.set_offset(1000.0);
+        let tree = layout(&list, &mut elements);
+        // The rows were shorter than estimated, so the end moved up
+        assert_close(100.0, controller.offset());
+        assert!(tree
+            .to_string()
+            .ends_with("  [0, 80, 100, 100] fill rgba(255, 0, 0, 255)\n"));
+    }
+
+    #[test]
+    fn rows_in_viewport_stay_put_when_rows_above_change_size() {
+        let heights = Rc::new(RefCell::new(vec![20.0; 20]));
+        let controller = ScrollController::new();
+        let list = {
+            let heights = Rc::clone(&heights);
+            ListView::builder(20, ItemExtent::Estimated(20.0), move |index| {
+                row(heights.borrow()[index])
+            })
+            .cache_extent(50.0)
+            .controller(controller.clone())
+        };
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        controller.set_offset(100.0);
+        let before = layout(&list, &mut elements);
+
+        // Row 4 is above the viewport, but close enough to be built
+        heights.borrow_mut()[4] = 50.0;
+        let after = layout(&list, &mut elements);
+        assert_close(130.0, controller.offset());
+        assert!(after
+            .to_string()
+            .contains("  [0, -50, 100, 0] fill rgba(255, 0, 0, 255)\n"));
+        let first_visible = |tree: &LayoutTree| {
+            tree.to_string()
+                .lines()
+                .find(|line| line.starts_with("  [0, 0,"))
+                .map(str::to_string)
+        };
+        assert_eq!(first_visible(&before), first_visible(&after));
+    }
+
+    #[test]
+    fn fixed_rows_of_a_huge_list_are_found_by_offset() {
+        let built = Rc::new(RefCell::new(vec![]));
+        let controller = ScrollController::new();
+        let list = recording_list(1 << 40, &built)
+            .cache_extent(0.0)
+            .controller(controller.clone());
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        controller.set_offset(1_000_010.0);
+        built.borrow_mut().clear();
+        let tree = layout(&list, &mut elements);
+        assert_eq!(
+            vec![50_000, 50_001, 50_002, 50_003, 50_004, 50_005],
+            *built.borrow()
+        );
+        assert!(tree
+            .to_string()
+            .contains("  [0, -10, 100, 10] fill rgba(255, 0, 0, 255)\n"));
+    }
+
+    #[test]
+    fn estimated_rows_far_from_measured_rows_use_estimate() {
+        let built = Rc::new(RefCell::new(vec![]));
+        let controller = ScrollController::new();
+        let list = {
+            let built = Rc::clone(&built);
+            ListView::builder(1_000_000, ItemExtent::Estimated(20.0), move |index| {
+                built.borrow_mut().push(index);
+                row(30.0)
+            })
+            .cache_extent(0.0)
+            .controller(controller.clone())
+        };
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        // Rows 0 - 3 were measured at 30, so row 1000 starts at 20040
+        controller.set_offset(20_040.0);
+        built.borrow_mut().clear();
+        let _ = layout(&list, &mut elements);
+        assert_eq!(vec![1000, 1001, 1002, 1003], *built.borrow());
+        assert_close(20_040.0, controller.offset());
+        let measured = 8.0 * 30.0 + (1_000_000.0 - 8.0) * 20.0;
+        assert_close(measured - 100.0, controller.max_offset());
+    }
+
+    #[test]
+    fn shrinking_list_forgets_measured_rows_past_the_end() {
+        let controller = ScrollController::new();
+        let list = |count| {
+            ListView::builder(count, ItemExtent::Estimated(20.0), |_| row(30.0))
+                .cache_extent(0.0)
+                .controller(controller.clone())
+        };
+        let mut elements = ElementTree::new();
+        let _ = layout(&list(10), &mut elements);
+        let _ = layout(&list(2), &mut elements);
+        let _ = layout(&list(10), &mut elements);
+        assert_close(4.0 * 30.0 + 6.0 * 20.0 - 100.0, controller.max_offset());
+    }
+
+    #[test]
+    fn wheel_scrolls_list() {
+        let controller = ScrollController::new();
+        let list = ListView::builder(20, ItemExtent::Fixed(40.0), |_| row(10.0))
+            .controller(controller.clone());
+        let tree = layout(&list, &mut ElementTree::new());
+        let event = PointerEvent::wheel(0, Vector2::new(50.0, 50.0), Vector2::new(0.0, 60.0));
+        assert_eq!(EventStatus::Handled, tree.dispatch_pointer_event(&event));
+        assert_close(60.0, controller.offset());
+    }
+
+    #[test]
+    fn rows_out_of_view_drop_their_elements() {
+        let built = Rc::new(RefCell::new(vec![]));
+        let controller = ScrollController::new();
+        let list = recording_list(100, &built)
+            .cache_extent(0.0)
+            .controller(controller.clone());
+        let mut elements = ElementTree::new();
+        let _ = layout(&list, &mut elements);
+        // The list and its 5 visible rows
+        assert_eq!(6, elements.len());
+        controller.set_offset(1000.0);
+        let _ = layout(&list, &mut elements);
+        assert_eq!(6, elements.len());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn layout(widget: &dyn Layout, elements: &mut ElementTree) -> LayoutTree {
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        LayoutTree::from_root_with_elements(widget, &constraints, elements)
+    }
+
+    fn row(height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+
+    // A list of 20 pixel rows that records which rows were built
+    fn recording_list(count: usize, built: &Rc<RefCell<Vec<usize>>>) -> ListView {
+        let built = Rc::clone(built);
+        ListView::builder(count, ItemExtent::Fixed(20.0), move |index| {
+            built.borrow_mut().push(index);
+            row(20.0)
+        })
+    }
+
+    fn assert_close(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+}
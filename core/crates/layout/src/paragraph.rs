@@ -0,0 +1,580 @@
+use crate::text::{GlyphRun, PositionedGlyph, TextMeasurer, TextStyle};
+use math::{Rect, Vector2};
+use std::ops::Range;
+
+// How the lines of a paragraph are positioned horizontally.
+#[derive(Eq, PartialEq, Copy, Clone, Default, Debug)]
+pub enum TextAlign {
+    // Push lines to the left edge of the paragraph
+    #[default]
+    Start,
+    // Push lines to the right edge of the paragraph
+    End,
+    // Position lines in the center of the paragraph
+    Center,
+    // Stretch the spaces in each line so it fills the paragraph. The last line
+    // before a newline, and the last line of the paragraph, are not stretched.
+    Justify,
+}
+
+/// Controls how a paragraph is broken into lines.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ParagraphStyle {
+    pub align: TextAlign,
+    /// The maximum number of lines to show. If the text needs more lines than
+    /// this, the last visible line is truncated and ends with `ellipsis`.
+    pub max_lines: Option<usize>,
+    pub ellipsis: String,
+}
+
+impl Default for ParagraphStyle {
+    fn default() -> ParagraphStyle {
+        ParagraphStyle {
+            align: TextAlign::Start,
+            max_lines: None,
+            ellipsis: String::from("\u{2026}"),
+        }
+    }
+}
+
+/// A single line of a laid out paragraph.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LineBox {
+    /// The area covered by the glyphs on this line, relative to the paragraph.
+    pub bounds: Rect,
+    /// The byte range of the source text shown on this line. Trailing
+    /// whitespace at a soft line break is not included.
+    pub range: Range<usize>,
+    pub glyphs: Vec<PositionedGlyph>,
+    /// Whether the line was cut short by `ParagraphStyle::max_lines`.
+    pub ellipsized: bool,
+}
+
+/// The result of breaking text into lines that fit inside a maximum width.
+///
+/// Lines are broken at word boundaries. A word that is wider than the maximum
+/// width by itself is broken between characters. Explicit newlines always start
+/// a new line.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct Paragraph {
+    pub lines: Vec<LineBox>,
+    pub size: Vector2,
+}
+
+// A glyph that has been measured but not positioned.
+#[derive(Copy, Clone, Debug)]
+struct Item {
+    ch: char,
+    index: usize,
+    advance: f32,
+}
+
+// A line that has been broken but not positioned.
+struct RawLine {
+    items: Vec<Item>,
+    range: Range<usize>,
+    // The last line before a newline or the end of the text
+    is_hard_break: bool,
+    ellipsized: bool,
+}
+
+impl Paragraph {
+    #[must_use]
+    pub fn layout(
+        text: &str,
+        style: &TextStyle,
+        paragraph_style: &ParagraphStyle,
+        measurer: &dyn TextMeasurer,
+        max_width: f32,
+    ) -> Paragraph {
+        let mut lines = vec![];
+        let mut offset = 0;
+        for hard_line in text.split('\n') {
+            lines.extend(break_line(hard_line, offset, style, measurer, max_width));
+            offset += hard_line.len() + 1;
+        }
+
+        if let Some(max_lines) = paragraph_style.max_lines {
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                if let Some(last) = lines.last_mut() {
+                    ellipsize(last, &paragraph_style.ellipsis, style, measurer, max_width);
+                }
+            }
+        }
+
+        let widest = lines
+            .iter()
+            .map(|line| line_width(&line.items))
+            .fold(0.0, f32::max);
+        let align_width = if max_width.is_finite() {
+            max_width
+        } else {
+            widest
+        };
+        let width = match paragraph_style.align {
+            TextAlign::Start => widest,
+            _ => align_width,
+        };
+
+        let line_height = style.line_height_px();
+        let lines: Vec<LineBox> = lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                #[allow(clippy::cast_precision_loss)]
+                let y = i as f32 * line_height;
+                position_line(line, paragraph_style.align, align_width, y, line_height)
+            })
+            .collect();
+
+        #[allow(clippy::cast_precision_loss)]
+        let height = lines.len() as f32 * line_height;
+        Paragraph {
+            lines,
+            size: Vector2::new(width, height),
+        }
+    }
+
+    /// Flatten the glyphs of every line into a single run.
+    #[must_use]
+    pub fn glyph_run(&self, style: &TextStyle) -> GlyphRun {
+        GlyphRun {
+            glyphs: self
+                .lines
+                .iter()
+                .flat_map(|line| line.glyphs.iter().copied())
+                .collect(),
+            style: *style,
+        }
+    }
+
+    /// Get the index of the line at a vertical position. Positions above or
+    /// below the paragraph resolve to the first or last line.
+    #[must_use]
+    pub fn line_at(&self, y: f32) -> Option<usize> {
+        let last = self.lines.len().checked_sub(1)?;
+        let index = self
+            .lines
+            .iter()
+            .position(|line| y < line.bounds.max.y)
+            .unwrap_or(last);
+        Some(index)
+    }
+
+    /// Get the byte offset of the caret position closest to `point`. This is
+    /// the building block for selection and hit testing.
+    #[must_use]
+    pub fn offset_at(&self, point: Vector2) -> usize {
+        let line = match self.line_at(point.y) {
+            Some(index) => &self.lines[index],
+            None => return 0,
+        };
+        for glyph in &line.glyphs {
+            if point.x < glyph.position.x + glyph.advance / 2.0 {
+                return glyph.index.min(line.range.end);
+            }
+        }
+        line.range.end
+    }
+}
+
+// Break a single line of text, which contains no newlines, into lines that fit
+// inside `max_width`.
+fn break_line(
+    text: &str,
+    offset: usize,
+    style: &TextStyle,
+    measurer: &dyn TextMeasurer,
+    max_width: f32,
+) -> Vec<RawLine> {
+    let mut lines = vec![];
+    let mut current: Vec<Item> = vec![];
+    let mut width = 0.0;
+    for segment in segments(text, offset, style, measurer) {
+        let segment_width = line_width(&segment);
+        if segment[0].ch.is_whitespace() {
+            width += segment_width;
+            current.extend(segment);
+            continue;
+        }
+        if !current.is_empty() && width + segment_width > max_width {
+            lines.push(raw_line(std::mem::take(&mut current), offset, false));
+            width = 0.0;
+        }
+        if current.is_empty() && segment_width > max_width {
+            // The word doesn't fit on a line by itself, so break it between
+            // characters instead.
+            for item in segment {
+                if !current.is_empty() && width + item.advance > max_width {
+                    lines.push(raw_line(std::mem::take(&mut current), offset, false));
+                    width = 0.0;
+                }
+                width += item.advance;
+                current.push(item);
+            }
+        } else {
+            width += segment_width;
+            current.extend(segment);
+        }
+    }
+    lines.push(raw_line(current, offset + text.len(), true));
+    lines
+}
+
+// Split text into alternating runs of whitespace and words.
+fn segments(
+    text: &str,
+    offset: usize,
+    style: &TextStyle,
+    measurer: &dyn TextMeasurer,
+) -> Vec<Vec<Item>> {
+    let mut segments: Vec<Vec<Item>> = vec![];
+    for (index, ch) in text.char_indices() {
+        let item = Item {
+            ch,
+            index: offset + index,
+            advance: measurer.advance(ch, style),
+        };
+        match segments.last_mut() {
+            Some(segment) if segment[0].ch.is_whitespace() == ch.is_whitespace() => {
+                segment.push(item);
+            }
+            _ => segments.push(vec![item]),
+        }
+    }
+    segments
+}
+
+// Trailing whitespace is dropped so that it doesn't affect alignment. `empty_at`
+// is the offset used for the range of an empty line.
+fn raw_line(mut items: Vec<Item>, empty_at: usize, is_hard_break: bool) -> RawLine {
+    while items.last().is_some_and(|item| item.ch.is_whitespace()) {
+        items.pop();
+    }
+    let range = match (items.first(), items.last()) {
+        (Some(first), Some(last)) => first.index..last.index + last.ch.len_utf8(),
+        _ => empty_at..empty_at,
+    };
+    RawLine {
+        items,
+        range,
+        is_hard_break,
+        ellipsized: false,
+    }
+}
+
+// Remove glyphs from the end of the line until the ellipsis fits.
+fn ellipsize(
+    line: &mut RawLine,
+    ellipsis: &str,
+    style: &TextStyle,
+    measurer: &dyn TextMeasurer,
+    max_width: f32,
+) {
+    let ellipsis_width: f32 = ellipsis.chars().map(|ch| measurer.advance(ch, style)).sum();
+    while !line.items.is_empty() && line_width(&line.items) + ellipsis_width > max_width {
+        line.items.pop();
+    }
+    while line
+        .items
+        .last()
+        .is_some_and(|item| item.ch.is_whitespace())
+    {
+        line.items.pop();
+    }
+    line.range.end = match line.items.last() {
+        Some(last) => last.index + last.ch.len_utf8(),
+        None => line.range.start,
+    };
+    for ch in ellipsis.chars() {
+        line.items.push(Item {
+            ch,
+            index: line.range.end,
+            advance: measurer.advance(ch, style),
+        });
+    }
+    line.is_hard_break = true;
+    line.ellipsized = true;
+}
+
+fn position_line(
+    line: RawLine,
+    align: TextAlign,
+    align_width: f32,
+    y: f32,
+    line_height: f32,
+) -> LineBox {
+    let width = line_width(&line.items);
+    let free_space = (align_width - width).max(0.0);
+    let spaces = line
+        .items
+        .iter()
+        .filter(|item| item.ch.is_whitespace())
+        .count();
+    let (start, extra_per_space) = match align {
+        TextAlign::End => (free_space, 0.0),
+        TextAlign::Center => (free_space / 2.0, 0.0),
+        #[allow(clippy::cast_precision_loss)]
+        TextAlign::Justify if !line.is_hard_break && spaces > 0 => {
+            (0.0, free_space / spaces as f32)
+        }
+        TextAlign::Start | TextAlign::Justify => (0.0, 0.0),
+    };
+
+    let mut x = start;
+    let mut glyphs = Vec::with_capacity(line.items.len());
+    for item in line.items {
+        let advance = if item.ch.is_whitespace() {
+            item.advance + extra_per_space
+        } else {
+            item.advance
+        };
+        glyphs.push(PositionedGlyph {
+            ch: item.ch,
+            index: item.index,
+            position: Vector2::new(x, y),
+            advance,
+        });
+        x += advance;
+    }
+    LineBox {
+        bounds: Rect::new(Vector2::new(start, y), Vector2::new(x, y + line_height)),
+        range: line.range,
+        glyphs,
+        ellipsized: line.ellipsized,
+    }
+}
+
+fn line_width(items: &[Item]) -> f32 {
+    items.iter().map(|item| item.advance).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::MonospaceMeasurer;
+
+    // --------------------------------------------------
+    // Line breaking
+    // --------------------------------------------------
+
+    #[test]
+    fn paragraph_without_max_width_is_one_line() {
+        let paragraph = layout("hello world", &ParagraphStyle::default(), f32::INFINITY);
+        assert_eq!(vec!["hello world"], line_texts(&paragraph));
+        assert_eq!(Vector2::new(110.0, 10.0), paragraph.size);
+    }
+
+    #[test]
+    fn paragraph_wraps_at_word_boundary() {
+        let paragraph = layout("hello big world", &ParagraphStyle::default(), 90.0);
+        assert_eq!(vec!["hello big", "world"], line_texts(&paragraph));
+        assert_eq!(Vector2::new(90.0, 20.0), paragraph.size);
+    }
+
+    #[test]
+    fn paragraph_breaks_on_explicit_newlines() {
+        let paragraph = layout("a\n\nb", &ParagraphStyle::default(), f32::INFINITY);
+        assert_eq!(vec!["a", "", "b"], line_texts(&paragraph));
+        assert_eq!(Vector2::new(10.0, 30.0), paragraph.size);
+    }
+
+    #[test]
+    fn paragraph_breaks_long_word_between_characters() {
+        let paragraph = layout("abcdefg hi", &ParagraphStyle::default(), 30.0);
+        assert_eq!(vec!["abc", "def", "g", "hi"], line_texts(&paragraph));
+    }
+
+    #[test]
+    fn paragraph_line_height_controls_line_spacing() {
+        let style = TextStyle {
+            line_height: 1.5,
+            ..a_text_style()
+        };
+        let paragraph = Paragraph::layout(
+            "a\nb",
+            &style,
+            &ParagraphStyle::default(),
+            &MonospaceMeasurer { advance_ratio: 1.0 },
+            f32::INFINITY,
+        );
+        assert_eq!(Vector2::new(10.0, 30.0), paragraph.size);
+        assert_eq!(
+            Rect::from_pos((0.0, 15.0), (10.0, 15.0)),
+            paragraph.lines[1].bounds
+        );
+    }
+
+    #[test]
+    fn paragraph_line_ranges_skip_trailing_whitespace() {
+        let paragraph = layout("ab cd\nef", &ParagraphStyle::default(), 30.0);
+        let ranges: Vec<Range<usize>> = paragraph.lines.iter().map(|l| l.range.clone()).collect();
+        assert_eq!(vec![0..2, 3..5, 6..8], ranges);
+    }
+
+    // --------------------------------------------------
+    // Max lines
+    // --------------------------------------------------
+
+    #[test]
+    fn paragraph_with_max_lines_ellipsizes_last_line() {
+        let paragraph_style = ParagraphStyle {
+            max_lines: Some(1),
+            ellipsis: String::from("."),
+            ..ParagraphStyle::default()
+        };
+        let paragraph = layout("abc def", &paragraph_style, 40.0);
+        assert_eq!(vec!["abc."], line_texts(&paragraph));
+        assert!(paragraph.lines[0].ellipsized);
+        assert_eq!(0..3, paragraph.lines[0].range);
+    }
+
+    #[test]
+    fn paragraph_with_max_lines_removes_glyphs_to_fit_ellipsis() {
+        let paragraph_style = ParagraphStyle {
+            max_lines: Some(1),
+            ellipsis: String::from(".."),
+            ..ParagraphStyle::default()
+        };
+        let paragraph = layout("abcd efgh", &paragraph_style, 40.0);
+        assert_eq!(vec!["ab.."], line_texts(&paragraph));
+        assert_eq!(Vector2::new(40.0, 10.0), paragraph.size);
+    }
+
+    #[test]
+    fn paragraph_within_max_lines_is_not_ellipsized() {
+        let paragraph_style = ParagraphStyle {
+            max_lines: Some(2),
+            ..ParagraphStyle::default()
+        };
+        let paragraph = layout("ab cd", &paragraph_style, 20.0);
+        assert_eq!(vec!["ab", "cd"], line_texts(&paragraph));
+        assert!(!paragraph.lines[1].ellipsized);
+    }
+
+    // --------------------------------------------------
+    // Alignment
+    // --------------------------------------------------
+
+    #[test]
+    fn paragraph_align_start_shrinks_to_widest_line() {
+        let paragraph = layout("ab\nabcd", &ParagraphStyle::default(), 100.0);
+        assert_eq!(Vector2::new(40.0, 20.0), paragraph.size);
+        assert_eq!(
+            Rect::from_pos((0.0, 0.0), (20.0, 10.0)),
+            paragraph.lines[0].bounds
+        );
+    }
+
+    #[test]
+    fn paragraph_align_end_pushes_lines_to_right_edge() {
+        let paragraph = layout("ab\nabcd", &aligned(TextAlign::End), 100.0);
+        assert_eq!(Vector2::new(100.0, 20.0), paragraph.size);
+        assert_eq!(
+            Rect::from_pos((80.0, 0.0), (20.0, 10.0)),
+            paragraph.lines[0].bounds
+        );
+        assert_eq!(
+            Rect::from_pos((60.0, 10.0), (40.0, 10.0)),
+            paragraph.lines[1].bounds
+        );
+    }
+
+    #[test]
+    fn paragraph_align_center_centers_lines() {
+        let paragraph = layout("ab", &aligned(TextAlign::Center), 100.0);
+        assert_eq!(
+            Rect::from_pos((40.0, 0.0), (20.0, 10.0)),
+            paragraph.lines[0].bounds
+        );
+    }
+
+    #[test]
+    fn paragraph_align_center_without_max_width_uses_widest_line() {
+        let paragraph = layout("ab\nabcd", &aligned(TextAlign::Center), f32::INFINITY);
+        assert_eq!(Vector2::new(40.0, 20.0), paragraph.size);
+        assert_eq!(
+            Rect::from_pos((10.0, 0.0), (20.0, 10.0)),
+            paragraph.lines[0].bounds
+        );
+    }
+
+    #[test]
+    fn paragraph_align_justify_stretches_spaces() {
+        let paragraph = layout("a b c dd", &aligned(TextAlign::Justify), 60.0);
+        assert_eq!(vec!["a b c", "dd"], line_texts(&paragraph));
+        let first = &paragraph.lines[0];
+        assert_eq!(Rect::from_pos((0.0, 0.0), (60.0, 10.0)), first.bounds);
+        let xs: Vec<f32> = first.glyphs.iter().map(|g| g.position.x).collect();
+        assert_eq!(vec![0.0, 10.0, 25.0, 35.0, 50.0], xs);
+    }
+
+    #[test]
+    fn paragraph_align_justify_does_not_stretch_last_line() {
+        let paragraph = layout("a b c dd", &aligned(TextAlign::Justify), 60.0);
+        assert_eq!(
+            Rect::from_pos((0.0, 10.0), (20.0, 10.0)),
+            paragraph.lines[1].bounds
+        );
+    }
+
+    // --------------------------------------------------
+    // Hit testing
+    // --------------------------------------------------
+
+    #[test]
+    fn paragraph_offset_at_finds_closest_caret_position() {
+        let paragraph = layout("ab cd", &ParagraphStyle::default(), 20.0);
+        assert_eq!(0, paragraph.offset_at(Vector2::new(2.0, 5.0)));
+        assert_eq!(1, paragraph.offset_at(Vector2::new(8.0, 5.0)));
+        assert_eq!(2, paragraph.offset_at(Vector2::new(50.0, 5.0)));
+        assert_eq!(4, paragraph.offset_at(Vector2::new(12.0, 15.0)));
+        assert_eq!(5, paragraph.offset_at(Vector2::new(25.0, 100.0)));
+    }
+
+    #[test]
+    fn empty_paragraph_has_one_empty_line() {
+        let paragraph = layout("", &ParagraphStyle::default(), 100.0);
+        assert_eq!(vec![""], line_texts(&paragraph));
+        assert_eq!(Vector2::new(0.0, 10.0), paragraph.size);
+        assert_eq!(0, paragraph.offset_at(Vector2::new(5.0, 5.0)));
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn layout(text: &str, paragraph_style: &ParagraphStyle, max_width: f32) -> Paragraph {
+        Paragraph::layout(
+            text,
+            &a_text_style(),
+            paragraph_style,
+            &MonospaceMeasurer { advance_ratio: 1.0 },
+            max_width,
+        )
+    }
+
+    fn a_text_style() -> TextStyle {
+        TextStyle {
+            font_size: 10.0,
+            line_height: 1.0,
+            ..TextStyle::default()
+        }
+    }
+
+    fn aligned(align: TextAlign) -> ParagraphStyle {
+        ParagraphStyle {
+            align,
+            ..ParagraphStyle::default()
+        }
+    }
+
+    fn line_texts(paragraph: &Paragraph) -> Vec<String> {
+        paragraph
+            .lines
+            .iter()
+            .map(|line| line.glyphs.iter().map(|glyph| glyph.ch).collect())
+            .collect()
+    }
+}
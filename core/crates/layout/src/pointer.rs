@@ -0,0 +1,87 @@
+use crate::event::EventHandler;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+
+// The phase of a pointer interaction. These mirror the browser's pointer
+// events, so they cover the mouse, pen and touch.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum PointerEventKind {
+    // A button was pressed, or a finger touched the screen
+    Down,
+    // The pointer moved, whether or not a button is pressed
+    Move,
+    // A button was released, or a finger left the screen
+    Up,
+    // The interaction was interrupted, e.g. by the browser starting a scroll
+    Cancel,
+    // The wheel or trackpad scrolled by `scroll_delta`
+    Wheel,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct PointerEvent {
+    pub kind: PointerEventKind,
+    /// Identifies the pointer when there are several at once, like fingers on
+    /// a touch screen.
+    pub pointer_id: i32,
+    /// The position of the pointer in pixels from the top-left corner of the
+    /// viewport.
+    pub position: Vector2,
+    /// The position of the pointer relative to the top-left corner of the box
+    /// that is currently handling the event. This is set during dispatch.
+    pub local_position: Vector2,
+    /// How far to scroll in pixels for `Wheel` events. Positive values scroll
+    /// the content right and down, like the browser's `WheelEvent`.
+    pub scroll_delta: Vector2,
+    /// When the event happened in milliseconds, on the same clock as the frame
+    /// times passed to `ElementTree::set_time`. This is the browser's
+    /// `Event.timeStamp`.
+    pub time: f32,
+}
+
+impl PointerEvent {
+    #[must_use]
+    pub fn new(kind: PointerEventKind, pointer_id: i32, position: Vector2) -> PointerEvent {
+        PointerEvent {
+            kind,
+            pointer_id,
+            position,
+            local_position: position,
+            scroll_delta: Vector2::zero(),
+            time: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn wheel(pointer_id: i32, position: Vector2, scroll_delta: Vector2) -> PointerEvent {
+        PointerEvent {
+            scroll_delta,
+            ..PointerEvent::new(PointerEventKind::Wheel, pointer_id, position)
+        }
+    }
+}
+
+/// A callback for pointer events that is attached to a `LayoutBox`.
+pub type PointerHandler = EventHandler<PointerEvent>;
+
+/// Calls `on_pointer` for pointer events that hit its child, or any of the
+/// child's descendants. It doesn't change the layout of the child.
+#[derive(Debug)]
+pub struct Listener {
+    pub on_pointer: PointerHandler,
+    pub child: Box<dyn Layout>,
+}
+
+impl Layout for Listener {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let sbox = self.child.layout(tree, constraints);
+        let size = sbox.size;
+        let id = tree.insert(LayoutBox::from_child(sbox, Vector2::zero()));
+        SizedLayoutBox {
+            size,
+            children: vec![id],
+            pointer_handler: Some(self.on_pointer.clone()),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
@@ -0,0 +1,864 @@
+use crate::element::Key;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutBoxId, LayoutTree, SizedLayoutBox};
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+// --------------------------------------------------
+// Dependency tracking
+// --------------------------------------------------
+
+// Something that depends on reactive values, and is notified when they change.
+//
+// Each time a subscriber re-runs it starts a new generation, and only
+// subscriptions made in the current generation are notified. This drops the
+// dependencies that it stopped reading, without having to unsubscribe from
+// every source.
+pub(crate) trait Subscriber {
+    fn notify(&self);
+
+    fn generation(&self) -> u64;
+}
+
+#[derive(Clone)]
+struct Subscription {
+    subscriber: Weak<dyn Subscriber>,
+    generation: u64,
+}
+
+impl Subscription {
+    // Whether the subscriber is still alive, and hasn't re-run since it
+    // subscribed.
+    fn is_current(&self) -> bool {
+        self.subscriber
+            .upgrade()
+            .is_some_and(|subscriber| subscriber.generation() == self.generation)
+    }
+}
+
+thread_local! {
+    // The subscribers that are currently running, innermost last. Reading a
+    // reactive value subscribes the innermost one.
+    static RUNNING: RefCell<Vec<Subscription>> = const { RefCell::new(Vec::new()) };
+}
+
+// Run `f` while tracking the reactive values it reads as dependencies of
+// `subscriber`.
+pub(crate) fn track<R>(
+    subscriber: Weak<dyn Subscriber>,
+    generation: u64,
+    f: impl FnOnce() -> R,
+) -> R {
+    RUNNING.with(|running| {
+        running.borrow_mut().push(Subscription {
+            subscriber,
+            generation,
+        });
+    });
+    let result = f();
+    RUNNING.with(|running| running.borrow_mut().pop());
+    result
+}
+
+/// Run `f` without subscribing to the reactive values it reads.
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    let saved = RUNNING.with(|running| std::mem::take(&mut *running.borrow_mut()));
+    let result = f();
+    RUNNING.with(|running| *running.borrow_mut() = saved);
+    result
+}
+
+// The subscribers of a reactive value.
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    subscriptions: RefCell<Vec<Subscription>>,
+}
+
+impl Subscribers {
+    // Subscribe the running subscriber, if there is one.
+    //
+    // Subscriptions that can no longer be notified are dropped first. A value
+    // that is read by a subscriber which keeps re-running because of other
+    // values is never notified itself, so otherwise its list would grow with
+    // every run.
+    pub(crate) fn track(&self) {
+        let Some(current) = RUNNING.with(|running| running.borrow().last().cloned()) else {
+            return;
+        };
+        let mut subscriptions = self.subscriptions.borrow_mut();
+        subscriptions.retain(Subscription::is_current);
+        let subscribed = subscriptions.iter().any(|sub| {
+            sub.generation == current.generation
+                && Weak::ptr_eq(&sub.subscriber, &current.subscriber)
+        });
+        if !subscribed {
+            subscriptions.push(current);
+        }
+    }
+
+    // Notify every current subscriber. The list is emptied first, because
+    // subscribers subscribe again when they re-run, and they may re-run
+    // during `notify`.
+    pub(crate) fn notify(&self) {
+        let subscriptions = std::mem::take(&mut *self.subscriptions.borrow_mut());
+        for sub in subscriptions {
+            if !sub.is_current() {
+                continue;
+            }
+            if let Some(subscriber) = sub.subscriber.upgrade() {
+                subscriber.notify();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("len", &self.subscriptions.borrow().len())
+            .finish()
+    }
+}
+
+// --------------------------------------------------
+// Signal
+// --------------------------------------------------
+
+/// A value that notifies the code that read it when it changes.
+///
+/// Reading a signal inside a `Reactive` widget, a `Memo` or an `Effect` makes
+/// it a dependency, so they are updated the next time the signal is set.
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    subscribers: Subscribers,
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Signal<T> {
+        Signal {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(value),
+                subscribers: Subscribers::default(),
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Read the value by reference, which avoids cloning it.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.subscribers.track();
+        f(&self.inner.value.borrow())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.inner.subscribers.notify();
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        self.inner.subscribers.notify();
+    }
+}
+
+// Derive would require `T: Clone`, but only the `Rc` is cloned.
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Signal<T> {
+        Signal {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Signal")
+            .field(&self.inner.value.borrow())
+            .finish()
+    }
+}
+
+// --------------------------------------------------
+// Memo
+// --------------------------------------------------
+
+/// A value derived from other reactive values. It is recomputed as soon as one
+/// of them changes, but only notifies its own readers when the result is
+/// different.
+pub struct Memo<T> {
+    inner: Rc<MemoInner<T>>,
+}
+
+struct MemoInner<T> {
+    compute: Box<dyn Fn() -> T>,
+    value: RefCell<Option<T>>,
+    generation: Cell<u64>,
+    subscribers: Subscribers,
+    this: Weak<MemoInner<T>>,
+}
+
+impl<T: PartialEq + 'static> MemoInner<T> {
+    // Returns whether the value changed
+    fn recompute(&self) -> bool {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        let this: Weak<dyn Subscriber> = self.this.clone();
+        let value = track(this, generation, &self.compute);
+        let mut current = self.value.borrow_mut();
+        if current.as_ref() == Some(&value) {
+            return false;
+        }
+        *current = Some(value);
+        true
+    }
+}
+
+impl<T: PartialEq + 'static> Subscriber for MemoInner<T> {
+    fn notify(&self) {
+        if self.recompute() {
+            self.subscribers.notify();
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.get()
+    }
+}
+
+impl<T: PartialEq + 'static> Memo<T> {
+    pub fn new<F>(compute: F) -> Memo<T>
+    where
+        F: Fn() -> T + 'static,
+    {
+        let inner = Rc::new_cyclic(|this| MemoInner {
+            compute: Box::new(compute),
+            value: RefCell::new(None),
+            generation: Cell::new(0),
+            subscribers: Subscribers::default(),
+            this: this.clone(),
+        });
+        inner.recompute();
+        Memo { inner }
+    }
+
+    #[must_use]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Read the value by reference, which avoids cloning it.
+    ///
+    /// # Panics
+    ///
+    /// If it is called from inside the memo's own computation.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.subscribers.track();
+        let value = self.inner.value.borrow();
+        f(value.as_ref().expect("memo is computed when it is created"))
+    }
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Memo<T> {
+        Memo {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Memo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Memo")
+            .field(&self.inner.value.borrow())
+            .finish()
+    }
+}
+
+// --------------------------------------------------
+// Effect
+// --------------------------------------------------
+
+/// Runs a side effect once, and then again every time one of the reactive
+/// values it read changes. It stops when the `Effect` is dropped.
+#[must_use = "the effect stops running when it is dropped"]
+pub struct Effect {
+    inner: Rc<EffectInner>,
+}
+
+struct EffectInner {
+    run: Box<dyn Fn()>,
+    generation: Cell<u64>,
+    this: Weak<EffectInner>,
+}
+
+impl EffectInner {
+    fn run(&self) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        let this: Weak<dyn Subscriber> = self.this.clone();
+        track(this, generation, &self.run);
+    }
+}
+
+impl Subscriber for EffectInner {
+    fn notify(&self) {
+        self.run();
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.get()
+    }
+}
+
+impl Effect {
+    pub fn new<F>(run: F) -> Effect
+    where
+        F: Fn() + 'static,
+    {
+        let inner = Rc::new_cyclic(|this| EffectInner {
+            run: Box::new(run),
+            generation: Cell::new(0),
+            this: this.clone(),
+        });
+        inner.run();
+        Effect { inner }
+    }
+}
+
+impl fmt::Debug for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Effect")
+            .field("generation", &self.inner.generation.get())
+            .finish()
+    }
+}
+
+// --------------------------------------------------
+// Reactive
+// --------------------------------------------------
+
+/// A subtree that is only rebuilt when the reactive values it depends on
+/// change.
+///
+/// `build` runs the first time the widget is laid out, and again after any
+/// signal or memo it read (while building or laying out the subtree) has
+/// changed. In between, the widget reuses the subtree from the last frame, and
+/// when the constraints are the same it also reuses its layout, so an
+/// unchanged subtree isn't laid out again.
+///
+/// Because the subtree is reused, `build` should only depend on reactive
+/// values, and not on anything captured when the widget was created. State
+/// survives between frames through the `ElementTree`, so this only has an
+/// effect when the tree is laid out with `LayoutTree::from_root_with_elements`.
+pub struct Reactive {
+    pub key: Option<Key>,
+    build: Rc<dyn Fn() -> Box<dyn Layout>>,
+}
+
+impl Reactive {
+    pub fn new<F>(build: F) -> Reactive
+    where
+        F: Fn() -> Box<dyn Layout> + 'static,
+    {
+        Reactive {
+            key: None,
+            build: Rc::new(build),
+        }
+    }
+
+    #[must_use]
+    pub fn key<K: Into<Key>>(self, key: K) -> Reactive {
+        Reactive {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+}
+
+impl fmt::Debug for Reactive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reactive")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+// Tracks whether a `Reactive` element is out of date.
+#[derive(Default, Debug)]
+pub(crate) struct ReactiveNode {
+    generation: Cell<u64>,
+    // A dependency of this element changed, so it must be built again
+    needs_build: Cell<bool>,
+    // This element, or a reactive descendant, must be laid out again
+    needs_layout: Cell<bool>,
+    // The nearest reactive ancestor, which has to lay out again when this does
+    parent: RefCell<Option<Weak<ReactiveNode>>>,
+}
+
+impl ReactiveNode {
+    pub(crate) fn mark_needs_layout(&self) {
+        self.needs_layout.set(true);
+        let parent = self.parent.borrow().as_ref().and_then(Weak::upgrade);
+        if let Some(parent) = parent {
+            if !parent.needs_layout.get() {
+                parent.mark_needs_layout();
+            }
+        }
+    }
+}
+
+impl Subscriber for ReactiveNode {
+    fn notify(&self) {
+        self.needs_build.set(true);
+        self.mark_needs_layout();
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.get()
+    }
+}
+
+// The result of laying out the subtree, which can be copied into the next
+// frame's tree when nothing in it has changed.
+struct LayoutCache {
+    constraints: BoxConstraints,
+    sbox: SizedLayoutBox,
+    // The boxes inserted while laying out the subtree, which are contiguous
+    boxes: Vec<LayoutBox>,
+    start: LayoutBoxId,
+}
+
+impl LayoutCache {
+    fn insert_into(&self, tree: &mut LayoutTree) -> SizedLayoutBox {
+        let start = tree.boxes.len();
+        let remap = |id: LayoutBoxId| id - self.start + start;
+        for lbox in &self.boxes {
+            let children = lbox.children.iter().copied().map(remap).collect();
+            tree.insert(LayoutBox {
+                children,
+                ..lbox.clone()
+            });
+        }
+        SizedLayoutBox {
+            children: self.sbox.children.iter().copied().map(remap).collect(),
+            ..self.sbox.clone()
+        }
+    }
+}
+
+struct ReactiveState {
+    node: Rc<ReactiveNode>,
+    child: Option<Rc<dyn Layout>>,
+    cache: Option<LayoutCache>,
+}
+
+impl Layout for Reactive {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let state = tree
+            .elements
+            .enter(TypeId::of::<Reactive>(), self.key.clone(), || {
+                ReactiveState {
+                    node: Rc::new(ReactiveNode::default()),
+                    child: None,
+                    cache: None,
+                }
+            });
+        let node = Rc::clone(&state.untracked().node);
+        let parent = tree.reactive_scopes.last().map(Rc::downgrade);
+        *node.parent.borrow_mut() = parent;
+
+        if !node.needs_layout.get() {
+            let cached = state
+                .untracked()
+                .cache
+                .as_ref()
+                .filter(|cache| cache.constraints == *constraints)
+                .map(|cache| cache.insert_into(tree));
+            if let Some(sbox) = cached {
+                tree.elements.keep_descendants(state.id());
+                tree.elements.exit();
+                return sbox;
+            }
+        }
+
+        let subscriber: Rc<dyn Subscriber> = node.clone();
+        let subscriber = Rc::downgrade(&subscriber);
+        let child = state.untracked().child.clone();
+        let child = match child {
+            Some(child) if !node.needs_build.get() => child,
+            _ => {
+                node.generation.set(node.generation.get() + 1);
+                node.needs_build.set(false);
+                let child: Rc<dyn Layout> = Rc::from(track(
+                    subscriber.clone(),
+                    node.generation.get(),
+                    &*self.build,
+                ));
+                state.update_untracked(|state| state.child = Some(Rc::clone(&child)));
+                child
+            }
+        };
+
+        // A measured layout isn't cached, because descendants can't ask to be
+        // laid out again while they are measured, and the layout that follows
+        // has to run anyway.
+        let measuring = tree.elements.is_measuring();
+        // Cleared first, so descendants that animate can ask to be laid out
+        // again next frame.
+        if !measuring {
+            node.needs_layout.set(false);
+        }
+        tree.reactive_scopes.push(Rc::clone(&node));
+        let start = tree.boxes.len();
+        let sbox = track(subscriber, node.generation.get(), || {
+            child.layout(tree, constraints)
+        });
+        tree.reactive_scopes.pop();
+
+        if !measuring {
+            let cache = LayoutCache {
+                constraints: constraints.clone(),
+                sbox: sbox.clone(),
+                boxes: tree.boxes[start..].to_vec(),
+                start,
+            };
+            state.update_untracked(|state| state.cache = Some(cache));
+        }
+        tree.elements.exit();
+        sbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use crate::element::ElementTree;
+    use crate::flex::{Flex, FlexLayout};
+    use math::Vector2;
+
+    #[test]
+    fn signal_get_and_set() {
+        let signal = Signal::new(1);
+        signal.set(2);
+        assert_eq!(2, signal.get());
+        signal.update(|value| *value += 1);
+        assert_eq!(3, signal.get());
+    }
+
+    #[test]
+    fn effect_runs_again_when_signal_changes() {
+        let signal = Signal::new(1);
+        let seen = Rc::new(RefCell::new(vec![]));
+        let _effect = {
+            let signal = signal.clone();
+            let seen = Rc::clone(&seen);
+            Effect::new(move || seen.borrow_mut().push(signal.get()))
+        };
+        signal.set(2);
+        signal.set(3);
+        assert_eq!(vec![1, 2, 3], *seen.borrow());
+    }
+
+    #[test]
+    fn effect_stops_when_dropped() {
+        let signal = Signal::new(1);
+        let seen = Rc::new(RefCell::new(vec![]));
+        let effect = {
+            let signal = signal.clone();
+            let seen = Rc::clone(&seen);
+            Effect::new(move || seen.borrow_mut().push(signal.get()))
+        };
+        drop(effect);
+        signal.set(2);
+        assert_eq!(vec![1], *seen.borrow());
+    }
+
+    #[test]
+    fn effect_only_depends_on_signals_read_in_last_run() {
+        let toggle = Signal::new(true);
+        let a = Signal::new(0);
+        let b = Signal::new(0);
+        let runs = Rc::new(Cell::new(0));
+        let _effect = {
+            let (toggle, a, b, runs) = (toggle.clone(), a.clone(), b.clone(), Rc::clone(&runs));
+            Effect::new(move || {
+                runs.set(runs.get() + 1);
+                let _ = if toggle.get() { a.get() } else { b.get() };
+            })
+        };
+        toggle.set(false);
+        assert_eq!(2, runs.get());
+        a.set(1);
+        assert_eq!(2, runs.get());
+        b.set(1);
+        assert_eq!(3, runs.get());
+    }
+
+    #[test]
+    fn untracked_reads_are_not_dependencies() {
+        let signal = Signal::new(1);
+        let runs = Rc::new(Cell::new(0));
+        let _effect = {
+            let (signal, runs) = (signal.clone(), Rc::clone(&runs));
+            Effect::new(move || {
+                runs.set(runs.get() + 1);
+                let _ = untracked(|| signal.get());
+            })
+        };
+        signal.set(2);
+        assert_eq!(1, runs.get());
+    }
+
+    #[test]
+    fn memo_updates_when_source_changes() {
+        let signal = Signal::new(2);
+        let doubled = {
+            let signal = signal.clone();
+            Memo::new(move || signal.get() * 2)
+        };
+        assert_eq!(4, doubled.get());
+        signal.set(5);
+        assert_eq!(10, doubled.get());
+    }
+
+    #[test]
+    fn memo_only_notifies_when_value_changes() {
+        let signal = Signal::new(1);
+        let is_even = {
+            let signal = signal.clone();
+            Memo::new(move || signal.get() % 2 == 0)
+        };
+        let runs = Rc::new(Cell::new(0));
+        let _effect = {
+            let (is_even, runs) = (is_even.clone(), Rc::clone(&runs));
+            Effect::new(move || {
+                runs.set(runs.get() + 1);
+                let _ = is_even.get();
+            })
+        };
+        signal.set(3);
+        assert_eq!(1, runs.get());
+        signal.set(4);
+        assert_eq!(2, runs.get());
+    }
+
+    #[test]
+    fn reactive_is_not_rebuilt_until_signal_changes() {
+        let width = Signal::new(10.0);
+        let builds = Rc::new(Cell::new(0));
+        let mut elements = ElementTree::new();
+        let widget = || sized_box(&width, &builds);
+
+        let first = layout(&widget(), &mut elements);
+        let second = layout(&widget(), &mut elements);
+        assert_eq!(1, builds.get());
+        assert_eq!(first.to_string(), second.to_string());
+
+        width.set(20.0);
+        let third = layout(&widget(), &mut elements);
+        assert_eq!(2, builds.get());
+        assert_eq!(Vector2::new(20.0, 10.0), root_size(&third));
+    }
+
+    #[test]
+    fn only_the_affected_reactive_subtree_is_rebuilt() {
+        let a = Signal::new(10.0);
+        let b = Signal::new(10.0);
+        let a_builds = Rc::new(Cell::new(0));
+        let b_builds = Rc::new(Cell::new(0));
+        let mut elements = ElementTree::new();
+        let widget = || Flex {
+            children: vec![
+                Box::new(sized_box(&a, &a_builds)) as Box<dyn FlexLayout>,
+                Box::new(sized_box(&b, &b_builds)),
+            ],
+            ..Flex::default()
+        };
+
+        let _ = layout(&widget(), &mut elements);
+        b.set(30.0);
+        let tree = layout(&widget(), &mut elements);
+        assert_eq!(1, a_builds.get());
+        assert_eq!(2, b_builds.get());
+        assert_eq!(
+            "[0, 0, 30, 100]\n  [0, 0, 10, 10] fill rgba(0, 0, 0, 0)\n  [0, 10, 30, 20] fill rgba(0, 0, 0, 0)\n",
+            tree.to_string()
+        );
+    }
+
+    #[test]
+    fn nested_reactive_change_relays_out_ancestors_without_rebuilding_them() {
+        let width = Signal::new(10.0);
+        let inner_builds = Rc::new(Cell::new(0));
+        let outer_builds = Rc::new(Cell::new(0));
+        let mut elements = ElementTree::new();
+        let widget = || {
+            let (width, inner_builds, outer_builds) = (
+                width.clone(),
+                Rc::clone(&inner_builds),
+                Rc::clone(&outer_builds),
+            );
+            Reactive::new(move || {
+                outer_builds.set(outer_builds.get() + 1);
+                Box::new(Container {
+                    padding: crate::base::EdgeInsets::all(5.0),
+                    child: Some(Box::new(sized_box(&width, &inner_builds))),
+                    ..Container::default()
+                })
+            })
+        };
+
+        let _ = layout(&widget(), &mut elements);
+        width.set(40.0);
+        let tree = layout(&widget(), &mut elements);
+        assert_eq!(1, outer_builds.get());
+        assert_eq!(2, inner_builds.get());
+        assert!(tree.to_string().contains("[5, 5, 45, 15]"), "{tree}");
+    }
+
+    #[test]
+    fn reactive_lays_out_again_when_constraints_change() {
+        let color = Signal::new(Color::red());
+        let builds = Rc::new(Cell::new(0));
+        let mut elements = ElementTree::new();
+        let widget = || {
+            let (color, builds) = (color.clone(), Rc::clone(&builds));
+            Reactive::new(move || {
+                builds.set(builds.get() + 1);
+                Box::new(Container {
+                    color: color.get(),
+                    ..Container::default()
+                })
+            })
+        };
+
+        let _ = layout(&widget(), &mut elements);
+        let constraints = BoxConstraints::from_max(Vector2::new(50.0, 50.0));
+        let tree = LayoutTree::from_root_with_elements(&widget(), &constraints, &mut elements);
+        assert_eq!(1, builds.get());
+        assert_eq!(Vector2::new(50.0, 50.0), root_size(&tree));
+    }
+
+    #[test]
+    fn reused_subtree_keeps_nested_element_state() {
+        let width = Signal::new(10.0);
+        let inner_builds = Rc::new(Cell::new(0));
+        let mut elements = ElementTree::new();
+        let widget = || {
+            let (width, inner_builds) = (width.clone(), Rc::clone(&inner_builds));
+            Reactive::new(move || Box::new(sized_box(&width, &inner_builds)))
+        };
+
+        let _ = layout(&widget(), &mut elements);
+        let _ = layout(&widget(), &mut elements);
+        assert_eq!(2, elements.len());
+        let _ = layout(&widget(), &mut elements);
+        assert_eq!(1, inner_builds.get());
+    }
+
+    #[test]
+    fn rerunning_effect_does_not_grow_subscriber_list_of_other_signals() {
+        let a = Signal::new(0);
+        let b = Signal::new(0);
+        let _effect = {
+            let (a, b) = (a.clone(), b.clone());
+            Effect::new(move || {
+                let _ = a.get() + b.get();
+            })
+        };
+        for i in 0..100 {
+            b.set(i);
+        }
+        assert_eq!(1, subscriber_count(&a));
+        assert_eq!(1, subscriber_count(&b));
+    }
+
+    #[test]
+    fn rebuilding_reactive_does_not_grow_subscriber_list_of_other_signals() {
+        let width = Signal::new(10.0);
+        let height = Signal::new(10.0);
+        let mut elements = ElementTree::new();
+        let widget = || {
+            let (width, height) = (width.clone(), height.clone());
+            Reactive::new(move || {
+                Box::new(Container {
+                    width: Some(width.get()),
+                    height: Some(height.get()),
+                    ..Container::default()
+                })
+            })
+        };
+
+        for i in 0..100u8 {
+            height.set(f32::from(i));
+            let _ = layout(&widget(), &mut elements);
+        }
+        assert_eq!(1, subscriber_count(&width));
+        assert_eq!(1, subscriber_count(&height));
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed_when_tracking() {
+        let a = Signal::new(0);
+        for _ in 0..10 {
+            let a = a.clone();
+            let _effect = Effect::new(move || {
+                let _ = a.get();
+            });
+        }
+        let _effect = {
+            let a = a.clone();
+            Effect::new(move || {
+                let _ = a.get();
+            })
+        };
+        assert_eq!(1, subscriber_count(&a));
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn subscriber_count<T>(signal: &Signal<T>) -> usize {
+        signal.inner.subscribers.subscriptions.borrow().len()
+    }
+
+    fn layout(widget: &dyn Layout, elements: &mut ElementTree) -> LayoutTree {
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        LayoutTree::from_root_with_elements(widget, &constraints, elements)
+    }
+
+    fn sized_box(width: &Signal<f32>, builds: &Rc<Cell<u32>>) -> Reactive {
+        let (width, builds) = (width.clone(), Rc::clone(builds));
+        Reactive::new(move || {
+            builds.set(builds.get() + 1);
+            Box::new(Container {
+                width: Some(width.get()),
+                height: Some(10.0),
+                ..Container::default()
+            })
+        })
+    }
+
+    fn root_size(tree: &LayoutTree) -> Vector2 {
+        let root = tree.root.and_then(|root| tree.get(root)).unwrap();
+        root.bounds.size()
+    }
+}
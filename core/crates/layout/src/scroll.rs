@@ -0,0 +1,738 @@
+use crate::element::Key;
+use crate::event::EventStatus;
+use crate::flex::Axis;
+use crate::pointer::{PointerEvent, PointerEventKind, PointerHandler};
+use crate::reactive::Subscribers;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::{Rect, Vector2};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+// How quickly a fling slows down. The velocity shrinks by a factor of e every
+// `1 / FRICTION` seconds, so a fling travels `velocity / FRICTION` pixels.
+const FRICTION: f32 = 4.0;
+
+// A fling stops once it is slower than this, in pixels per second.
+const MIN_FLING_VELOCITY: f32 = 20.0;
+
+// The fling velocity is measured over the pointer events during this many
+// milliseconds before the pointer was released.
+const VELOCITY_WINDOW: f32 = 100.0;
+
+/// Reads and sets the scroll offset of a `ScrollView`.
+///
+/// It is cheap to clone. Keep one in the app state and pass it to a
+/// `ScrollView` to control the view from outside; otherwise the view creates
+/// its own. Like a `Signal`, reading the offset inside a `Reactive` widget
+/// makes it a dependency, so scrolling lays the widget out again.
+#[derive(Clone, Default, Debug)]
+pub struct ScrollController {
+    inner: Rc<ScrollInner>,
+}
+
+#[derive(Default, Debug)]
+struct ScrollInner {
+    position: RefCell<ScrollPosition>,
+    subscribers: Subscribers,
+}
+
+#[derive(Default, Debug)]
+struct ScrollPosition {
+    offset: f32,
+    // The largest offset from the last layout, or `None` before the first one
+    max_offset: Option<f32>,
+    drag: Option<Drag>,
+    // The speed of a fling in pixels per second, and the frame time it was
+    // last advanced to
+    velocity: f32,
+    last_time: Option<f32>,
+}
+
+#[derive(Debug)]
+struct Drag {
+    pointer_id: i32,
+    // Where the pointer was along the scroll axis at the last event
+    last_position: f32,
+    // The time and offset after each pointer event of the drag, oldest first
+    samples: VecDeque<(f32, f32)>,
+}
+
+impl Drag {
+    // Record the offset at `time`, and forget the samples that are too old to
+    // count towards the velocity.
+    fn sample(&mut self, time: f32, offset: f32) {
+        self.samples.push_back((time, offset));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(sample_time, _)| *sample_time < time - VELOCITY_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl ScrollController {
+    #[must_use]
+    pub fn new() -> ScrollController {
+        ScrollController::default()
+    }
+
+    /// How far the content is scrolled from its start, in pixels.
+    #[must_use]
+    pub fn offset(&self) -> f32 {
+        self.inner.subscribers.track();
+        self.inner.position.borrow().offset
+    }
+
+    /// The largest offset the content can be scrolled to, which is how much
+    /// longer it is than the viewport. This is 0 until the view is laid out.
+    #[must_use]
+    pub fn max_offset(&self) -> f32 {
+        self.inner.subscribers.track();
+        self.inner.position.borrow().max_offset.unwrap_or(0.0)
+    }
+
+    /// Whether the content is being dragged or is still moving after a fling.
+    #[must_use]
+    pub fn is_scrolling(&self) -> bool {
+        let position = self.inner.position.borrow();
+        position.drag.is_some() || position.velocity != 0.0
+    }
+
+    /// Jump to `offset`, stopping any fling. The offset is clamped to the
+    /// content, once the view has been laid out.
+    pub fn set_offset(&self, offset: f32) {
+        {
+            let mut position = self.inner.position.borrow_mut();
+            position.stop_fling();
+            position.offset = position.clamp(offset);
+        }
+        self.inner.subscribers.notify();
+    }
+
+    // Move the content by `delta` pixels. Returns whether it moved, which is
+    // false when it is already at the end it's moving towards.
+    fn scroll_by(&self, delta: f32) -> bool {
+        let moved = {
+            let mut position = self.inner.position.borrow_mut();
+            let offset = position.clamp(position.offset + delta);
+            let moved = (offset - position.offset).abs() > f32::EPSILON;
+            position.offset = offset;
+            moved
+        };
+        if moved {
+            self.inner.subscribers.notify();
+        }
+        moved
+    }
+
+    pub(crate) fn handle_pointer(&self, event: &PointerEvent, axis: Axis) -> EventStatus {
+        let pointer = main_axis(axis, event.position);
+        match event.kind {
+            PointerEventKind::Wheel => {
+                self.inner.position.borrow_mut().stop_fling();
+                // Ignored at the end of the content, so an outer scroll view
+                // can take over.
+                if self.scroll_by(main_axis(axis, event.scroll_delta)) {
+                    EventStatus::Handled
+                } else {
+                    EventStatus::Ignored
+                }
+            }
+            PointerEventKind::Down => {
+                // Touching the content catches a fling
+                let mut position = self.inner.position.borrow_mut();
+                position.stop_fling();
+                let mut drag = Drag {
+                    pointer_id: event.pointer_id,
+                    last_position: pointer,
+                    samples: VecDeque::new(),
+                };
+                drag.sample(event.time, position.offset);
+                position.drag = Some(drag);
+                EventStatus::Ignored
+            }
+            PointerEventKind::Move => {
+                let delta = {
+                    let mut position = self.inner.position.borrow_mut();
+                    match &mut position.drag {
+                        Some(drag) if drag.pointer_id == event.pointer_id => {
+                            let delta = drag.last_position - pointer;
+                            drag.last_position = pointer;
+                            delta
+                        }
+                        _ => return EventStatus::Ignored,
+                    }
+                };
+                let _ = self.scroll_by(delta);
+                let mut position = self.inner.position.borrow_mut();
+                let offset = position.offset;
+                if let Some(drag) = &mut position.drag {
+                    drag.sample(event.time, offset);
+                }
+                EventStatus::Handled
+            }
+            PointerEventKind::Up | PointerEventKind::Cancel => {
+                let mut position = self.inner.position.borrow_mut();
+                match position.drag.take() {
+                    Some(mut drag) if drag.pointer_id == event.pointer_id => {
+                        // A pointer that stopped before it was released
+                        // doesn't fling, because the samples from before the
+                        // pause are dropped.
+                        if event.kind == PointerEventKind::Up {
+                            drag.sample(event.time, position.offset);
+                            position.fling(&drag);
+                        }
+                    }
+                    drag => position.drag = drag,
+                }
+                EventStatus::Ignored
+            }
+        }
+    }
+
+    // Update the position for a frame at `time`, with the sizes from layout.
+    // Returns the offset to draw the content at, and whether it's still
+    // moving. This doesn't notify the subscribers, because they are being
+    // laid out.
+    pub(crate) fn layout(&self, time: f32, viewport: f32, content: f32) -> (f32, bool) {
+        self.inner.subscribers.track();
+        let mut position = self.inner.position.borrow_mut();
+        position.max_offset = Some((content - viewport).max(0.0));
+        position.offset = position.clamp(position.offset);
+        position.advance(time);
+        (position.offset, position.velocity != 0.0)
+    }
+
+    // Move to `offset` without notifying, when layout finds that the content
+    // before the viewport changed size. Returns the offset clamped to the new
+    // size of the content. A drag in progress is shifted by the same amount,
+    // so the jump doesn't count towards its velocity.
+    pub(crate) fn correct(&self, offset: f32, viewport: f32, content: f32) -> f32 {
+        let mut position = self.inner.position.borrow_mut();
+        position.max_offset = Some((content - viewport).max(0.0));
+        let corrected = position.clamp(offset);
+        let shift = corrected - position.offset;
+        position.offset = corrected;
+        if let Some(drag) = &mut position.drag {
+            for (_, offset) in &mut drag.samples {
+                *offset += shift;
+            }
+        }
+        corrected
+    }
+}
+
+impl ScrollPosition {
+    fn clamp(&self, offset: f32) -> f32 {
+        offset.clamp(0.0, self.max_offset.unwrap_or(f32::INFINITY))
+    }
+
+    fn stop_fling(&mut self) {
+        self.velocity = 0.0;
+        self.last_time = None;
+    }
+
+    // Keep moving at the speed the content was dragged at when it was
+    // released.
+    fn fling(&mut self, drag: &Drag) {
+        let (Some((first_time, first)), Some((last_time, last))) =
+            (drag.samples.front(), drag.samples.back())
+        else {
+            return;
+        };
+        if last_time <= first_time {
+            return;
+        }
+        let velocity = (last - first) / (last_time - first_time) * 1000.0;
+        if velocity.abs() >= MIN_FLING_VELOCITY {
+            self.velocity = velocity;
+            self.last_time = Some(*last_time);
+        }
+    }
+
+    fn advance(&mut self, time: f32) {
+        if self.velocity == 0.0 {
+            return;
+        }
+        let Some(last_time) = self.last_time.replace(time) else {
+            return;
+        };
+        // The exact solution for a velocity that decays exponentially, so the
+        // distance doesn't depend on the frame rate.
+        let dt = ((time - last_time) / 1000.0).max(0.0);
+        let decay = (-FRICTION * dt).exp();
+        let offset = self.offset + self.velocity * (1.0 - decay) / FRICTION;
+        self.velocity *= decay;
+        self.offset = self.clamp(offset);
+        // Hitting either end stops the fling instead of overscrolling
+        let max_offset = self.max_offset.unwrap_or(f32::INFINITY);
+        if offset <= 0.0 || offset >= max_offset || self.velocity.abs() < MIN_FLING_VELOCITY {
+            self.stop_fling();
+        }
+    }
+}
+
+/// Lays out its child with no limit on its size along `axis`, and shows the
+/// part of it that fits in the viewport, starting at the scroll offset.
+///
+/// The view fills its constraints along `axis`, and is as large as the child
+/// across it. The child is clipped to the view. The offset can be changed with
+/// the wheel, or by dragging the content, which keeps moving with momentum
+/// when it's released. It is always kept between the start and the end of the
+/// child.
+#[derive(Debug)]
+pub struct ScrollView {
+    pub key: Option<Key>,
+    pub axis: Axis,
+    pub controller: Option<ScrollController>,
+    pub child: Box<dyn Layout>,
+}
+
+impl ScrollView {
+    /// A vertical scroll view that keeps track of its own offset.
+    #[must_use]
+    pub fn new(child: Box<dyn Layout>) -> ScrollView {
+        ScrollView {
+            key: None,
+            axis: Axis::Vertical,
+            controller: None,
+            child,
+        }
+    }
+
+    #[must_use]
+    pub fn key<K: Into<Key>>(self, key: K) -> ScrollView {
+        ScrollView {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn axis(self, axis: Axis) -> ScrollView {
+        ScrollView { axis, ..self }
+    }
+
+    #[must_use]
+    pub fn controller(self, controller: ScrollController) -> ScrollView {
+        ScrollView {
+            controller: Some(controller),
+            ..self
+        }
+    }
+}
+
+impl Layout for ScrollView {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let state = tree.elements.enter(
+            TypeId::of::<ScrollView>(),
+            self.key.clone(),
+            ScrollController::new,
+        );
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.untracked().clone(),
+        };
+
+        let child_constraints = match self.axis {
+            Axis::Vertical => BoxConstraints {
+                min: Vector2::new(constraints.min.x, 0.0),
+                max: Vector2::new(constraints.max.x, f32::INFINITY),
+            },
+            Axis::Horizontal => BoxConstraints {
+                min: Vector2::new(0.0, constraints.min.y),
+                max: Vector2::new(f32::INFINITY, constraints.max.y),
+            },
+        };
+        let sbox = self.child.layout(tree, &child_constraints);
+        tree.elements.exit();
+
+        let content = main_axis(self.axis, sbox.size);
+        let (min, max) = (
+            main_axis(self.axis, constraints.min),
+            main_axis(self.axis, constraints.max),
+        );
+        let viewport = if max.is_finite() {
+            max
+        } else {
+            content.max(min)
+        };
+        let size = match self.axis {
+            Axis::Vertical => Vector2::new(sbox.size.x, viewport),
+            Axis::Horizontal => Vector2::new(viewport, sbox.size.y),
+        };
+
+        // Measuring can pass an unbounded viewport, which would clamp the
+        // offset to 0, so the position is only updated for the real layout.
+        let offset = if tree.elements.is_measuring() {
+            controller.offset()
+        } else {
+            let (offset, animating) = controller.layout(tree.elements.time(), viewport, content);
+            if animating {
+                tree.request_frame();
+            }
+            offset
+        };
+        let position = match self.axis {
+            Axis::Vertical => Vector2::new(0.0, -offset),
+            Axis::Horizontal => Vector2::new(-offset, 0.0),
+        };
+        let id = tree.insert(LayoutBox::from_child(sbox, position));
+
+        let axis = self.axis;
+        SizedLayoutBox {
+            size,
+            children: vec![id],
+            pointer_handler: Some(PointerHandler::new(move |event| {
+                controller.handle_pointer(event, axis)
+            })),
+            clip: Some(Rect::from_size(size)),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+fn main_axis(axis: Axis, vector: Vector2) -> f32 {
+    match axis {
+        Axis::Vertical => vector.y,
+        Axis::Horizontal => vector.x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use crate::element::ElementTree;
+    use crate::hit_test::PointerRouter;
+    use crate::reactive::Reactive;
+
+    #[test]
+    fn child_is_laid_out_with_unbounded_height() {
+        let tree = layout(
+            &ScrollView::new(content(300.0)),
+            0.0,
+            &mut ElementTree::new(),
+        );
+        assert_eq!(
+            "[0, 0, 100, 100] clip [0, 0, 100, 100]\n  [0, 0, 100, 300] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+    }
+
+    #[test]
+    fn child_is_positioned_by_offset() {
+        let controller = ScrollController::new();
+        controller.set_offset(50.0);
+        let view = ScrollView::new(content(300.0)).controller(controller.clone());
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+        assert_eq!(
+            "[0, 0, 100, 100] clip [0, 0, 100, 100]\n  [0, -50, 100, 250] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+    }
+
+    #[test]
+    fn horizontal_view_scrolls_along_x() {
+        let controller = ScrollController::new();
+        controller.set_offset(20.0);
+        let child = Container {
+            width: Some(300.0),
+            height: Some(10.0),
+            color: Color::red(),
+            ..Container::default()
+        };
+        let view = ScrollView::new(Box::new(child))
+            .axis(Axis::Horizontal)
+            .controller(controller);
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+        assert_eq!(
+            "[0, 0, 100, 10] clip [0, 0, 100, 10]\n  [-20, 0, 280, 10] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+    }
+
+    #[test]
+    fn offset_is_clamped_to_content() {
+        let controller = ScrollController::new();
+        controller.set_offset(500.0);
+        let view = ScrollView::new(content(300.0)).controller(controller.clone());
+        let _ = layout(&view, 0.0, &mut ElementTree::new());
+        assert_close(200.0, controller.offset());
+        assert_close(200.0, controller.max_offset());
+
+        controller.set_offset(-10.0);
+        assert_close(0.0, controller.offset());
+        controller.set_offset(250.0);
+        assert_close(200.0, controller.offset());
+    }
+
+    #[test]
+    fn short_content_does_not_scroll() {
+        let controller = ScrollController::new();
+        let view = ScrollView::new(content(50.0)).controller(controller.clone());
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+        controller.set_offset(20.0);
+        assert_close(0.0, controller.offset());
+        assert_eq!(
+            EventStatus::Ignored,
+            tree.dispatch_pointer_event(&wheel(20.0))
+        );
+    }
+
+    #[test]
+    fn offset_is_kept_between_frames_without_controller() {
+        let mut elements = ElementTree::new();
+        let tree = layout(&ScrollView::new(content(300.0)), 0.0, &mut elements);
+        let _ = tree.dispatch_pointer_event(&wheel(30.0));
+        let tree = layout(&ScrollView::new(content(300.0)), 16.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 100, 100] clip [0, 0, 100, 100]\n  [0, -30, 100, 270] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+    }
+
+    #[test]
+    fn wheel_scrolls_until_end_of_content() {
+        let controller = ScrollController::new();
+        let view = ScrollView::new(content(300.0)).controller(controller.clone());
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+
+        assert_eq!(
+            EventStatus::Handled,
+            tree.dispatch_pointer_event(&wheel(150.0))
+        );
+        assert_close(150.0, controller.offset());
+        assert_eq!(
+            EventStatus::Handled,
+            tree.dispatch_pointer_event(&wheel(150.0))
+        );
+        assert_close(200.0, controller.offset());
+        // Already at the end, so an outer view can handle it
+        assert_eq!(
+            EventStatus::Ignored,
+            tree.dispatch_pointer_event(&wheel(150.0))
+        );
+    }
+
+    #[test]
+    fn dragging_moves_content_with_pointer() {
+        let controller = ScrollController::new();
+        let view = ScrollView::new(content(300.0)).controller(controller.clone());
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+
+        let _ = tree.dispatch_pointer_event(&pointer(PointerEventKind::Down, 80.0));
+        let status = tree.dispatch_pointer_event(&pointer(PointerEventKind::Move, 50.0));
+        assert_eq!(EventStatus::Handled, status);
+        assert_close(30.0, controller.offset());
+        let _ = tree.dispatch_pointer_event(&pointer(PointerEventKind::Move, 60.0));
+        assert_close(20.0, controller.offset());
+        assert!(controller.is_scrolling());
+
+        let _ = tree.dispatch_pointer_event(&pointer(PointerEventKind::Up, 60.0));
+        let status = tree.dispatch_pointer_event(&pointer(PointerEventKind::Move, 0.0));
+        assert_eq!(EventStatus::Ignored, status);
+        assert_close(20.0, controller.offset());
+        assert!(!controller.is_scrolling());
+    }
+
+    #[test]
+    fn drag_released_outside_view_ends() {
+        let controller = ScrollController::new();
+        let view = Container {
+            height: Some(50.0),
+            child: Some(Box::new(
+                ScrollView::new(content(300.0)).controller(controller.clone()),
+            )),
+            ..Container::default()
+        };
+        let tree = layout(&view, 0.0, &mut ElementTree::new());
+        let mut router = PointerRouter::new();
+
+        let _ = router.dispatch(&tree, &pointer(PointerEventKind::Down, 40.0));
+        let _ = router.dispatch(&tree, &pointer(PointerEventKind::Move, 20.0));
+        let _ = router.dispatch(&tree, &pointer(PointerEventKind::Up, 80.0));
+        assert!(!controller.is_scrolling());
+        let offset = controller.offset();
+
+        // Hovering over the view afterwards doesn't drag it
+        let _ = router.dispatch(&tree, &pointer(PointerEventKind::Move, 10.0));
+        assert_close(offset, controller.offset());
+    }
+
+    #[test]
+    fn released_drag_keeps_moving_with_momentum() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || ScrollView::new(content(1000.0)).controller(controller.clone());
+        drag(&view, &mut elements, 10.0);
+        assert_close(30.0, controller.offset());
+
+        // Dragged at 10 pixels per 16 milliseconds
+        let _ = layout(&view(), 64.0, &mut elements);
+        assert!(controller.offset() > 30.0);
+        assert!(elements.is_animating());
+
+        let mut time = 64.0;
+        while elements.is_animating() && time < 5000.0 {
+            time += 16.0;
+            let _ = layout(&view(), time, &mut elements);
+        }
+        assert!(!elements.is_animating());
+        assert!(!controller.is_scrolling());
+        // A fling travels its velocity divided by the friction, less the
+        // part that is too slow to keep going.
+        let velocity = 10.0 / 16.0 * 1000.0;
+        let expected = 30.0 + (velocity - MIN_FLING_VELOCITY) / FRICTION;
+        assert!((controller.offset() - expected).abs() < 1.0);
+    }
+
+    #[test]
+    fn drag_held_still_before_release_does_not_fling() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || ScrollView::new(content(1000.0)).controller(controller.clone());
+        // No frames are laid out during the pause, so only the time of the
+        // release shows that the pointer stopped.
+        drag_and_release_at(&view, &mut elements, 10.0, 48.0 + VELOCITY_WINDOW * 2.0);
+        assert!(!controller.is_scrolling());
+
+        let _ = layout(&view(), 400.0, &mut elements);
+        assert_close(30.0, controller.offset());
+        assert!(!elements.is_animating());
+    }
+
+    #[test]
+    fn fling_uses_pointer_events_between_frames() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || ScrollView::new(content(1000.0)).controller(controller.clone());
+        let tree = layout(&view(), 0.0, &mut elements);
+
+        // Several moves arrive before the next frame
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Down, 90.0, 0.0));
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Move, 80.0, 8.0));
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Move, 70.0, 16.0));
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Up, 70.0, 16.0));
+        assert!(controller.is_scrolling());
+
+        let _ = layout(&view(), 32.0, &mut elements);
+        assert!(controller.offset() > 20.0);
+        assert!(elements.is_animating());
+    }
+
+    #[test]
+    fn fling_stops_at_end_of_content() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || ScrollView::new(content(150.0)).controller(controller.clone());
+        drag(&view, &mut elements, 10.0);
+
+        let _ = layout(&view(), 500.0, &mut elements);
+        assert_close(50.0, controller.offset());
+        assert!(!elements.is_animating());
+    }
+
+    #[test]
+    fn touching_content_stops_fling() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || ScrollView::new(content(1000.0)).controller(controller.clone());
+        drag(&view, &mut elements, 10.0);
+
+        let tree = layout(&view(), 64.0, &mut elements);
+        let _ = tree.dispatch_pointer_event(&pointer(PointerEventKind::Down, 50.0));
+        let offset = controller.offset();
+        let _ = layout(&view(), 80.0, &mut elements);
+        assert_close(offset, controller.offset());
+        assert!(!elements.is_animating());
+    }
+
+    #[test]
+    fn setting_offset_lays_out_cached_reactive_again() {
+        let controller = ScrollController::new();
+        let mut elements = ElementTree::new();
+        let view = || {
+            let controller = controller.clone();
+            Reactive::new(move || {
+                Box::new(ScrollView::new(content(300.0)).controller(controller.clone()))
+            })
+        };
+        let _ = layout(&view(), 0.0, &mut elements);
+        controller.set_offset(40.0);
+        let tree = layout(&view(), 16.0, &mut elements);
+        assert_eq!(
+            "[0, 0, 100, 100] clip [0, 0, 100, 100]\n  [0, -40, 100, 260] fill rgba(255, 0, 0, 255)\n",
+            tree.to_string()
+        );
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn layout(widget: &dyn Layout, time: f32, elements: &mut ElementTree) -> LayoutTree {
+        let constraints = BoxConstraints::from_max(Vector2::new(100.0, 100.0));
+        elements.set_time(time);
+        LayoutTree::from_root_with_elements(widget, &constraints, elements)
+    }
+
+    fn content(height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+
+    // Drag the content up by `step` pixels each frame, with a frame every 16
+    // milliseconds, for 3 frames, then release it.
+    fn drag(view: &dyn Fn() -> ScrollView, elements: &mut ElementTree, step: f32) {
+        drag_and_release_at(view, elements, step, 48.0);
+    }
+
+    // Like `drag`, but the pointer is held still until `release_time`.
+    fn drag_and_release_at(
+        view: &dyn Fn() -> ScrollView,
+        elements: &mut ElementTree,
+        step: f32,
+        release_time: f32,
+    ) {
+        let mut tree = layout(&view(), 0.0, elements);
+        let mut y = 90.0;
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Down, y, 0.0));
+        for time in [16.0, 32.0, 48.0] {
+            y -= step;
+            let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Move, y, time));
+            tree = layout(&view(), time, elements);
+        }
+        let _ = tree.dispatch_pointer_event(&pointer_at(PointerEventKind::Up, y, release_time));
+    }
+
+    fn assert_close(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn pointer(kind: PointerEventKind, y: f32) -> PointerEvent {
+        PointerEvent::new(kind, 0, Vector2::new(50.0, y))
+    }
+
+    fn pointer_at(kind: PointerEventKind, y: f32, time: f32) -> PointerEvent {
+        PointerEvent {
+            time,
+            ..pointer(kind, y)
+        }
+    }
+
+    fn wheel(delta: f32) -> PointerEvent {
+        PointerEvent::wheel(0, Vector2::new(50.0, 50.0), Vector2::new(0.0, delta))
+    }
+}
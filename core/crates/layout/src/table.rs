@@ -0,0 +1,861 @@
+use crate::decoration::{BorderSide, Borders, Material};
+use crate::flex::CrossAxisAlignment;
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutBoxId, LayoutTree, SizedLayoutBox};
+use math::{Rect, Vector2};
+
+/// How the width of a `Table` column is decided.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum TableColumnWidth {
+    // Exactly this many pixels
+    Fixed(f32),
+    // A share of the width left over once the other columns are sized,
+    // relative to the other flex columns. When the table has an unbounded
+    // width, this behaves like `Intrinsic`.
+    Flex(f32),
+    // The width of the widest cell in the column
+    Intrinsic,
+    // This fraction of the width available to the columns, or nothing when
+    // the table has an unbounded width
+    Fraction(f32),
+}
+
+impl Default for TableColumnWidth {
+    fn default() -> TableColumnWidth {
+        TableColumnWidth::Flex(1.0)
+    }
+}
+
+/// A column of a `Table`. Its `borders` are drawn on its left and right edges
+/// in every row, and on its top and bottom edges at the top and bottom of the
+/// table.
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+pub struct TableColumn {
+    pub width: TableColumnWidth,
+    pub borders: Borders,
+}
+
+impl TableColumn {
+    #[must_use]
+    pub fn new(width: TableColumnWidth) -> TableColumn {
+        TableColumn {
+            width,
+            borders: Borders::none(),
+        }
+    }
+
+    #[must_use]
+    pub fn borders(self, borders: Borders) -> TableColumn {
+        TableColumn { borders, ..self }
+    }
+}
+
+/// A row of a `Table`. Its `borders` are drawn on its top and bottom edges in
+/// every column, and on its left and right edges at the sides of the table.
+#[derive(Debug, Default)]
+pub struct TableRow {
+    pub cells: Vec<Box<dyn Layout>>,
+    pub borders: Borders,
+}
+
+impl TableRow {
+    #[must_use]
+    pub fn new(cells: Vec<Box<dyn Layout>>) -> TableRow {
+        TableRow {
+            cells,
+            borders: Borders::none(),
+        }
+    }
+
+    #[must_use]
+    pub fn borders(self, borders: Borders) -> TableRow {
+        TableRow { borders, ..self }
+    }
+}
+
+/// Lays out rows of cells that share their column widths.
+///
+/// Each row is as tall as its tallest cell, and `vertical_alignment` positions
+/// the cells that are shorter. Every cell is given its own box that clips its
+/// child, so hit testing and clipping work per cell. Columns past the end of
+/// `columns` use `default_column_width`.
+///
+/// Borders sit in the space between cells, and each edge is only drawn once.
+/// When two borders meet on the same edge, the border of the row or column
+/// that comes first wins. Row borders win over column borders on horizontal
+/// edges, and column borders win over row borders on vertical edges.
+/// Horizontal borders are drawn across the corners where edges cross.
+#[derive(Debug)]
+pub struct Table {
+    pub columns: Vec<TableColumn>,
+    pub default_column_width: TableColumnWidth,
+    pub vertical_alignment: CrossAxisAlignment,
+    pub rows: Vec<TableRow>,
+}
+
+impl Default for Table {
+    fn default() -> Table {
+        Table {
+            columns: vec![],
+            default_column_width: TableColumnWidth::default(),
+            vertical_alignment: CrossAxisAlignment::Start,
+            rows: vec![],
+        }
+    }
+}
+
+impl Layout for Table {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let column_count = self
+            .rows
+            .iter()
+            .map(|row| row.cells.len())
+            .fold(self.columns.len(), usize::max);
+        let columns: Vec<TableColumn> = (0..column_count)
+            .map(|i| match self.columns.get(i) {
+                Some(column) => *column,
+                None => TableColumn::new(self.default_column_width),
+            })
+            .collect();
+
+        // The thickness of the edges between the columns and rows, including
+        // the outside edges of the table
+        let vertical_edges: Vec<f32> = (0..=column_count)
+            .map(|i| {
+                self.vertical_edge(&columns, i)
+                    .into_iter()
+                    .fold(0.0, max_width)
+            })
+            .collect();
+        let horizontal_edges: Vec<f32> = (0..=self.rows.len())
+            .map(|i| {
+                self.horizontal_edge(&columns, i)
+                    .into_iter()
+                    .fold(0.0, max_width)
+            })
+            .collect();
+
+        let available = constraints.max.x - vertical_edges.iter().sum::<f32>();
+        let widths = self.column_widths(tree, &columns, available);
+        let column_starts = starts(&widths, &vertical_edges);
+
+        let mut cells = vec![];
+        let mut heights = vec![];
+        for row in &self.rows {
+            let height = row_height(tree, row, &widths);
+            let row_cells: Vec<LayoutBoxId> = row
+                .cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let size = Vector2::new(widths[i], height);
+                    let sbox = self.layout_cell(tree, cell.as_ref(), size);
+                    tree.insert(LayoutBox::from_child(sbox, (column_starts[i], 0.0)))
+                })
+                .collect();
+            cells.push(row_cells);
+            heights.push(height);
+        }
+        let row_starts = starts(&heights, &horizontal_edges);
+        let size = Vector2::new(
+            widths.iter().sum::<f32>() + vertical_edges.iter().sum::<f32>(),
+            heights.iter().sum::<f32>() + horizontal_edges.iter().sum::<f32>(),
+        );
+
+        // Each row gets a box across the whole table
+        let mut children = vec![];
+        for ((cells, height), y) in cells.into_iter().zip(heights.iter()).zip(&row_starts) {
+            let row_box = SizedLayoutBox {
+                size: Vector2::new(size.x, *height),
+                children: cells,
+                ..SizedLayoutBox::default()
+            };
+            children.push(tree.insert(LayoutBox::from_child(row_box, (0.0, *y))));
+        }
+
+        // Horizontal borders cover the edge to their right, and the first one
+        // also covers the left edge of the table, so the corners are filled.
+        for (i, edge) in horizontal_edges.iter().enumerate() {
+            let y = row_starts.get(i).copied().unwrap_or(size.y) - edge;
+            for (column, side) in self.horizontal_edge(&columns, i).iter().enumerate() {
+                if let Some(side) = side {
+                    let min_x = if column == 0 {
+                        0.0
+                    } else {
+                        column_starts[column]
+                    };
+                    let max_x = column_starts[column] + widths[column] + vertical_edges[column + 1];
+                    let rect = Rect::new((min_x, y).into(), (max_x, y + side.width).into());
+                    children.push(insert_line(tree, rect, *side));
+                }
+            }
+        }
+        for (i, edge) in vertical_edges.iter().enumerate() {
+            let x = column_starts.get(i).copied().unwrap_or(size.x) - edge;
+            for (row, side) in self.vertical_edge(&columns, i).iter().enumerate() {
+                if let Some(side) = side {
+                    let min = Vector2::new(x, row_starts[row]);
+                    let rect = Rect::new(min, min + Vector2::new(side.width, heights[row]));
+                    children.push(insert_line(tree, rect, *side));
+                }
+            }
+        }
+
+        SizedLayoutBox {
+            size: size.clamp_between(constraints.min, constraints.max),
+            children,
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+impl Table {
+    // The border of each row along the vertical edge before column `index`.
+    fn vertical_edge(&self, columns: &[TableColumn], index: usize) -> Vec<Option<BorderSide>> {
+        let before = index.checked_sub(1).and_then(|i| columns.get(i));
+        let after = columns.get(index);
+        let column = before
+            .and_then(|column| column.borders.right)
+            .or_else(|| after.and_then(|column| column.borders.left));
+        self.rows
+            .iter()
+            .map(|row| {
+                column.or(if index == 0 {
+                    row.borders.left
+                } else if index == columns.len() {
+                    row.borders.right
+                } else {
+                    None
+                })
+            })
+            .collect()
+    }
+
+    // The border of each column along the horizontal edge before row `index`.
+    fn horizontal_edge(&self, columns: &[TableColumn], index: usize) -> Vec<Option<BorderSide>> {
+        let before = index.checked_sub(1).and_then(|i| self.rows.get(i));
+        let after = self.rows.get(index);
+        let row = before
+            .and_then(|row| row.borders.bottom)
+            .or_else(|| after.and_then(|row| row.borders.top));
+        columns
+            .iter()
+            .map(|column| {
+                row.or(if index == 0 {
+                    column.borders.top
+                } else if index == self.rows.len() {
+                    column.borders.bottom
+                } else {
+                    None
+                })
+            })
+            .collect()
+    }
+
+    fn column_widths(
+        &self,
+        tree: &mut LayoutTree,
+        columns: &[TableColumn],
+        available: f32,
+    ) -> Vec<f32> {
+        let mut widths = vec![0.0; columns.len()];
+        let mut flex_columns = vec![];
+        for (i, column) in columns.iter().enumerate() {
+            widths[i] = match column.width {
+                TableColumnWidth::Fixed(width) => width,
+                TableColumnWidth::Fraction(fraction) if available.is_finite() => {
+                    fraction * available
+                }
+                TableColumnWidth::Fraction(_) => 0.0,
+                TableColumnWidth::Flex(flex) if available.is_finite() => {
+                    flex_columns.push((i, flex));
+                    0.0
+                }
+                TableColumnWidth::Flex(_) | TableColumnWidth::Intrinsic => {
+                    self.intrinsic_width(tree, i)
+                }
+            };
+        }
+
+        let sum_flex: f32 = flex_columns.iter().map(|(_, flex)| flex).sum();
+        if sum_flex > 0.0 {
+            let free_space = (available - widths.iter().sum::<f32>()).max(0.0);
+            for (i, flex) in flex_columns {
+                widths[i] = free_space * flex / sum_flex;
+            }
+        }
+        widths
+    }
+
+    // The width of the widest cell in column `index`.
+    fn intrinsic_width(&self, tree: &mut LayoutTree, index: usize) -> f32 {
+        let unbounded = BoxConstraints::from_max((f32::INFINITY, f32::INFINITY));
+        self.rows
+            .iter()
+            .filter_map(|row| row.cells.get(index))
+            .map(|cell| tree.measure(cell.as_ref(), &unbounded).x)
+            .fold(0.0, f32::max)
+    }
+
+    // Lay out the box of a cell, which clips the cell to its column and row.
+    fn layout_cell(
+        &self,
+        tree: &mut LayoutTree,
+        cell: &dyn Layout,
+        size: Vector2,
+    ) -> SizedLayoutBox {
+        let min_height = match self.vertical_alignment {
+            CrossAxisAlignment::Stretch => size.y,
+            _ => 0.0,
+        };
+        let constraints = BoxConstraints {
+            min: Vector2::new(size.x, min_height),
+            max: size,
+        };
+        let sbox = cell.layout(tree, &constraints);
+        let y = match self.vertical_alignment {
+            CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => 0.0,
+            CrossAxisAlignment::End => size.y - sbox.size.y,
+            CrossAxisAlignment::Center => (size.y - sbox.size.y) / 2.0,
+        };
+        let child = tree.insert(LayoutBox::from_child(sbox, (0.0, y)));
+        SizedLayoutBox {
+            size,
+            children: vec![child],
+            clip: Some(Rect::from_size(size)),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+// The height of the tallest cell in a row, at the width of its column.
+fn row_height(tree: &mut LayoutTree, row: &TableRow, widths: &[f32]) -> f32 {
+    row.cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| {
+            let constraints = BoxConstraints {
+                min: Vector2::new(*width, 0.0),
+                max: Vector2::new(*width, f32::INFINITY),
+            };
+            tree.measure(cell.as_ref(), &constraints).y
+        })
+        .fold(0.0, f32::max)
+}
+
+fn max_width(width: f32, side: Option<BorderSide>) -> f32 {
+    side.map_or(width, |side| width.max(side.width))
+}
+
+// Where each track starts, after the edge before it.
+fn starts(sizes: &[f32], edges: &[f32]) -> Vec<f32> {
+    let mut start = 0.0;
+    sizes
+        .iter()
+        .zip(edges)
+        .map(|(size, edge)| {
+            start += edge;
+            let track = start;
+            start += size;
+            track
+        })
+        .collect()
+}
+
+fn insert_line(tree: &mut LayoutTree, rect: Rect, side: BorderSide) -> LayoutBoxId {
+    let sbox = SizedLayoutBox {
+        size: rect.size(),
+        material: Some(Material::filled(side.color)),
+        ..SizedLayoutBox::default()
+    };
+    tree.insert(LayoutBox::from_child(sbox, rect.min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animated_container::AnimatedContainer;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use crate::element::{ElementTree, State, StatefulWidget};
+    use crate::reactive::{Reactive, Signal};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_util::assert_snapshot_eq;
+
+    // --------------------------------------------------
+    // Column widths
+    // --------------------------------------------------
+
+    #[test]
+    fn fixed_columns_are_shared_by_every_row() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Fixed(20.0)),
+                TableColumn::new(TableColumnWidth::Fixed(30.0)),
+            ],
+            rows: vec![
+                TableRow::new(vec![cell(10.0), cell(5.0)]),
+                TableRow::new(vec![cell(8.0), cell(8.0)]),
+            ],
+            ..Table::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 18]
+              [0, 0, 50, 10]
+                [0, 0, 20, 10] clip [0, 0, 20, 10]
+                  [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+                [20, 0, 50, 10] clip [20, 0, 50, 10]
+                  [20, 0, 50, 5] fill rgba(255, 0, 0, 255)
+              [0, 10, 50, 18]
+                [0, 10, 20, 18] clip [0, 10, 20, 18]
+                  [0, 10, 20, 18] fill rgba(255, 0, 0, 255)
+                [20, 10, 50, 18] clip [20, 10, 50, 18]
+                  [20, 10, 50, 18] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&table, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn flex_columns_share_the_leftover_width() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Fixed(20.0)),
+                TableColumn::new(TableColumnWidth::Flex(1.0)),
+                TableColumn::new(TableColumnWidth::Flex(3.0)),
+            ],
+            rows: vec![TableRow::new(vec![cell(10.0), cell(10.0), cell(10.0)])],
+            ..Table::default()
+        };
+        assert_eq!(vec![20.0, 20.0, 60.0], cell_widths(&table, 100.0));
+    }
+
+    #[test]
+    fn intrinsic_column_fits_its_widest_cell() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Intrinsic),
+                TableColumn::new(TableColumnWidth::Flex(1.0)),
+            ],
+            rows: vec![
+                TableRow::new(vec![sized(15.0, 10.0), cell(10.0)]),
+                TableRow::new(vec![sized(25.0, 10.0), cell(10.0)]),
+            ],
+            ..Table::default()
+        };
+        assert_eq!(vec![25.0, 75.0, 25.0, 75.0], cell_widths(&table, 100.0));
+    }
+
+    #[test]
+    fn stateful_cell_in_intrinsic_column_is_built_once_per_frame() {
+        let builds = Rc::new(Cell::new(0));
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Intrinsic)],
+            rows: vec![TableRow::new(vec![Box::new(Built(Rc::clone(&builds)))])],
+            ..Table::default()
+        };
+        let mut elements = ElementTree::new();
+        for time in [0.0, 100.0] {
+            let _ = frame(&table, time, &mut elements);
+        }
+        assert_eq!(2, builds.get());
+    }
+
+    #[test]
+    fn animated_cell_in_intrinsic_column_animates_once_per_frame() {
+        let width = Signal::new(10.0);
+        let layouts = Rc::new(Cell::new(0));
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Intrinsic)],
+            rows: vec![TableRow::new(vec![animated(&width, &layouts)])],
+            ..Table::default()
+        };
+        let mut elements = ElementTree::new();
+        let _ = frame(&table, 0.0, &mut elements);
+        width.set(30.0);
+        let widths: Vec<f32> = [100.0, 150.0, 200.0]
+            .into_iter()
+            .map(|time| frame(&table, time, &mut elements).x)
+            .collect();
+        assert_eq!(vec![10.0, 20.0, 30.0], widths);
+        assert!(!elements.is_animating());
+
+        // The layout is reused from the last frame, so the cell is only laid
+        // out again to measure its width and its height
+        layouts.set(0);
+        let _ = frame(&table, 250.0, &mut elements);
+        assert_eq!(2, layouts.get());
+    }
+
+    #[test]
+    fn fraction_columns_are_a_fraction_of_the_table() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Fraction(0.25)),
+                TableColumn::new(TableColumnWidth::Fraction(0.5)),
+            ],
+            rows: vec![TableRow::new(vec![cell(10.0), cell(10.0)])],
+            ..Table::default()
+        };
+        assert_eq!(vec![25.0, 50.0], cell_widths(&table, 100.0));
+        let tree = LayoutTree::from_root(&table, &BoxConstraints::from_max((100.0, 100.0)));
+        assert_eq!(Vector2::new(75.0, 10.0), root_size(&tree));
+    }
+
+    #[test]
+    fn flex_columns_are_intrinsic_without_a_maximum_width() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Flex(1.0)),
+                TableColumn::new(TableColumnWidth::Fraction(0.5)),
+                TableColumn::new(TableColumnWidth::Fixed(10.0)),
+            ],
+            rows: vec![TableRow::new(vec![
+                sized(30.0, 10.0),
+                sized(30.0, 10.0),
+                sized(30.0, 10.0),
+            ])],
+            ..Table::default()
+        };
+        assert_eq!(vec![30.0, 0.0, 10.0], cell_widths(&table, f32::INFINITY));
+    }
+
+    #[test]
+    fn columns_past_the_end_use_the_default_width() {
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Fixed(40.0))],
+            default_column_width: TableColumnWidth::Flex(1.0),
+            rows: vec![TableRow::new(vec![cell(10.0), cell(10.0), cell(10.0)])],
+            ..Table::default()
+        };
+        assert_eq!(vec![40.0, 30.0, 30.0], cell_widths(&table, 100.0));
+    }
+
+    #[test]
+    fn rows_can_have_fewer_cells_than_columns() {
+        let table = Table {
+            rows: vec![
+                TableRow::new(vec![cell(10.0), cell(10.0)]),
+                TableRow::new(vec![cell(5.0)]),
+            ],
+            ..Table::default()
+        };
+        assert_eq!(vec![50.0, 50.0, 50.0], cell_widths(&table, 100.0));
+        let tree = LayoutTree::from_root(&table, &BoxConstraints::from_max((100.0, 100.0)));
+        assert_eq!(Vector2::new(100.0, 15.0), root_size(&tree));
+    }
+
+    // --------------------------------------------------
+    // Row heights
+    // --------------------------------------------------
+
+    #[test]
+    fn cells_are_aligned_to_the_top_by_default() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 20] clip [0, 0, 20, 20]
+              [0, 0, 20, 20] fill rgba(255, 0, 0, 255)
+            [20, 0, 40, 20] clip [20, 0, 40, 20]
+              [20, 0, 40, 8] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned_cells(CrossAxisAlignment::Start),
+        );
+    }
+
+    #[test]
+    fn cells_are_aligned_to_the_bottom() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 20] clip [0, 0, 20, 20]
+              [0, 0, 20, 20] fill rgba(255, 0, 0, 255)
+            [20, 0, 40, 20] clip [20, 0, 40, 20]
+              [20, 12, 40, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned_cells(CrossAxisAlignment::End),
+        );
+    }
+
+    #[test]
+    fn cells_are_aligned_to_the_center() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 20] clip [0, 0, 20, 20]
+              [0, 0, 20, 20] fill rgba(255, 0, 0, 255)
+            [20, 0, 40, 20] clip [20, 0, 40, 20]
+              [20, 6, 40, 14] fill rgba(255, 0, 0, 255)
+            ",
+            &aligned_cells(CrossAxisAlignment::Center),
+        );
+    }
+
+    #[test]
+    fn cells_are_stretched_to_the_row_height() {
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 20] clip [0, 0, 20, 20]
+              [0, 0, 20, 20] fill rgba(255, 0, 0, 255)
+            [20, 0, 40, 20] clip [20, 0, 40, 20]
+              [20, 0, 40, 20] fill rgba(0, 255, 0, 255)
+            ",
+            &aligned_cells(CrossAxisAlignment::Stretch),
+        );
+    }
+
+    // --------------------------------------------------
+    // Borders
+    // --------------------------------------------------
+
+    #[test]
+    fn row_borders_are_drawn_once_between_rows() {
+        // Both rows set a border on the edge between them, and only the first
+        // row's border is drawn
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Fixed(20.0))],
+            rows: vec![
+                TableRow::new(vec![cell(10.0)]).borders(Borders::bottom(Color::blue(), 2.0)),
+                TableRow::new(vec![cell(10.0)]).borders(Borders::top(Color::green(), 1.0)),
+            ],
+            ..Table::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 22]
+              [0, 0, 20, 10]
+                [0, 0, 20, 10] clip [0, 0, 20, 10]
+                  [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [0, 12, 20, 22]
+                [0, 12, 20, 22] clip [0, 12, 20, 22]
+                  [0, 12, 20, 22] fill rgba(255, 0, 0, 255)
+              [0, 10, 20, 12] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&table, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn column_borders_are_drawn_once_between_columns() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Fixed(10.0))
+                    .borders(Borders::right(Color::blue(), 2.0)),
+                TableColumn::new(TableColumnWidth::Fixed(10.0))
+                    .borders(Borders::left(Color::green(), 1.0)),
+            ],
+            rows: vec![
+                TableRow::new(vec![cell(5.0), cell(5.0)]),
+                TableRow::new(vec![cell(5.0), cell(5.0)]),
+            ],
+            ..Table::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 22, 10]
+              [0, 0, 22, 5]
+                [0, 0, 10, 5] clip [0, 0, 10, 5]
+                  [0, 0, 10, 5] fill rgba(255, 0, 0, 255)
+                [12, 0, 22, 5] clip [12, 0, 22, 5]
+                  [12, 0, 22, 5] fill rgba(255, 0, 0, 255)
+              [0, 5, 22, 10]
+                [0, 5, 10, 10] clip [0, 5, 10, 10]
+                  [0, 5, 10, 10] fill rgba(255, 0, 0, 255)
+                [12, 5, 22, 10] clip [12, 5, 22, 10]
+                  [12, 5, 22, 10] fill rgba(255, 0, 0, 255)
+              [10, 0, 12, 5] fill rgba(0, 0, 255, 255)
+              [10, 5, 12, 10] fill rgba(0, 0, 255, 255)
+            ",
+            &dump(&table, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn outside_borders_surround_the_table() {
+        // The rows draw the left and right edges, and the columns draw the top
+        // and bottom edges. The top and bottom borders also fill the corners.
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Fixed(10.0)).borders(Borders {
+                    top: Some(BorderSide::new(Color::blue(), 1.0)),
+                    bottom: Some(BorderSide::new(Color::blue(), 1.0)),
+                    ..Borders::none()
+                }),
+            ],
+            rows: vec![TableRow::new(vec![cell(10.0)]).borders(Borders {
+                left: Some(BorderSide::new(Color::green(), 2.0)),
+                right: Some(BorderSide::new(Color::green(), 2.0)),
+                ..Borders::none()
+            })],
+            ..Table::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 14, 12]
+              [0, 1, 14, 11]
+                [2, 1, 12, 11] clip [2, 1, 12, 11]
+                  [2, 1, 12, 11] fill rgba(255, 0, 0, 255)
+              [0, 0, 14, 1] fill rgba(0, 0, 255, 255)
+              [0, 11, 14, 12] fill rgba(0, 0, 255, 255)
+              [0, 1, 2, 11] fill rgba(0, 255, 0, 255)
+              [12, 1, 14, 11] fill rgba(0, 255, 0, 255)
+            ",
+            &dump(&table, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn borders_take_space_from_flex_columns() {
+        let table = Table {
+            columns: vec![
+                TableColumn::new(TableColumnWidth::Flex(1.0))
+                    .borders(Borders::right(Color::blue(), 10.0)),
+                TableColumn::new(TableColumnWidth::Flex(1.0)),
+            ],
+            rows: vec![TableRow::new(vec![cell(10.0), cell(10.0)])],
+            ..Table::default()
+        };
+        assert_eq!(vec![45.0, 45.0], cell_widths(&table, 100.0));
+    }
+
+    // --------------------------------------------------
+    // Cells
+    // --------------------------------------------------
+
+    #[test]
+    fn each_cell_is_hit_tested_on_its_own() {
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Fixed(20.0)); 2],
+            rows: vec![
+                TableRow::new(vec![cell(10.0), cell(10.0)]),
+                TableRow::new(vec![cell(5.0), cell(10.0)]),
+            ],
+            ..Table::default()
+        };
+        let tree = LayoutTree::from_root(&table, &BoxConstraints::from_max((100.0, 100.0)));
+        let path = tree.hit_test(Vector2::new(10.0, 18.0));
+        // The bottom-left cell's child is only 5 pixels tall, so the cell is
+        // the deepest box under the point
+        assert_eq!(Rect::from_pos((0.0, 10.0), (20.0, 10.0)), path[0].bounds);
+        assert_eq!(3, path.len());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn dump(widget: &dyn Layout, max: Vector2) -> String {
+        LayoutTree::from_root(widget, &BoxConstraints::from_max(max)).to_string()
+    }
+
+    fn root_size(tree: &LayoutTree) -> Vector2 {
+        tree.get(tree.root.unwrap()).unwrap().bounds.size()
+    }
+
+    // The width of every cell, row by row
+    fn cell_widths(table: &Table, max_width: f32) -> Vec<f32> {
+        let constraints = BoxConstraints::from_max((max_width, f32::INFINITY));
+        let tree = LayoutTree::from_root(table, &constraints);
+        tree.iter()
+            .filter(|(_, lbox, _)| lbox.clip.is_some())
+            .map(|(_, lbox, _)| lbox.bounds.size().x)
+            .collect()
+    }
+
+    // The cells of a 20 pixel tall row, where the second cell is 8 pixels tall
+    // unless it is stretched
+    fn aligned_cells(vertical_alignment: CrossAxisAlignment) -> String {
+        let stretchable = Box::new(Container {
+            color: Color::green(),
+            ..Container::default()
+        });
+        let second = match vertical_alignment {
+            CrossAxisAlignment::Stretch => stretchable,
+            _ => cell(8.0),
+        };
+        let table = Table {
+            columns: vec![TableColumn::new(TableColumnWidth::Fixed(20.0)); 2],
+            vertical_alignment,
+            rows: vec![TableRow::new(vec![cell(20.0), second])],
+            ..Table::default()
+        };
+        let tree = LayoutTree::from_root(&table, &BoxConstraints::from_max((100.0, 100.0)));
+        // Only the cells and their children
+        tree.to_string()
+            .lines()
+            .skip(2)
+            .map(|line| line.trim_start_matches("    "))
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    // A stateful widget that counts how many times it is built
+    #[derive(Debug)]
+    struct Built(Rc<Cell<u32>>);
+
+    impl StatefulWidget for Built {
+        type State = ();
+
+        fn create_state(&self) {}
+
+        fn build(&self, _: &State<()>) -> Box<dyn Layout> {
+            self.0.set(self.0.get() + 1);
+            sized(15.0, 5.0)
+        }
+    }
+
+    // Counts how many times its child is laid out
+    #[derive(Debug)]
+    struct Counted {
+        layouts: Rc<Cell<u32>>,
+        child: Box<dyn Layout>,
+    }
+
+    impl Layout for Counted {
+        fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+            self.layouts.set(self.layouts.get() + 1);
+            self.child.layout(tree, constraints)
+        }
+    }
+
+    // A 10 pixel tall container in a `Reactive`, which animates to `width`
+    // over 100 milliseconds
+    fn animated(width: &Signal<f32>, layouts: &Rc<Cell<u32>>) -> Box<dyn Layout> {
+        let (width, layouts) = (width.clone(), Rc::clone(layouts));
+        Box::new(Reactive::new(move || {
+            Box::new(Counted {
+                layouts: Rc::clone(&layouts),
+                child: Box::new(AnimatedContainer {
+                    duration: 100.0,
+                    width: Some(width.get()),
+                    height: Some(10.0),
+                    ..AnimatedContainer::default()
+                }),
+            })
+        }))
+    }
+
+    // Lay out a frame at `time`, and return the size of the root
+    fn frame(widget: &dyn Layout, time: f32, elements: &mut ElementTree) -> Vector2 {
+        elements.set_time(time);
+        let constraints = BoxConstraints::from_max((100.0, 100.0));
+        let tree = LayoutTree::from_root_with_elements(widget, &constraints, elements);
+        tree.get(tree.root.unwrap()).unwrap().bounds.size()
+    }
+
+    // A cell that fills the width of its column
+    fn cell(height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+
+    fn sized(width: f32, height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            width: Some(width),
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+}
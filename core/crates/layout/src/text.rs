@@ -0,0 +1,217 @@
+use crate::decoration::Color;
+use crate::paragraph::{Paragraph, ParagraphStyle};
+use crate::tree::{BoxConstraints, Layout, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// The visual properties used to measure and paint text.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TextStyle {
+    /// The size of the font in pixels.
+    pub font_size: f32,
+    /// The height of each line as a multiple of the font size. This behaves
+    /// like a unitless CSS `line-height`.
+    pub line_height: f32,
+    pub color: Color,
+}
+
+impl Default for TextStyle {
+    fn default() -> TextStyle {
+        TextStyle {
+            font_size: 14.0,
+            line_height: 1.2,
+            color: Color::white(),
+        }
+    }
+}
+
+impl TextStyle {
+    /// The height of a single line in pixels.
+    #[must_use]
+    pub fn line_height_px(&self) -> f32 {
+        self.font_size * self.line_height
+    }
+}
+
+/// Measures glyphs during layout.
+///
+/// Layout must not depend on a particular font backend, so the platform (or a
+/// test) provides the measurer. The browser can measure with a canvas context,
+/// and tests can use the deterministic [`MonospaceMeasurer`].
+pub trait TextMeasurer: Debug {
+    /// The horizontal distance to move the pen after drawing `ch`. `Paragraph`
+    /// sums these to wrap lines and size text.
+    fn advance(&self, ch: char, style: &TextStyle) -> f32;
+}
+
+/// A measurer where every glyph has the same advance, which is a fixed
+/// fraction of the font size. The results do not depend on any font being
+/// installed, so it's useful for running layout natively in tests.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MonospaceMeasurer {
+    /// The advance of each glyph as a multiple of the font size.
+    pub advance_ratio: f32,
+}
+
+impl Default for MonospaceMeasurer {
+    fn default() -> MonospaceMeasurer {
+        MonospaceMeasurer { advance_ratio: 0.6 }
+    }
+}
+
+impl TextMeasurer for MonospaceMeasurer {
+    fn advance(&self, _: char, style: &TextStyle) -> f32 {
+        style.font_size * self.advance_ratio
+    }
+}
+
+/// A single glyph that has been positioned by layout.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct PositionedGlyph {
+    pub ch: char,
+    /// The byte offset of the glyph in the source text.
+    pub index: usize,
+    /// The top-left corner of the line box the glyph sits in, relative to the
+    /// `LayoutBox` that owns the glyph run.
+    pub position: Vector2,
+    pub advance: f32,
+}
+
+/// The glyphs produced by laying out some text. This is carried on the
+/// `LayoutBox` so that the driver can paint it.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct GlyphRun {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub style: TextStyle,
+}
+
+/// Displays a paragraph of text. The text wraps to fit the maximum width
+/// provided by the parent.
+#[derive(Debug)]
+pub struct Text {
+    pub text: String,
+    pub style: TextStyle,
+    pub paragraph: ParagraphStyle,
+    pub measurer: Rc<dyn TextMeasurer>,
+}
+
+impl Default for Text {
+    fn default() -> Text {
+        Text {
+            text: String::new(),
+            style: TextStyle::default(),
+            paragraph: ParagraphStyle::default(),
+            measurer: Rc::new(MonospaceMeasurer::default()),
+        }
+    }
+}
+
+impl Layout for Text {
+    fn layout(&self, _: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let paragraph = Paragraph::layout(
+            &self.text,
+            &self.style,
+            &self.paragraph,
+            self.measurer.as_ref(),
+            constraints.max.x,
+        );
+        let size = paragraph
+            .size
+            .clamp_between(constraints.min, constraints.max);
+        SizedLayoutBox {
+            size,
+            text: Some(paragraph.glyph_run(&self.style)),
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::LayoutBox;
+
+    #[test]
+    fn text_is_sized_by_measurer() {
+        let text = a_text("hello");
+        let sbox = text.layout(&mut LayoutTree::new(), &unbounded());
+        assert_eq!(Vector2::new(25.0, 10.0), sbox.size);
+    }
+
+    #[test]
+    fn text_positions_glyphs_on_separate_lines() {
+        let text = a_text("ab\nc");
+        let sbox = text.layout(&mut LayoutTree::new(), &unbounded());
+        let positions: Vec<(char, Vector2)> = sbox
+            .text
+            .unwrap()
+            .glyphs
+            .iter()
+            .map(|glyph| (glyph.ch, glyph.position))
+            .collect();
+        let expected = vec![
+            ('a', Vector2::new(0.0, 0.0)),
+            ('b', Vector2::new(5.0, 0.0)),
+            ('c', Vector2::new(0.0, 10.0)),
+        ];
+        assert_eq!(expected, positions);
+        assert_eq!(Vector2::new(10.0, 20.0), sbox.size);
+    }
+
+    #[test]
+    fn text_wraps_to_max_width() {
+        let text = a_text("hello world");
+        let constraints = BoxConstraints::from_max(Vector2::new(30.0, 100.0));
+        let sbox = text.layout(&mut LayoutTree::new(), &constraints);
+        assert_eq!(Vector2::new(25.0, 20.0), sbox.size);
+    }
+
+    #[test]
+    fn text_is_clamped_to_constraints() {
+        let text = a_text("hello");
+        let constraints = BoxConstraints {
+            min: Vector2::new(0.0, 20.0),
+            max: Vector2::new(100.0, 100.0),
+        };
+        let sbox = text.layout(&mut LayoutTree::new(), &constraints);
+        assert_eq!(Vector2::new(25.0, 20.0), sbox.size);
+    }
+
+    #[test]
+    fn empty_text_has_height_of_one_line() {
+        let text = a_text("");
+        let sbox = text.layout(&mut LayoutTree::new(), &unbounded());
+        assert_eq!(Vector2::new(0.0, 10.0), sbox.size);
+    }
+
+    #[test]
+    fn layout_box_from_child_keeps_glyph_run() {
+        let text = a_text("a");
+        let sbox = text.layout(&mut LayoutTree::new(), &unbounded());
+        let run = sbox.text.clone();
+        let lbox = LayoutBox::from_child(sbox, Vector2::zero());
+        assert_eq!(run, lbox.text);
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn a_text(text: &str) -> Text {
+        Text {
+            text: text.to_string(),
+            style: TextStyle {
+                font_size: 10.0,
+                line_height: 1.0,
+                color: Color::white(),
+            },
+            paragraph: ParagraphStyle::default(),
+            measurer: Rc::new(MonospaceMeasurer { advance_ratio: 0.5 }),
+        }
+    }
+
+    fn unbounded() -> BoxConstraints {
+        BoxConstraints::from_max(Vector2::new(f32::INFINITY, f32::INFINITY))
+    }
+}
@@ -1,8 +1,16 @@
 use crate::base::EdgeInsets;
-use crate::decoration::Material;
+use crate::decoration::{BorderRadius, Material};
+use crate::element::ElementTree;
+use crate::focus::FocusId;
+use crate::image::ImagePaint;
+use crate::keyboard::KeyHandler;
+use crate::pointer::PointerHandler;
+use crate::reactive::ReactiveNode;
+use crate::text::GlyphRun;
 use math::{Rect, Vector2};
 use std::collections::VecDeque;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
 
 /// This is the essential trait of the box model. It is implemented by all
 /// components that undergo the box layout process.
@@ -78,6 +86,13 @@ pub struct SizedLayoutBox {
     pub size: Vector2,
     pub children: Vec<LayoutBoxId>,
     pub material: Option<Material>,
+    pub text: Option<GlyphRun>,
+    pub image: Option<ImagePaint>,
+    pub pointer_handler: Option<PointerHandler>,
+    pub key_handler: Option<KeyHandler>,
+    pub focus_id: Option<FocusId>,
+    pub clip: Option<Rect>,        // Relative to the box, like its children
+    pub clip_radius: BorderRadius, // Rounds the corners of `clip`
 }
 
 /// An element that has finished layout. It has been been sized and positioned.
@@ -87,6 +102,13 @@ pub struct LayoutBox {
     pub margin: EdgeInsets,
     pub children: Vec<LayoutBoxId>,
     pub material: Option<Material>,
+    pub text: Option<GlyphRun>,
+    pub image: Option<ImagePaint>,
+    pub pointer_handler: Option<PointerHandler>,
+    pub key_handler: Option<KeyHandler>,
+    pub focus_id: Option<FocusId>,
+    pub clip: Option<Rect>, // Relative to `bounds.min`, like the children
+    pub clip_radius: BorderRadius, // Rounds the corners of `clip`
 }
 
 impl Eq for LayoutBox {}
@@ -105,6 +127,28 @@ impl LayoutBox {
             margin: child.margin,
             children: child.children,
             material: child.material,
+            text: child.text,
+            image: child.image,
+            pointer_handler: child.pointer_handler,
+            key_handler: child.key_handler,
+            focus_id: child.focus_id,
+            clip: child.clip,
+            clip_radius: child.clip_radius,
+        }
+    }
+
+    /// The area the box paints, relative to its parent like `bounds`. This
+    /// excludes the margins, but includes anything its material paints outside
+    /// of the box, like shadows. Children aren't included.
+    #[must_use]
+    pub fn paint_bounds(&self) -> Rect {
+        let rect = Rect::new(
+            self.bounds.min + self.margin.min(),
+            self.bounds.max - self.margin.max(),
+        );
+        match &self.material {
+            Some(material) => material.paint_bounds(rect),
+            None => rect,
         }
     }
 }
@@ -122,6 +166,10 @@ impl LayoutBox {
 pub struct LayoutTree {
     pub root: Option<LayoutBoxId>,
     pub boxes: Vec<LayoutBox>,
+    // The state of stateful widgets while they are being laid out
+    pub(crate) elements: ElementTree,
+    // The `Reactive` widgets that are being laid out, innermost last
+    pub(crate) reactive_scopes: Vec<Rc<ReactiveNode>>,
 }
 
 impl LayoutTree {
@@ -131,9 +179,27 @@ impl LayoutTree {
         LayoutTree {
             root: None,
             boxes: Vec::new(),
+            elements: ElementTree::new(),
+            reactive_scopes: Vec::new(),
         }
     }
 
+    /// Lay out a widget as the root of a new tree. The root is positioned at
+    /// the origin. Stateful widgets start with fresh state, see
+    /// `from_root_with_elements` to keep it between frames.
+    #[must_use]
+    pub fn from_root(widget: &dyn Layout, constraints: &BoxConstraints) -> LayoutTree {
+        let mut tree = LayoutTree::new();
+        tree.layout_root(widget, constraints);
+        tree
+    }
+
+    pub(crate) fn layout_root(&mut self, widget: &dyn Layout, constraints: &BoxConstraints) {
+        let sbox = widget.layout(self, constraints);
+        let root = self.insert(LayoutBox::from_child(sbox, (0.0, 0.0)));
+        self.set_root(Some(root));
+    }
+
     /// Set the root of the tree. This assumes that the `LayoutBoxId` provided
     /// by the caller points to a valid `LayoutBox`.
     pub fn set_root(&mut self, root: Option<LayoutBoxId>) {
@@ -174,6 +240,66 @@ impl LayoutTree {
     }
 }
 
+/// Prints the tree as indented text, with one box per line and each child
+/// indented under its parent. Every box shows its absolute bounds (including
+/// margins), followed by its margin, material and text when it has them:
+///
+/// ```text
+/// [0, 0, 100, 100] fill rgba(0, 255, 0, 255)
+///   [10, 10, 60, 60] margin (10, 10, 10, 10) fill rgba(255, 0, 0, 255)
+/// ```
+///
+/// This is meant for snapshot tests, which can compare the structure of the
+/// tree without depending on the order boxes were inserted into the arena.
+impl fmt::Display for LayoutTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.root {
+            Some(root) => self.fmt_box(f, root, Vector2::zero(), 0),
+            None => Ok(()),
+        }
+    }
+}
+
+impl LayoutTree {
+    fn fmt_box(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        id: LayoutBoxId,
+        parent_offset: Vector2,
+        depth: usize,
+    ) -> fmt::Result {
+        let Some(lbox) = self.get(id) else {
+            return writeln!(f, "{}<missing box {id}>", "  ".repeat(depth));
+        };
+        let bounds = lbox.bounds.translate(parent_offset);
+        write!(f, "{}{bounds}", "  ".repeat(depth))?;
+        if lbox.margin != EdgeInsets::zero() {
+            write!(f, " margin {}", lbox.margin)?;
+        }
+        if let Some(material) = &lbox.material {
+            write!(f, " {material}")?;
+        }
+        if let Some(run) = &lbox.text {
+            let text: String = run.glyphs.iter().map(|glyph| glyph.ch).collect();
+            write!(f, " text {text:?}")?;
+        }
+        if let Some(image) = &lbox.image {
+            write!(f, " image {image}")?;
+        }
+        if let Some(clip) = lbox.clip {
+            write!(f, " clip {}", clip.translate(bounds.min))?;
+            if !lbox.clip_radius.is_zero() {
+                write!(f, " radius {}", lbox.clip_radius)?;
+            }
+        }
+        writeln!(f)?;
+        for child in &lbox.children {
+            self.fmt_box(f, *child, bounds.min, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct LayoutTreeIterator<'a> {
     tree: &'a LayoutTree,
     parents: VecDeque<LayoutBoxId>,
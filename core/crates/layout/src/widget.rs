@@ -1,6 +1,6 @@
 use super::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
-use crate::decoration::{Color, Material};
-use math::Vector2;
+use crate::decoration::{BorderRadius, Color, Material};
+use math::{Rect, Vector2};
 use std::fmt::Debug;
 
 // --------------------------------------------------
@@ -81,3 +81,135 @@ impl Layout for Positioned {
         }
     }
 }
+
+// --------------------------------------------------
+// ClipRect
+// --------------------------------------------------
+
+/// Clips its child to `rect`, or to the child's bounds excluding its margins
+/// when `rect` is `None`. The child isn't painted outside of the clip, and
+/// doesn't receive pointer events there. A clip inside another clip is
+/// limited to both.
+#[derive(Debug)]
+pub struct ClipRect {
+    /// Relative to the top-left corner of the child, including its margins.
+    pub rect: Option<Rect>,
+    /// Rounds the corners of the clip. The radii are fit to the size of the
+    /// clip like the corners of a `Material`.
+    pub radius: BorderRadius,
+    pub child: Box<dyn Layout>,
+}
+
+impl Layout for ClipRect {
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let sbox = self.child.layout(tree, constraints);
+        let size = sbox.size;
+        let clip = self
+            .rect
+            .unwrap_or_else(|| Rect::new(sbox.margin.min(), size - sbox.margin.max()));
+        let id = tree.insert(LayoutBox::from_child(sbox, Vector2::zero()));
+        SizedLayoutBox {
+            size,
+            children: vec![id],
+            clip: Some(clip),
+            clip_radius: self.radius,
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::EdgeInsets;
+    use crate::container::Container;
+
+    #[test]
+    fn clip_rect_defaults_to_child_bounds() {
+        let clip = ClipRect {
+            rect: None,
+            radius: BorderRadius::zero(),
+            child: Box::new(Container {
+                width: Some(20.0),
+                height: Some(10.0),
+                margin: EdgeInsets::all(5.0),
+                ..Container::default()
+            }),
+        };
+        assert_eq!(
+            "[0, 0, 30, 20] clip [5, 5, 25, 15]\n  [0, 0, 30, 20] margin (5, 5, 5, 5) fill rgba(0, 0, 0, 0)\n",
+            layout(&clip).to_string()
+        );
+    }
+
+    #[test]
+    fn clip_rect_is_relative_to_child() {
+        let clip = Positioned {
+            position: Vector2::new(10.0, 10.0),
+            child: Box::new(ClipRect {
+                rect: Some(Rect::from_pos((2.0, 4.0), (6.0, 8.0))),
+                radius: BorderRadius::zero(),
+                child: Box::new(Container {
+                    width: Some(20.0),
+                    height: Some(20.0),
+                    ..Container::default()
+                }),
+            }),
+        };
+        let tree = layout(&clip);
+        assert!(tree
+            .to_string()
+            .contains("[10, 10, 30, 30] clip [12, 14, 18, 22]\n"));
+    }
+
+    #[test]
+    fn nested_clips_only_hit_test_their_intersection() {
+        let inner = ClipRect {
+            rect: Some(Rect::from_pos((10.0, 10.0), (50.0, 50.0))),
+            radius: BorderRadius::zero(),
+            child: Box::new(Container::default()),
+        };
+        let outer = ClipRect {
+            rect: Some(Rect::from_size((30.0, 30.0))),
+            radius: BorderRadius::zero(),
+            child: Box::new(inner),
+        };
+        let tree = layout(&outer);
+        let depth = |x, y| tree.hit_test(Vector2::new(x, y)).len();
+        // Inside both clips, so the container is hit
+        assert_eq!(3, depth(20.0, 20.0));
+        // Outside the outer clip, so only the outer box itself is hit
+        assert_eq!(1, depth(40.0, 40.0));
+        // Outside the inner clip, so the container isn't hit
+        assert_eq!(2, depth(5.0, 5.0));
+    }
+
+    #[test]
+    fn rounded_clip_rect_skips_corners_in_hit_test() {
+        let clip = ClipRect {
+            rect: None,
+            radius: BorderRadius::all(10.0),
+            child: Box::new(Container {
+                width: Some(40.0),
+                height: Some(40.0),
+                ..Container::default()
+            }),
+        };
+        let tree = layout(&clip);
+        assert!(tree
+            .to_string()
+            .starts_with("[0, 0, 40, 40] clip [0, 0, 40, 40] radius (10, 10, 10, 10)\n"));
+        let depth = |x, y| tree.hit_test(Vector2::new(x, y)).len();
+        assert_eq!(1, depth(2.0, 2.0));
+        assert_eq!(1, depth(38.0, 38.0));
+        assert_eq!(2, depth(5.0, 5.0));
+        assert_eq!(2, depth(20.0, 1.0));
+    }
+
+    fn layout(widget: &dyn Layout) -> LayoutTree {
+        LayoutTree::from_root(
+            widget,
+            &BoxConstraints::from_max(Vector2::new(100.0, 100.0)),
+        )
+    }
+}
@@ -0,0 +1,456 @@
+use crate::flex::{Axis, CrossAxisAlignment, MainAxisAlignment, MainAxisSize};
+use crate::tree::{BoxConstraints, Layout, LayoutBox, LayoutTree, SizedLayoutBox};
+use math::Vector2;
+use std::ops::Range;
+
+/// Places its children one after another along the main axis like `Flex`, but
+/// starts a new run whenever the next child would overflow, like text wrapping
+/// onto a new line.
+///
+/// `main_axis_alignment` positions the children within each run,
+/// `cross_axis_alignment` positions them within the cross axis size of their
+/// run, and `run_alignment` positions the runs within the wrap. Because runs
+/// are only sized once their children have been laid out,
+/// `CrossAxisAlignment::Stretch` behaves like `CrossAxisAlignment::Start`.
+///
+/// When the main axis is unbounded, every child is placed in a single run.
+#[derive(Debug)]
+pub struct Wrap {
+    pub axis: Axis,
+    pub main_axis_size: MainAxisSize,
+    pub main_axis_alignment: MainAxisAlignment,
+    pub cross_axis_alignment: CrossAxisAlignment,
+    pub run_alignment: MainAxisAlignment,
+    /// The gap between neighbouring children in a run.
+    pub spacing: f32,
+    /// The gap between neighbouring runs.
+    pub run_spacing: f32,
+    pub children: Vec<Box<dyn Layout>>,
+}
+
+impl Default for Wrap {
+    fn default() -> Wrap {
+        Wrap {
+            axis: Axis::Horizontal,
+            main_axis_size: MainAxisSize::Max,
+            main_axis_alignment: MainAxisAlignment::Start,
+            cross_axis_alignment: CrossAxisAlignment::Start,
+            run_alignment: MainAxisAlignment::Start,
+            spacing: 0.0,
+            run_spacing: 0.0,
+            children: vec![],
+        }
+    }
+}
+
+// A line of children
+struct Run {
+    children: Range<usize>,
+    main_size: f32,
+    cross_size: f32,
+}
+
+impl Layout for Wrap {
+    #[allow(clippy::cast_precision_loss)]
+    fn layout(&self, tree: &mut LayoutTree, constraints: &BoxConstraints) -> SizedLayoutBox {
+        let (main_min, main_max) = self.main_axis_constraint(constraints);
+        let (cross_min, cross_max) = self.cross_axis_constraint(constraints);
+
+        // Children are only limited by the size of the wrap, because they can
+        // always start a new run
+        let child_constraints = BoxConstraints::from_max(self.align_to_axis(main_max, cross_max));
+        let sboxes: Vec<SizedLayoutBox> = self
+            .children
+            .iter()
+            .map(|child| child.layout(tree, &child_constraints))
+            .collect();
+
+        // Break the children into runs. A child always fits in an empty run,
+        // even when it is larger than the wrap.
+        let mut runs: Vec<Run> = vec![];
+        for (i, sbox) in sboxes.iter().enumerate() {
+            let main_size = self.main_axis_size(sbox.size);
+            let cross_size = self.cross_axis_size(sbox.size);
+            match runs.last_mut() {
+                Some(run) if run.main_size + self.spacing + main_size <= main_max => {
+                    run.children.end = i + 1;
+                    run.main_size += self.spacing + main_size;
+                    run.cross_size = run.cross_size.max(cross_size);
+                }
+                _ => runs.push(Run {
+                    children: i..i + 1,
+                    main_size,
+                    cross_size,
+                }),
+            }
+        }
+
+        let content_main_size = runs.iter().map(|run| run.main_size).fold(0.0, f32::max);
+        let content_cross_size = runs.iter().map(|run| run.cross_size).sum::<f32>()
+            + self.run_spacing * runs.len().saturating_sub(1) as f32;
+        let main_size = match self.main_axis_size {
+            MainAxisSize::Max if main_max.is_finite() => main_max,
+            _ => content_main_size.clamp(main_min, main_max),
+        };
+        let cross_size = content_cross_size.clamp(cross_min, cross_max);
+
+        // Position the runs along the cross axis, and the children in each run
+        // along the main axis
+        let mut positions = vec![Vector2::zero(); sboxes.len()];
+        let (mut cross_pos, run_gap) = distribute(
+            self.run_alignment,
+            cross_size - content_cross_size,
+            runs.len(),
+            self.run_spacing,
+        );
+        for run in &runs {
+            let (mut main_pos, gap) = distribute(
+                self.main_axis_alignment,
+                main_size - run.main_size,
+                run.children.len(),
+                self.spacing,
+            );
+            for i in run.children.clone() {
+                let child_main_size = self.main_axis_size(sboxes[i].size);
+                let child_cross_size = self.cross_axis_size(sboxes[i].size);
+                let offset = match self.cross_axis_alignment {
+                    CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => 0.0,
+                    CrossAxisAlignment::End => run.cross_size - child_cross_size,
+                    CrossAxisAlignment::Center => (run.cross_size - child_cross_size) / 2.0,
+                };
+                positions[i] = self.align_to_axis(main_pos, cross_pos + offset);
+                main_pos += child_main_size + gap;
+            }
+            cross_pos += run.cross_size + run_gap;
+        }
+
+        let children = sboxes
+            .into_iter()
+            .zip(positions)
+            .map(|(sbox, pos)| tree.insert(LayoutBox::from_child(sbox, pos)))
+            .collect();
+        SizedLayoutBox {
+            size: self.align_to_axis(main_size, cross_size),
+            children,
+            ..SizedLayoutBox::default()
+        }
+    }
+}
+
+impl Wrap {
+    // Get the minimum and maximum size of a constraint along the main axis.
+    fn main_axis_constraint(&self, constraints: &BoxConstraints) -> (f32, f32) {
+        let BoxConstraints { min, max } = constraints;
+        match self.axis {
+            Axis::Horizontal => (min.x, max.x),
+            Axis::Vertical => (min.y, max.y),
+        }
+    }
+
+    // Get the minimum and maximum size of a constraint along the cross axis.
+    fn cross_axis_constraint(&self, constraints: &BoxConstraints) -> (f32, f32) {
+        let BoxConstraints { min, max } = constraints;
+        match self.axis {
+            Axis::Horizontal => (min.y, max.y),
+            Axis::Vertical => (min.x, max.x),
+        }
+    }
+
+    // Get a 2D coordinate from positions relative to the main and cross axes.
+    fn align_to_axis(&self, main_pos: f32, cross_pos: f32) -> Vector2 {
+        match self.axis {
+            Axis::Horizontal => Vector2::new(main_pos, cross_pos),
+            Axis::Vertical => Vector2::new(cross_pos, main_pos),
+        }
+    }
+
+    // Get the size along the main axis.
+    fn main_axis_size(&self, size: Vector2) -> f32 {
+        match self.axis {
+            Axis::Horizontal => size.x,
+            Axis::Vertical => size.y,
+        }
+    }
+
+    // Get the size along the cross axis.
+    fn cross_axis_size(&self, size: Vector2) -> f32 {
+        match self.axis {
+            Axis::Horizontal => size.y,
+            Axis::Vertical => size.x,
+        }
+    }
+}
+
+// Spread `free_space` around `count` items that are already `spacing` apart.
+// Returns the position of the first item, and the gap between each item. Like
+// `Flex`, a single item is centered by `SpaceBetween`.
+#[allow(clippy::cast_precision_loss)]
+fn distribute(
+    alignment: MainAxisAlignment,
+    free_space: f32,
+    count: usize,
+    spacing: f32,
+) -> (f32, f32) {
+    let free_space = free_space.max(0.0);
+    let count = count as f32;
+    match alignment {
+        MainAxisAlignment::Start => (0.0, spacing),
+        MainAxisAlignment::End => (free_space, spacing),
+        MainAxisAlignment::SpaceBetween if count > 1.0 => {
+            (0.0, spacing + free_space / (count - 1.0))
+        }
+        MainAxisAlignment::Center | MainAxisAlignment::SpaceBetween => (free_space / 2.0, spacing),
+        MainAxisAlignment::SpaceAround => {
+            let space = free_space / count;
+            (space / 2.0, spacing + space)
+        }
+        MainAxisAlignment::SpaceEvenly => {
+            let space = free_space / (count + 1.0);
+            (space, spacing + space)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::Container;
+    use crate::decoration::Color;
+    use test_util::assert_snapshot_eq;
+
+    #[test]
+    fn children_that_fit_share_a_run() {
+        let wrap = Wrap {
+            spacing: 5.0,
+            children: vec![chip(20.0, 10.0), chip(30.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [25, 0, 55, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(100.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn children_that_overflow_start_a_new_run() {
+        let wrap = Wrap {
+            spacing: 5.0,
+            run_spacing: 2.0,
+            children: vec![
+                chip(20.0, 10.0),
+                chip(30.0, 10.0),
+                chip(30.0, 12.0),
+                chip(10.0, 8.0),
+            ],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 60, 24]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [25, 0, 55, 10] fill rgba(255, 0, 0, 255)
+              [0, 12, 30, 24] fill rgba(255, 0, 0, 255)
+              [35, 12, 45, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(60.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn child_larger_than_wrap_gets_its_own_run() {
+        let wrap = Wrap {
+            children: vec![chip(10.0, 10.0), chip(80.0, 10.0), chip(10.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 30]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [0, 10, 50, 20] fill rgba(255, 0, 0, 255)
+              [0, 20, 10, 30] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(50.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn main_axis_alignment_positions_children_within_each_run() {
+        let wrap = Wrap {
+            main_axis_alignment: MainAxisAlignment::Center,
+            spacing: 4.0,
+            children: vec![chip(20.0, 10.0), chip(20.0, 10.0), chip(10.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 20]
+              [3, 0, 23, 10] fill rgba(255, 0, 0, 255)
+              [27, 0, 47, 10] fill rgba(255, 0, 0, 255)
+              [20, 10, 30, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(50.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn space_between_spreads_children_across_the_run() {
+        let wrap = Wrap {
+            main_axis_alignment: MainAxisAlignment::SpaceBetween,
+            children: vec![chip(10.0, 10.0), chip(10.0, 10.0), chip(10.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 50, 10]
+              [0, 0, 10, 10] fill rgba(255, 0, 0, 255)
+              [20, 0, 30, 10] fill rgba(255, 0, 0, 255)
+              [40, 0, 50, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(50.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn cross_axis_alignment_positions_children_within_their_run() {
+        let wrap = Wrap {
+            cross_axis_alignment: CrossAxisAlignment::End,
+            children: vec![chip(10.0, 20.0), chip(10.0, 10.0), chip(10.0, 4.0)],
+            ..Wrap::default()
+        };
+        let centered = Wrap {
+            cross_axis_alignment: CrossAxisAlignment::Center,
+            children: vec![chip(10.0, 20.0), chip(10.0, 10.0), chip(10.0, 4.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 24]
+              [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+              [10, 10, 20, 20] fill rgba(255, 0, 0, 255)
+              [0, 20, 10, 24] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(20.0, 100.0)),
+        );
+        assert_snapshot_eq(
+            "
+            [0, 0, 20, 24]
+              [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+              [10, 5, 20, 15] fill rgba(255, 0, 0, 255)
+              [0, 20, 10, 24] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&centered, Vector2::new(20.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn run_alignment_positions_runs_within_the_wrap() {
+        let wrap = Wrap {
+            run_alignment: MainAxisAlignment::End,
+            run_spacing: 5.0,
+            children: vec![chip(20.0, 10.0), chip(20.0, 10.0)],
+            ..Wrap::default()
+        };
+        let constraints = BoxConstraints {
+            min: Vector2::new(0.0, 50.0),
+            max: Vector2::new(30.0, 100.0),
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 30, 50]
+              [0, 25, 20, 35] fill rgba(255, 0, 0, 255)
+              [0, 40, 20, 50] fill rgba(255, 0, 0, 255)
+            ",
+            &LayoutTree::from_root(&wrap, &constraints).to_string(),
+        );
+    }
+
+    #[test]
+    fn vertical_wrap_flows_into_columns() {
+        let wrap = Wrap {
+            axis: Axis::Vertical,
+            spacing: 2.0,
+            run_spacing: 4.0,
+            children: vec![chip(10.0, 20.0), chip(12.0, 20.0), chip(10.0, 20.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 26, 50]
+              [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+              [0, 22, 12, 42] fill rgba(255, 0, 0, 255)
+              [16, 0, 26, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(100.0, 50.0)),
+        );
+    }
+
+    #[test]
+    fn unbounded_main_axis_keeps_children_in_one_run() {
+        let wrap = Wrap {
+            spacing: 5.0,
+            main_axis_alignment: MainAxisAlignment::End,
+            children: vec![chip(20.0, 10.0), chip(30.0, 10.0), chip(40.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 100, 10]
+              [0, 0, 20, 10] fill rgba(255, 0, 0, 255)
+              [25, 0, 55, 10] fill rgba(255, 0, 0, 255)
+              [60, 0, 100, 10] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(f32::INFINITY, f32::INFINITY)),
+        );
+    }
+
+    #[test]
+    fn main_axis_size_min_shrinks_to_longest_run() {
+        let wrap = Wrap {
+            main_axis_size: MainAxisSize::Min,
+            main_axis_alignment: MainAxisAlignment::Center,
+            children: vec![chip(30.0, 10.0), chip(20.0, 10.0), chip(10.0, 10.0)],
+            ..Wrap::default()
+        };
+        assert_snapshot_eq(
+            "
+            [0, 0, 30, 20]
+              [0, 0, 30, 10] fill rgba(255, 0, 0, 255)
+              [0, 10, 20, 20] fill rgba(255, 0, 0, 255)
+              [20, 10, 30, 20] fill rgba(255, 0, 0, 255)
+            ",
+            &dump(&wrap, Vector2::new(45.0, 100.0)),
+        );
+    }
+
+    #[test]
+    fn empty_wrap_is_sized_by_constraints() {
+        let wrap = Wrap::default();
+        let constraints = BoxConstraints {
+            min: Vector2::new(0.0, 10.0),
+            max: Vector2::new(50.0, 100.0),
+        };
+        assert_snapshot_eq(
+            "[0, 0, 50, 10]",
+            &LayoutTree::from_root(&wrap, &constraints).to_string(),
+        );
+    }
+
+    // ---- Helpers ----
+
+    fn dump(widget: &dyn Layout, max: Vector2) -> String {
+        LayoutTree::from_root(widget, &BoxConstraints::from_max(max)).to_string()
+    }
+
+    fn chip(width: f32, height: f32) -> Box<dyn Layout> {
+        Box::new(Container {
+            width: Some(width),
+            height: Some(height),
+            color: Color::red(),
+            ..Container::default()
+        })
+    }
+}
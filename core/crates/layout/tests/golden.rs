@@ -0,0 +1,396 @@
+use layout::{
+    Alignment, Axis, BorderRadius, BorderSide, Borders, BoxShadow, ClipRect, Color, ColorStop,
+    Container, CrossAxisAlignment, EdgeInsets, Flex, FlexLayout, LinearGradient, MainAxisAlignment,
+    MainAxisSize, Paint, RadialGradient,
+};
+use math::{Rect, Vector2};
+use test_util::assert_golden;
+
+#[test]
+fn container_borders() {
+    let container = Container {
+        padding: EdgeInsets::all(8.0),
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        borders: Borders {
+            top: Some(BorderSide::new(Color::red(), 2.0)),
+            bottom: Some(BorderSide::new(Color::green(), 4.0)),
+            left: Some(BorderSide::new(Color::blue(), 6.0)),
+            right: Some(BorderSide::new(Color::yellow(), 8.0)),
+        },
+        child: Some(Box::new(Container {
+            color: Color::white(),
+            borders: Borders::all(Color::red().alpha(0.5), 3.0),
+            ..Container::default()
+        })),
+        ..Container::default()
+    };
+    assert_golden(&container, Vector2::new(64.0, 48.0), "container_borders");
+}
+
+#[test]
+fn container_margin_and_single_borders() {
+    let column = Flex {
+        children: vec![
+            bordered(Borders::top(Color::red(), 3.0)),
+            bordered(Borders::bottom(Color::green(), 3.0)),
+            bordered(Borders::left(Color::blue(), 3.0)),
+            bordered(Borders::right(Color::yellow(), 3.0)),
+        ],
+        ..Flex::default()
+    };
+    assert_golden(
+        &column,
+        Vector2::new(40.0, 80.0),
+        "container_margin_and_single_borders",
+    );
+}
+
+#[test]
+fn rounded_corners() {
+    // A filled box with mixed radii, a uniform rounded border, and a rounded
+    // border with a different width and color on each side.
+    let boxes = Flex {
+        axis: Axis::Horizontal,
+        main_axis_alignment: MainAxisAlignment::SpaceEvenly,
+        cross_axis_alignment: CrossAxisAlignment::Center,
+        children: vec![
+            rounded(
+                BorderRadius {
+                    top_left: 16.0,
+                    bottom_right: 16.0,
+                    ..BorderRadius::all(4.0)
+                },
+                Borders::none(),
+            ),
+            rounded(BorderRadius::all(12.0), Borders::all(Color::white(), 3.0)),
+            rounded(
+                BorderRadius::all(14.0),
+                Borders {
+                    top: Some(BorderSide::new(Color::red(), 2.0)),
+                    bottom: Some(BorderSide::new(Color::green(), 4.0)),
+                    left: Some(BorderSide::new(Color::blue(), 6.0)),
+                    right: Some(BorderSide::new(Color::yellow(), 8.0)),
+                },
+            ),
+        ],
+        ..Flex::default()
+    };
+    let row = Container {
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        child: Some(Box::new(boxes)),
+        ..Container::default()
+    };
+    assert_golden(&row, Vector2::new(112.0, 48.0), "rounded_corners");
+}
+
+#[test]
+fn nested_clips() {
+    // The green square overflows the bordered container, which clips it to
+    // the inside of its borders. The clip rect then cuts off the left and
+    // bottom of both.
+    let clipped = Container {
+        width: Some(40.0),
+        height: Some(32.0),
+        color: Color::blue(),
+        borders: Borders::all(Color::red(), 2.0),
+        clip: true,
+        child: Some(Box::new(Container {
+            width: Some(100.0),
+            height: Some(100.0),
+            color: Color::green(),
+            ..Container::default()
+        })),
+        ..Container::default()
+    };
+    let container = Container {
+        padding: EdgeInsets::all(8.0),
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        child: Some(Box::new(ClipRect {
+            rect: Some(Rect::from_pos((10.0, 0.0), (40.0, 24.0))),
+            radius: BorderRadius::zero(),
+            child: Box::new(clipped),
+        })),
+        ..Container::default()
+    };
+    assert_golden(&container, Vector2::new(64.0, 48.0), "nested_clips");
+}
+
+#[test]
+fn rounded_clips() {
+    // The green squares overflow a rounded, bordered container and a rounded
+    // clip rect, so both are cut off along the curve of the corners.
+    let overflowing = || {
+        Box::new(Container {
+            width: Some(100.0),
+            height: Some(100.0),
+            color: Color::green(),
+            ..Container::default()
+        })
+    };
+    let row = Flex {
+        axis: Axis::Horizontal,
+        main_axis_alignment: MainAxisAlignment::SpaceEvenly,
+        cross_axis_alignment: CrossAxisAlignment::Center,
+        children: vec![
+            Box::new(Container {
+                width: Some(40.0),
+                height: Some(32.0),
+                color: Color::blue(),
+                borders: Borders::all(Color::red(), 3.0),
+                border_radius: BorderRadius::all(12.0),
+                clip: true,
+                child: Some(overflowing()),
+                ..Container::default()
+            }),
+            Box::new(Container {
+                width: Some(40.0),
+                height: Some(32.0),
+                child: Some(Box::new(ClipRect {
+                    rect: None,
+                    radius: BorderRadius::top(16.0),
+                    child: overflowing(),
+                })),
+                ..Container::default()
+            }),
+        ],
+        ..Flex::default()
+    };
+    assert_golden(&row, Vector2::new(112.0, 48.0), "rounded_clips");
+}
+
+#[test]
+fn box_shadows() {
+    // A soft offset shadow under a rounded box, a hard spread shadow with no
+    // blur, and two stacked shadows under a square box.
+    let boxes = Flex {
+        axis: Axis::Horizontal,
+        main_axis_alignment: MainAxisAlignment::SpaceEvenly,
+        cross_axis_alignment: CrossAxisAlignment::Center,
+        children: vec![
+            shadowed(
+                BorderRadius::all(8.0),
+                vec![BoxShadow::new(
+                    Vector2::new(0.0, 4.0),
+                    8.0,
+                    0.0,
+                    Color::black().alpha(0.8),
+                )],
+            ),
+            shadowed(
+                BorderRadius::all(8.0),
+                vec![BoxShadow::new(Vector2::zero(), 0.0, 3.0, Color::red())],
+            ),
+            shadowed(
+                BorderRadius::zero(),
+                vec![
+                    BoxShadow::new(Vector2::new(4.0, 4.0), 4.0, 0.0, Color::blue()),
+                    BoxShadow::new(Vector2::zero(), 12.0, 2.0, Color::black().alpha(0.5)),
+                ],
+            ),
+        ],
+        ..Flex::default()
+    };
+    let row = Container {
+        color: Color::rgba(200.0, 200.0, 200.0, 255.0),
+        child: Some(Box::new(boxes)),
+        ..Container::default()
+    };
+    assert_golden(&row, Vector2::new(112.0, 48.0), "box_shadows");
+}
+
+#[test]
+fn gradients() {
+    // A horizontal and a diagonal linear gradient, a radial gradient that
+    // fades out, and a rounded, bordered box with a gradient fill.
+    let stops = vec![
+        ColorStop::new(0.0, Color::red()),
+        ColorStop::new(0.5, Color::yellow()),
+        ColorStop::new(1.0, Color::blue()),
+    ];
+    let boxes = Flex {
+        axis: Axis::Horizontal,
+        main_axis_alignment: MainAxisAlignment::SpaceEvenly,
+        cross_axis_alignment: CrossAxisAlignment::Center,
+        children: vec![
+            gradient(
+                Paint::LinearGradient(LinearGradient::new(90.0, stops.clone())),
+                BorderRadius::zero(),
+                Borders::none(),
+            ),
+            gradient(
+                Paint::LinearGradient(LinearGradient::new(135.0, stops)),
+                BorderRadius::zero(),
+                Borders::none(),
+            ),
+            gradient(
+                Paint::RadialGradient(RadialGradient::new(
+                    Alignment::new(0.5, 0.5),
+                    0.5,
+                    vec![
+                        ColorStop::new(0.0, Color::white()),
+                        ColorStop::new(1.0, Color::white().alpha(0.0)),
+                    ],
+                )),
+                BorderRadius::zero(),
+                Borders::none(),
+            ),
+            gradient(
+                Paint::LinearGradient(LinearGradient::new(
+                    180.0,
+                    vec![
+                        ColorStop::new(0.0, Color::green()),
+                        ColorStop::new(1.0, Color::black()),
+                    ],
+                )),
+                BorderRadius::all(8.0),
+                Borders::all(Color::white(), 2.0),
+            ),
+        ],
+        ..Flex::default()
+    };
+    let row = Container {
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        child: Some(Box::new(boxes)),
+        ..Container::default()
+    };
+    assert_golden(&row, Vector2::new(128.0, 40.0), "gradients");
+}
+
+#[test]
+fn flex_main_axis_alignment() {
+    let alignments = [
+        MainAxisAlignment::Start,
+        MainAxisAlignment::End,
+        MainAxisAlignment::Center,
+        MainAxisAlignment::SpaceEvenly,
+        MainAxisAlignment::SpaceAround,
+        MainAxisAlignment::SpaceBetween,
+    ];
+    let rows = alignments
+        .into_iter()
+        .map(|alignment| {
+            row(Flex {
+                axis: Axis::Horizontal,
+                main_axis_alignment: alignment,
+                children: squares(),
+                ..Flex::default()
+            })
+        })
+        .collect();
+    let column = Flex {
+        children: rows,
+        ..Flex::default()
+    };
+    assert_golden(
+        &column,
+        Vector2::new(80.0, 72.0),
+        "flex_main_axis_alignment",
+    );
+}
+
+#[test]
+fn flex_cross_axis_alignment() {
+    let alignments = [
+        CrossAxisAlignment::Start,
+        CrossAxisAlignment::End,
+        CrossAxisAlignment::Center,
+        CrossAxisAlignment::Stretch,
+    ];
+    let columns = alignments
+        .into_iter()
+        .map(|alignment| -> Box<dyn FlexLayout> {
+            Box::new(Container {
+                width: Some(20.0),
+                color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+                child: Some(Box::new(Flex {
+                    axis: Axis::Vertical,
+                    main_axis_size: MainAxisSize::Max,
+                    cross_axis_alignment: alignment,
+                    children: vec![square(Color::red(), 8.0), square(Color::green(), 12.0)],
+                    ..Flex::default()
+                })),
+                ..Container::default()
+            })
+        })
+        .collect();
+    let row = Flex {
+        axis: Axis::Horizontal,
+        main_axis_alignment: MainAxisAlignment::SpaceEvenly,
+        children: columns,
+        ..Flex::default()
+    };
+    assert_golden(&row, Vector2::new(100.0, 40.0), "flex_cross_axis_alignment");
+}
+
+// --------------------------------------------------
+// Helpers
+// --------------------------------------------------
+
+fn bordered(borders: Borders) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        height: Some(20.0),
+        margin: EdgeInsets::all(2.0),
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        borders,
+        ..Container::default()
+    })
+}
+
+fn rounded(border_radius: BorderRadius, borders: Borders) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        width: Some(32.0),
+        height: Some(32.0),
+        color: Color::rgba(0.0, 120.0, 200.0, 255.0),
+        borders,
+        border_radius,
+        ..Container::default()
+    })
+}
+
+fn shadowed(border_radius: BorderRadius, shadows: Vec<BoxShadow>) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        width: Some(24.0),
+        height: Some(24.0),
+        color: Color::white(),
+        border_radius,
+        shadows,
+        ..Container::default()
+    })
+}
+
+fn gradient(fill: Paint, border_radius: BorderRadius, borders: Borders) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        width: Some(24.0),
+        height: Some(24.0),
+        fill: Some(fill),
+        border_radius,
+        borders,
+        ..Container::default()
+    })
+}
+
+fn row(flex: Flex) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        height: Some(12.0),
+        color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+        child: Some(Box::new(flex)),
+        ..Container::default()
+    })
+}
+
+fn squares() -> Vec<Box<dyn FlexLayout>> {
+    vec![
+        square(Color::red(), 10.0),
+        square(Color::green(), 10.0),
+        square(Color::blue(), 10.0),
+    ]
+}
+
+fn square(color: Color, size: f32) -> Box<dyn FlexLayout> {
+    Box::new(Container {
+        width: Some(size),
+        height: Some(size),
+        color,
+        ..Container::default()
+    })
+}
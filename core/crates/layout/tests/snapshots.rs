@@ -0,0 +1,64 @@
+use layout::{
+    Axis, Borders, BoxConstraints, Color, Container, EdgeInsets, Flex, FlexLayout, Flexible,
+    LayoutTree, MainAxisAlignment, Text, TextStyle,
+};
+use math::Vector2;
+use test_util::assert_snapshot;
+
+#[test]
+fn sidebar_layout() {
+    let rows: Vec<Box<dyn FlexLayout>> = (0..3)
+        .map(|i| -> Box<dyn FlexLayout> {
+            Box::new(Container {
+                height: Some(20.0),
+                padding: EdgeInsets::horizontal(6.0),
+                child: Some(Box::new(Text {
+                    text: format!("file_{i}.rs"),
+                    style: TextStyle {
+                        font_size: 10.0,
+                        line_height: 2.0,
+                        ..TextStyle::default()
+                    },
+                    ..Text::default()
+                })),
+                ..Container::default()
+            })
+        })
+        .collect();
+    let root = Flex {
+        axis: Axis::Horizontal,
+        children: vec![
+            Box::new(Container {
+                width: Some(80.0),
+                color: Color::rgba(30.0, 30.0, 30.0, 255.0),
+                borders: Borders::right(Color::black(), 1.0),
+                child: Some(Box::new(Flex {
+                    children: rows,
+                    ..Flex::default()
+                })),
+                ..Container::default()
+            }),
+            Box::new(Flexible {
+                flex_factor: 1.0,
+                child: Box::new(Flex {
+                    main_axis_alignment: MainAxisAlignment::Center,
+                    // The margin is added to the size, so the box plus its
+                    // margin fills the 120 pixels left by the sidebar
+                    children: vec![Box::new(Container {
+                        width: Some(112.0),
+                        height: Some(40.0),
+                        margin: EdgeInsets::all(4.0),
+                        color: Color::blue(),
+                        ..Container::default()
+                    })],
+                    ..Flex::default()
+                }),
+            }),
+        ],
+        ..Flex::default()
+    };
+
+    let constraints = BoxConstraints::from_max(Vector2::new(200.0, 100.0));
+    let tree = LayoutTree::from_root(&root, &constraints);
+    assert_snapshot("sidebar_layout", &tree.to_string());
+}
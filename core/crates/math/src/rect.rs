@@ -1,4 +1,5 @@
 use crate::Vector2;
+use std::fmt;
 
 /// A 2-dimensional rectangle.
 #[repr(C)]
@@ -62,4 +63,52 @@ impl Rect {
         let intersects_y = self.min.y < point.y && point.y < self.max.y;
         intersects_x && intersects_y
     }
+
+    /// Get the area covered by both rectangles. When they don't overlap, the
+    /// result has a size of zero.
+    #[must_use]
+    pub fn intersection(self, other: Rect) -> Rect {
+        let min_x = self.min.x.max(other.min.x);
+        let min_y = self.min.y.max(other.min.y);
+        let max_x = self.max.x.min(other.max.x).max(min_x);
+        let max_y = self.max.y.min(other.max.y).max(min_y);
+        Rect::new(Vector2::new(min_x, min_y), Vector2::new(max_x, max_y))
+    }
+
+    /// Get the smallest rectangle that contains both rectangles.
+    #[must_use]
+    pub fn union(self, other: Rect) -> Rect {
+        Rect::new(
+            Vector2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            Vector2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        )
+    }
+
+    /// Grow the rectangle by `amount` on every side, or shrink it when
+    /// `amount` is negative.
+    #[must_use]
+    pub fn inflate(self, amount: f32) -> Rect {
+        Rect::new(self.min - amount, self.max + amount)
+    }
+
+    /// Check if the rectangles share any area. Rectangles that only touch at
+    /// an edge don't overlap.
+    #[must_use]
+    pub fn overlaps(self, other: Rect) -> bool {
+        self.min.x < other.max.x
+            && other.min.x < self.max.x
+            && self.min.y < other.max.y
+            && other.min.y < self.max.y
+    }
+}
+
+/// Formats the rectangle as `[min.x, min.y, max.x, max.y]`.
+impl fmt::Display for Rect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}, {}, {}, {}]",
+            self.min.x, self.min.y, self.max.x, self.max.y
+        )
+    }
 }
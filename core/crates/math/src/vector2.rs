@@ -1,6 +1,7 @@
 use crate::Vector3;
 use bytemuck::{Pod, Zeroable};
 use std::default::Default;
+use std::fmt;
 use std::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
@@ -163,6 +164,13 @@ fn safe_clamp(val: f32, min: f32, max: f32) -> f32 {
 // Vector operations
 // --------------------------------------------------
 
+/// Formats the vector as `(x, y)`.
+impl fmt::Display for Vector2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
 impl Default for Vector2 {
     /// Get the zero vector.
     fn default() -> Vector2 {
@@ -0,0 +1,567 @@
+use layout::{
+    BorderRadius, Borders, Color, GlyphRun, ImagePaint, LayoutBoxId, LayoutTree, Material, Paint,
+};
+use math::{Rect, Vector2};
+use std::fmt;
+
+/// A single paint command. All positions are absolute, in pixels from the
+/// top-left corner of the viewport.
+#[derive(PartialEq, Clone, Debug)]
+pub enum DisplayItem {
+    // A rect filled with a single color
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    // A filled rect with at least one border, rounded corner or gradient.
+    // Borders are drawn inside `rect`
+    BorderedRect {
+        rect: Rect,
+        material: Material,
+    },
+    // A rounded rect blurred with a Gaussian of `blur_radius / 2` standard
+    // deviation. `rect` is the shape of the shadow before it's blurred, so the
+    // blur paints past it
+    Shadow {
+        rect: Rect,
+        radius: BorderRadius,
+        blur_radius: f32,
+        color: Color,
+    },
+    // A straight line with square ends
+    Line {
+        start: Vector2,
+        end: Vector2,
+        width: f32,
+        color: Color,
+    },
+    // Only paint the following items inside `rect` with rounded corners,
+    // intersected with the current clip, until the matching `PopClip`. The
+    // radii are fit to the size of `rect` like a material's. Pixels are inside
+    // the clip when their center is
+    PushClip {
+        rect: Rect,
+        radius: BorderRadius,
+    },
+    PopClip,
+    // A glyph run. `origin` is the top-left corner of the box that owns it
+    Text {
+        origin: Vector2,
+        run: GlyphRun,
+    },
+    // A bitmap fit inside `rect`. The backend knows the size of the image, so
+    // it places it with `ImagePaint::placement`
+    Image {
+        rect: Rect,
+        image: ImagePaint,
+    },
+}
+
+/// A flat list of paint commands, in painting order.
+///
+/// This sits between layout and the rendering backends. It is built from a
+/// `LayoutTree` without knowing anything about the GPU, so it can be compared,
+/// printed, serialized with `to_json` and asserted on in tests. Backends only
+/// have to replay the items in order.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct DisplayList {
+    items: Vec<DisplayItem>,
+}
+
+impl DisplayList {
+    #[must_use]
+    pub fn new() -> DisplayList {
+        DisplayList::default()
+    }
+
+    /// Build the paint commands for a `LayoutTree`. Boxes are painted parent
+    /// first, then each child in order, which matches `LayoutTree::iter`. The
+    /// children of a box that clips are wrapped in a `PushClip` and `PopClip`.
+    ///
+    /// Boxes that are entirely outside of the current clip, including their
+    /// shadows, aren't painted. Their children still are, since they may
+    /// overflow the box.
+    #[must_use]
+    pub fn from_tree(tree: &LayoutTree) -> DisplayList {
+        let mut list = DisplayList::new();
+        if let Some(root) = tree.root {
+            list.push_box(tree, root, Vector2::zero(), None);
+        }
+        list
+    }
+
+    fn push_box(
+        &mut self,
+        tree: &LayoutTree,
+        id: LayoutBoxId,
+        parent_offset: Vector2,
+        clip: Option<Rect>,
+    ) {
+        let Some(lbox) = tree.get(id) else {
+            return;
+        };
+        let visible =
+            clip.is_none_or(|clip| clip.overlaps(lbox.paint_bounds().translate(parent_offset)));
+        if visible {
+            let min = lbox.bounds.min + parent_offset + lbox.margin.min();
+            let max = lbox.bounds.max + parent_offset - lbox.margin.max();
+            if let Some(material) = &lbox.material {
+                self.push_material(Rect::new(min, max), material.clone());
+            }
+            if let Some(run) = &lbox.text {
+                self.push(DisplayItem::Text {
+                    origin: min,
+                    run: run.clone(),
+                });
+            }
+            if let Some(image) = &lbox.image {
+                self.push(DisplayItem::Image {
+                    rect: Rect::new(min, max),
+                    image: image.clone(),
+                });
+            }
+        }
+        let offset = lbox.bounds.min + parent_offset;
+        let child_clip = match lbox.clip {
+            Some(rect) => {
+                let rect = rect.translate(offset);
+                self.push(DisplayItem::PushClip {
+                    rect,
+                    radius: lbox.clip_radius,
+                });
+                Some(clip.map_or(rect, |clip| clip.intersection(rect)))
+            }
+            None => clip,
+        };
+        for child in &lbox.children {
+            self.push_box(tree, *child, offset, child_clip);
+        }
+        if lbox.clip.is_some() {
+            self.push(DisplayItem::PopClip);
+        }
+    }
+
+    pub fn push(&mut self, item: DisplayItem) {
+        self.items.push(item);
+    }
+
+    /// Push the shadows of the material, followed by a `Rect`, or a
+    /// `BorderedRect` when the material has any borders, rounded corners or a
+    /// gradient fill.
+    /// Shadows without any blur are pushed as rects in the shadow color.
+    pub fn push_material(&mut self, rect: Rect, mut material: Material) {
+        for shadow in std::mem::take(&mut material.shadows) {
+            let shadow_rect = shadow.rect(rect);
+            let radius = shadow.radius(material.radius);
+            if shadow.blur_radius > 0.0 {
+                self.push(DisplayItem::Shadow {
+                    rect: shadow_rect,
+                    radius,
+                    blur_radius: shadow.blur_radius,
+                    color: shadow.color,
+                });
+            } else {
+                let material = Material {
+                    radius,
+                    ..Material::filled(shadow.color)
+                };
+                self.push_material(shadow_rect, material);
+            }
+        }
+        match material.fill {
+            Paint::Solid(color)
+                if material.borders == Borders::none() && material.radius.is_zero() =>
+            {
+                self.push(DisplayItem::Rect { rect, color });
+            }
+            _ => self.push(DisplayItem::BorderedRect { rect, material }),
+        }
+    }
+
+    #[must_use]
+    pub fn items(&self) -> &[DisplayItem] {
+        &self.items
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DisplayItem> {
+        self.items.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<'a> IntoIterator for &'a DisplayList {
+    type Item = &'a DisplayItem;
+    type IntoIter = std::slice::Iter<'a, DisplayItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+/// One item per line. Items inside a clip are indented, so the output can be
+/// compared against a readable snapshot.
+impl fmt::Display for DisplayList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut depth = 0;
+        for item in &self.items {
+            if *item == DisplayItem::PopClip {
+                depth -= 1;
+            }
+            writeln!(f, "{}{}", "  ".repeat(depth), item)?;
+            if let DisplayItem::PushClip { .. } = item {
+                depth += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DisplayItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayItem::Rect { rect, color } => write!(f, "rect {rect} fill {color}"),
+            DisplayItem::BorderedRect { rect, material } => write!(f, "rect {rect} {material}"),
+            DisplayItem::Shadow {
+                rect,
+                radius,
+                blur_radius,
+                color,
+            } => {
+                write!(f, "shadow {rect} blur {blur_radius} {color}")?;
+                if !radius.is_zero() {
+                    write!(f, " radius {radius}")?;
+                }
+                Ok(())
+            }
+            DisplayItem::Line {
+                start,
+                end,
+                width,
+                color,
+            } => write!(f, "line {start} -> {end} {width} {color}"),
+            DisplayItem::PushClip { rect, radius } => {
+                write!(f, "push_clip {rect}")?;
+                if !radius.is_zero() {
+                    write!(f, " radius {radius}")?;
+                }
+                Ok(())
+            }
+            DisplayItem::PopClip => write!(f, "pop_clip"),
+            DisplayItem::Text { origin, run } => {
+                let text: String = run.glyphs.iter().map(|glyph| glyph.ch).collect();
+                let style = run.style;
+                write!(
+                    f,
+                    "text {origin} {}px {} {text:?}",
+                    style.font_size, style.color
+                )
+            }
+            DisplayItem::Image { rect, image } => write!(f, "image {rect} {image}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{
+        Alignment, BorderRadius, BorderSide, BoxConstraints, BoxFit, ColorStop, Container,
+        EdgeInsets, Image, Layout, LayoutBox, RadialGradient, Text, TextStyle,
+    };
+
+    #[test]
+    fn empty_tree_has_no_items() {
+        let list = DisplayList::from_tree(&LayoutTree::new());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn container_without_borders_is_a_rect() {
+        let container = Container {
+            color: Color::red(),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 50.0));
+        let expected = vec![DisplayItem::Rect {
+            rect: Rect::from_size((100.0, 50.0)),
+            color: Color::red(),
+        }];
+        assert_eq!(expected, list.items());
+    }
+
+    #[test]
+    fn container_with_borders_is_a_bordered_rect() {
+        let borders = Borders::all(Color::white(), 2.0);
+        let container = Container {
+            color: Color::red(),
+            borders,
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 50.0));
+        let expected = vec![DisplayItem::BorderedRect {
+            rect: Rect::from_size((100.0, 50.0)),
+            material: Material {
+                fill: Color::red().into(),
+                borders,
+                ..Material::default()
+            },
+        }];
+        assert_eq!(expected, list.items());
+    }
+
+    #[test]
+    fn container_with_border_radius_is_a_bordered_rect() {
+        let container = Container {
+            color: Color::red(),
+            border_radius: BorderRadius::all(8.0),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 50.0));
+        assert_eq!(
+            "rect [0, 0, 100, 50] fill rgba(255, 0, 0, 255) radius (8, 8, 8, 8)\n",
+            list.to_string()
+        );
+    }
+
+    #[test]
+    fn container_with_gradient_is_a_bordered_rect() {
+        let container = Container {
+            fill: Some(Paint::RadialGradient(RadialGradient::new(
+                Alignment::new(0.5, 0.5),
+                0.5,
+                vec![ColorStop::new(0.0, Color::red())],
+            ))),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 50.0));
+        assert_eq!(
+            "rect [0, 0, 100, 50] fill radial-gradient((0.5, 0.5) 0.5, rgba(255, 0, 0, 255) 0)\n",
+            list.to_string()
+        );
+    }
+
+    #[test]
+    fn rect_excludes_margin() {
+        let container = Container {
+            width: Some(50.0),
+            height: Some(50.0),
+            margin: EdgeInsets::all(10.0),
+            color: Color::red(),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 100.0));
+        let expected = vec![DisplayItem::Rect {
+            rect: Rect::new(Vector2::new(10.0, 10.0), Vector2::new(60.0, 60.0)),
+            color: Color::red(),
+        }];
+        assert_eq!(expected, list.items());
+    }
+
+    #[test]
+    fn children_are_painted_after_parent_at_absolute_position() {
+        let container = Container {
+            padding: EdgeInsets::all(10.0),
+            color: Color::red(),
+            child: Some(Box::new(Container {
+                width: Some(20.0),
+                height: Some(20.0),
+                color: Color::blue(),
+                ..Container::default()
+            })),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 100.0));
+        let expected = vec![
+            DisplayItem::Rect {
+                rect: Rect::from_size((100.0, 100.0)),
+                color: Color::red(),
+            },
+            DisplayItem::Rect {
+                rect: Rect::new(Vector2::new(10.0, 10.0), Vector2::new(30.0, 30.0)),
+                color: Color::blue(),
+            },
+        ];
+        assert_eq!(expected, list.items());
+    }
+
+    #[test]
+    fn nested_boxes_are_offset_by_every_ancestor() {
+        let mut tree = LayoutTree::new();
+        let leaf = tree.insert(LayoutBox {
+            bounds: Rect::from_pos((1.0, 1.0), (5.0, 5.0)),
+            material: Some(Material::filled(Color::blue())),
+            ..LayoutBox::default()
+        });
+        let middle = tree.insert(LayoutBox {
+            bounds: Rect::from_pos((10.0, 10.0), (50.0, 50.0)),
+            children: vec![leaf],
+            ..LayoutBox::default()
+        });
+        let root = tree.insert(LayoutBox {
+            bounds: Rect::from_pos((100.0, 100.0), (500.0, 500.0)),
+            children: vec![middle],
+            ..LayoutBox::default()
+        });
+        tree.set_root(Some(root));
+
+        let list = DisplayList::from_tree(&tree);
+        let expected = vec![DisplayItem::Rect {
+            rect: Rect::from_pos((111.0, 111.0), (5.0, 5.0)),
+            color: Color::blue(),
+        }];
+        assert_eq!(expected, list.items());
+    }
+
+    #[test]
+    fn children_of_clipping_box_are_wrapped_in_clip() {
+        let mut tree = LayoutTree::new();
+        let leaf = tree.insert(LayoutBox {
+            bounds: Rect::from_pos((-5.0, -5.0), (50.0, 50.0)),
+            material: Some(Material::filled(Color::blue())),
+            ..LayoutBox::default()
+        });
+        let root = tree.insert(LayoutBox {
+            bounds: Rect::from_pos((10.0, 10.0), (30.0, 30.0)),
+            margin: EdgeInsets::all(5.0),
+            material: Some(Material::filled(Color::red())),
+            children: vec![leaf],
+            clip: Some(Rect::from_pos((5.0, 5.0), (20.0, 20.0))),
+            clip_radius: BorderRadius::all(4.0),
+            ..LayoutBox::default()
+        });
+        tree.set_root(Some(root));
+
+        let expected = "\
+rect [15, 15, 35, 35] fill rgba(255, 0, 0, 255)
+push_clip [15, 15, 35, 35] radius (4, 4, 4, 4)
+  rect [5, 5, 55, 55] fill rgba(0, 0, 255, 255)
+pop_clip
+";
+        assert_eq!(expected, DisplayList::from_tree(&tree).to_string());
+    }
+
+    #[test]
+    fn text_is_painted_at_box_origin() {
+        let container = Container {
+            padding: EdgeInsets::all(5.0),
+            child: Some(Box::new(Text {
+                text: "hi".to_string(),
+                ..Text::default()
+            })),
+            ..Container::default()
+        };
+        let list = paint(&container, Vector2::new(100.0, 100.0));
+        match &list.items()[1] {
+            DisplayItem::Text { origin, run } => {
+                assert_eq!(Vector2::new(5.0, 5.0), *origin);
+                assert_eq!(2, run.glyphs.len());
+            }
+            item => panic!("expected text but got {item:?}"),
+        }
+    }
+
+    #[test]
+    fn image_is_painted_over_box() {
+        let image = Image {
+            source: "photo.png".to_string(),
+            width: Some(20.0),
+            height: Some(10.0),
+            fit: BoxFit::Cover,
+            ..Image::default()
+        };
+        let list = paint(&image, Vector2::new(100.0, 50.0));
+        assert_eq!(
+            "image [0, 0, 20, 10] \"photo.png\" cover\n",
+            list.to_string()
+        );
+    }
+
+    #[test]
+    fn display_list_prints_one_item_per_line() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::Rect {
+            rect: Rect::from_size((10.0, 20.0)),
+            color: Color::red(),
+        });
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_size((5.0, 5.0)),
+            radius: BorderRadius::top(2.0),
+        });
+        list.push_material(
+            Rect::from_size((10.0, 10.0)),
+            Material {
+                fill: Color::black().into(),
+                borders: Borders {
+                    top: Some(BorderSide::new(Color::white(), 1.0)),
+                    ..Borders::default()
+                },
+                ..Material::default()
+            },
+        );
+        list.push(DisplayItem::PopClip);
+        list.push(DisplayItem::Line {
+            start: Vector2::zero(),
+            end: Vector2::new(10.0, 0.0),
+            width: 1.0,
+            color: Color::green(),
+        });
+        list.push(DisplayItem::Text {
+            origin: Vector2::new(1.0, 2.0),
+            run: Text {
+                text: "ok".to_string(),
+                style: TextStyle {
+                    font_size: 12.0,
+                    ..TextStyle::default()
+                },
+                ..Text::default()
+            }
+            .layout(&mut LayoutTree::new(), &unbounded())
+            .text
+            .unwrap(),
+        });
+        list.push(DisplayItem::Image {
+            rect: Rect::from_size((8.0, 8.0)),
+            image: ImagePaint {
+                source: "icon.png".to_string(),
+                ..ImagePaint::default()
+            },
+        });
+
+        let expected = "\
+rect [0, 0, 10, 20] fill rgba(255, 0, 0, 255)
+push_clip [0, 0, 5, 5] radius (2, 2, 0, 0)
+  rect [0, 0, 10, 10] fill rgba(0, 0, 0, 255) top 1 rgba(255, 255, 255, 255)
+pop_clip
+line (0, 0) -> (10, 0) 1 rgba(0, 255, 0, 255)
+text (1, 2) 12px rgba(255, 255, 255, 255) \"ok\"
+image [0, 0, 8, 8] \"icon.png\" contain
+";
+        assert_eq!(expected, list.to_string());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn paint(widget: &dyn Layout, viewport: Vector2) -> DisplayList {
+        let tree = LayoutTree::from_root(widget, &BoxConstraints::from_max(viewport));
+        DisplayList::from_tree(&tree)
+    }
+
+    fn unbounded() -> BoxConstraints {
+        BoxConstraints::from_max(Vector2::new(f32::INFINITY, f32::INFINITY))
+    }
+}
@@ -0,0 +1,378 @@
+use crate::{DisplayItem, DisplayList};
+use layout::{
+    BorderRadius, BorderSide, BoxShadow, Color, ColorStop, GlyphRun, ImagePaint, ImageRepeat,
+    Material, Paint,
+};
+use math::{Rect, Vector2};
+use std::fmt::Write;
+
+impl DisplayList {
+    /// Serialize the list as a JSON array with one object per item, so it can
+    /// be saved, diffed or read by other tools. Each object has a `"type"`
+    /// named like the item in the text dump, e.g.
+    /// `{"type":"rect","rect":[0,0,10,20],"color":[255,0,0,255]}`.
+    ///
+    /// Rects are `[min.x, min.y, max.x, max.y]`, points are `[x, y]`, colors
+    /// are `[r, g, b, a]` from 0 - 255 and radii are clockwise from the
+    /// top-left corner. Numbers that JSON can't represent are written as
+    /// `null`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        json.push('[');
+        for (i, item) in self.items().iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_item(&mut json, item);
+        }
+        json.push(']');
+        json
+    }
+}
+
+fn write_item(json: &mut String, item: &DisplayItem) {
+    match item {
+        DisplayItem::Rect { rect, color } => {
+            json.push_str(r#"{"type":"rect","rect":"#);
+            write_rect(json, *rect);
+            json.push_str(r#","color":"#);
+            write_color(json, *color);
+        }
+        DisplayItem::BorderedRect { rect, material } => {
+            json.push_str(r#"{"type":"bordered_rect","rect":"#);
+            write_rect(json, *rect);
+            json.push_str(r#","material":"#);
+            write_material(json, material);
+        }
+        DisplayItem::Shadow {
+            rect,
+            radius,
+            blur_radius,
+            color,
+        } => {
+            json.push_str(r#"{"type":"shadow","rect":"#);
+            write_rect(json, *rect);
+            json.push_str(r#","radius":"#);
+            write_radius(json, *radius);
+            json.push_str(r#","blur_radius":"#);
+            write_number(json, *blur_radius);
+            json.push_str(r#","color":"#);
+            write_color(json, *color);
+        }
+        DisplayItem::Line {
+            start,
+            end,
+            width,
+            color,
+        } => {
+            json.push_str(r#"{"type":"line","start":"#);
+            write_point(json, *start);
+            json.push_str(r#","end":"#);
+            write_point(json, *end);
+            json.push_str(r#","width":"#);
+            write_number(json, *width);
+            json.push_str(r#","color":"#);
+            write_color(json, *color);
+        }
+        DisplayItem::PushClip { rect, radius } => {
+            json.push_str(r#"{"type":"push_clip","rect":"#);
+            write_rect(json, *rect);
+            json.push_str(r#","radius":"#);
+            write_radius(json, *radius);
+        }
+        DisplayItem::PopClip => json.push_str(r#"{"type":"pop_clip""#),
+        DisplayItem::Text { origin, run } => {
+            json.push_str(r#"{"type":"text","origin":"#);
+            write_point(json, *origin);
+            write_run(json, run);
+        }
+        DisplayItem::Image { rect, image } => {
+            json.push_str(r#"{"type":"image","rect":"#);
+            write_rect(json, *rect);
+            write_image(json, image);
+        }
+    }
+    json.push('}');
+}
+
+fn write_material(json: &mut String, material: &Material) {
+    json.push_str(r#"{"fill":"#);
+    write_paint(json, &material.fill);
+    let sides = material.borders.to_array();
+    for (name, side) in ["top", "bottom", "left", "right"].iter().zip(sides) {
+        let _ = write!(json, r#","{name}":"#);
+        write_border(json, side);
+    }
+    json.push_str(r#","radius":"#);
+    write_radius(json, material.radius);
+    json.push_str(r#","shadows":["#);
+    for (i, shadow) in material.shadows.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_shadow(json, shadow);
+    }
+    json.push_str("]}");
+}
+
+fn write_paint(json: &mut String, paint: &Paint) {
+    let stops = match paint {
+        Paint::Solid(color) => {
+            json.push_str(r#"{"type":"solid","color":"#);
+            write_color(json, *color);
+            json.push('}');
+            return;
+        }
+        Paint::LinearGradient(gradient) => {
+            json.push_str(r#"{"type":"linear_gradient","angle":"#);
+            write_number(json, gradient.angle);
+            &gradient.stops
+        }
+        Paint::RadialGradient(gradient) => {
+            json.push_str(r#"{"type":"radial_gradient","center":"#);
+            write_point(json, Vector2::new(gradient.center.x, gradient.center.y));
+            json.push_str(r#","radius":"#);
+            write_number(json, gradient.radius);
+            &gradient.stops
+        }
+    };
+    json.push_str(r#","stops":["#);
+    for (i, stop) in stops.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_stop(json, *stop);
+    }
+    json.push_str("]}");
+}
+
+fn write_stop(json: &mut String, stop: ColorStop) {
+    json.push_str(r#"{"offset":"#);
+    write_number(json, stop.offset);
+    json.push_str(r#","color":"#);
+    write_color(json, stop.color);
+    json.push('}');
+}
+
+// Missing borders are `null`
+fn write_border(json: &mut String, side: Option<BorderSide>) {
+    let Some(side) = side else {
+        json.push_str("null");
+        return;
+    };
+    json.push_str(r#"{"width":"#);
+    write_number(json, side.width);
+    json.push_str(r#","color":"#);
+    write_color(json, side.color);
+    json.push('}');
+}
+
+fn write_shadow(json: &mut String, shadow: &BoxShadow) {
+    json.push_str(r#"{"offset":"#);
+    write_point(json, shadow.offset);
+    json.push_str(r#","blur_radius":"#);
+    write_number(json, shadow.blur_radius);
+    json.push_str(r#","spread":"#);
+    write_number(json, shadow.spread);
+    json.push_str(r#","color":"#);
+    write_color(json, shadow.color);
+    json.push('}');
+}
+
+fn write_run(json: &mut String, run: &GlyphRun) {
+    let text: String = run.glyphs.iter().map(|glyph| glyph.ch).collect();
+    json.push_str(r#","text":"#);
+    write_string(json, &text);
+    json.push_str(r#","font_size":"#);
+    write_number(json, run.style.font_size);
+    json.push_str(r#","color":"#);
+    write_color(json, run.style.color);
+    // The glyph positions are relative to the origin
+    json.push_str(r#","positions":["#);
+    for (i, glyph) in run.glyphs.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_point(json, glyph.position);
+    }
+    json.push(']');
+}
+
+fn write_image(json: &mut String, image: &ImagePaint) {
+    json.push_str(r#","source":"#);
+    write_string(json, &image.source);
+    json.push_str(r#","fit":"#);
+    write_string(json, &image.fit.to_string());
+    json.push_str(r#","alignment":"#);
+    write_point(json, Vector2::new(image.alignment.x, image.alignment.y));
+    let repeat = match image.repeat {
+        ImageRepeat::NoRepeat => "no-repeat",
+        ImageRepeat::Repeat => "repeat",
+        ImageRepeat::RepeatX => "repeat-x",
+        ImageRepeat::RepeatY => "repeat-y",
+    };
+    json.push_str(r#","repeat":"#);
+    write_string(json, repeat);
+}
+
+fn write_rect(json: &mut String, rect: Rect) {
+    write_numbers(json, &[rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
+}
+
+fn write_point(json: &mut String, point: Vector2) {
+    write_numbers(json, &[point.x, point.y]);
+}
+
+fn write_color(json: &mut String, color: Color) {
+    write_numbers(json, &[color.r, color.g, color.b, color.a]);
+}
+
+fn write_radius(json: &mut String, radius: BorderRadius) {
+    write_numbers(json, &radius.to_array());
+}
+
+fn write_numbers(json: &mut String, numbers: &[f32]) {
+    json.push('[');
+    for (i, number) in numbers.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_number(json, *number);
+    }
+    json.push(']');
+}
+
+fn write_number(json: &mut String, number: f32) {
+    if number.is_finite() {
+        let _ = write!(json, "{number}");
+    } else {
+        json.push_str("null");
+    }
+}
+
+fn write_string(json: &mut String, string: &str) {
+    json.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => json.push_str(r#"\""#),
+            '\\' => json.push_str(r"\\"),
+            '\n' => json.push_str(r"\n"),
+            '\r' => json.push_str(r"\r"),
+            '\t' => json.push_str(r"\t"),
+            ch if u32::from(ch) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", u32::from(ch));
+            }
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{Alignment, Borders, BoxFit, LinearGradient};
+
+    #[test]
+    fn empty_list_is_an_empty_array() {
+        assert_eq!("[]", DisplayList::new().to_json());
+    }
+
+    #[test]
+    fn items_are_objects_tagged_with_their_type() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_size((5.0, 5.0)),
+            radius: BorderRadius::all(1.0),
+        });
+        list.push(DisplayItem::Rect {
+            rect: Rect::from_size((10.0, 20.5)),
+            color: Color::red(),
+        });
+        list.push(DisplayItem::PopClip);
+        list.push(DisplayItem::Shadow {
+            rect: Rect::from_size((4.0, 4.0)),
+            radius: BorderRadius::all(2.0),
+            blur_radius: 3.0,
+            color: Color::black(),
+        });
+        let expected = concat!(
+            r#"[{"type":"push_clip","rect":[0,0,5,5],"radius":[1,1,1,1]},"#,
+            r#"{"type":"rect","rect":[0,0,10,20.5],"color":[255,0,0,255]},"#,
+            r#"{"type":"pop_clip"},"#,
+            r#"{"type":"shadow","rect":[0,0,4,4],"radius":[2,2,2,2],"blur_radius":3,"color":[0,0,0,255]}]"#,
+        );
+        assert_eq!(expected, list.to_json());
+    }
+
+    #[test]
+    fn line_has_its_end_points_and_width() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::Line {
+            start: Vector2::new(1.0, 2.0),
+            end: Vector2::new(11.0, 2.0),
+            width: 1.5,
+            color: Color::green(),
+        });
+        assert_eq!(
+            r#"[{"type":"line","start":[1,2],"end":[11,2],"width":1.5,"color":[0,255,0,255]}]"#,
+            list.to_json()
+        );
+    }
+
+    #[test]
+    fn bordered_rect_includes_every_part_of_the_material() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::BorderedRect {
+            rect: Rect::from_size((10.0, 10.0)),
+            material: Material {
+                fill: Paint::LinearGradient(LinearGradient::new(
+                    90.0,
+                    vec![ColorStop::new(0.0, Color::red())],
+                )),
+                borders: Borders::left(Color::blue(), 2.0),
+                ..Material::default()
+            },
+        });
+        let expected = concat!(
+            r#"[{"type":"bordered_rect","rect":[0,0,10,10],"material":{"#,
+            r#""fill":{"type":"linear_gradient","angle":90,"stops":[{"offset":0,"color":[255,0,0,255]}]},"#,
+            r#""top":null,"bottom":null,"left":{"width":2,"color":[0,0,255,255]},"right":null,"#,
+            r#""radius":[0,0,0,0],"shadows":[]}}]"#,
+        );
+        assert_eq!(expected, list.to_json());
+    }
+
+    #[test]
+    fn image_source_is_escaped() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::Image {
+            rect: Rect::from_size((8.0, 8.0)),
+            image: ImagePaint {
+                source: "a \"b\"\\c\n".to_string(),
+                fit: BoxFit::Cover,
+                alignment: Alignment::new(0.5, 0.5),
+                ..ImagePaint::default()
+            },
+        });
+        let expected = concat!(
+            r#"[{"type":"image","rect":[0,0,8,8],"source":"a \"b\"\\c\n","#,
+            r#""fit":"cover","alignment":[0.5,0.5],"repeat":"no-repeat"}]"#,
+        );
+        assert_eq!(expected, list.to_json());
+    }
+
+    #[test]
+    fn infinite_numbers_are_null() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::PushClip {
+            rect: Rect::new(Vector2::zero(), Vector2::new(f32::INFINITY, 1.0)),
+            radius: BorderRadius::zero(),
+        });
+        assert_eq!(
+            r#"[{"type":"push_clip","rect":[0,0,null,1],"radius":[0,0,0,0]}]"#,
+            list.to_json()
+        );
+    }
+}
@@ -0,0 +1,6 @@
+#![warn(clippy::all, clippy::pedantic)]
+
+mod display_list;
+pub use display_list::*;
+
+mod json;
@@ -0,0 +1,228 @@
+use crate::AppDriver;
+use layout::Layout;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+/// An app whose state only changes in response to messages, in the style of
+/// the Elm architecture.
+///
+/// `view` describes the UI for the current state. Event handlers in the view
+/// don't change the state directly. Instead, they send a `Message` through the
+/// `Sender`, and the messages are passed to `update` one at a time before the
+/// next frame is built. This keeps every state change in one place, so an app
+/// can be tested by calling `update` and inspecting the result.
+pub trait Application {
+    type Message: 'static;
+
+    fn update(&mut self, message: Self::Message);
+
+    fn view(&self, sender: &Sender<Self::Message>) -> Box<dyn Layout>;
+
+    /// Called at the start of every frame with the time in milliseconds. Apps
+    /// that animate can return a message to update their state with.
+    fn frame(&self, _time: f32) -> Option<Self::Message> {
+        None
+    }
+
+    /// See `AppDriver::is_animating`.
+    fn is_animating(&self) -> bool {
+        true
+    }
+}
+
+/// Queues messages for an `Application`. It is cheap to clone, so it can be
+/// moved into as many event handlers as needed.
+pub struct Sender<M> {
+    queue: Rc<RefCell<VecDeque<M>>>,
+}
+
+impl<M> Sender<M> {
+    #[must_use]
+    pub fn new() -> Sender<M> {
+        Sender {
+            queue: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    pub fn send(&self, message: M) {
+        self.queue.borrow_mut().push_back(message);
+    }
+
+    // Take the oldest message. The queue isn't borrowed while the message is
+    // handled, so handlers can keep sending.
+    fn next(&self) -> Option<M> {
+        self.queue.borrow_mut().pop_front()
+    }
+}
+
+impl<M> Default for Sender<M> {
+    fn default() -> Sender<M> {
+        Sender::new()
+    }
+}
+
+// Derive would require `M: Clone`, but only the `Rc` is cloned.
+impl<M> Clone for Sender<M> {
+    fn clone(&self) -> Sender<M> {
+        Sender {
+            queue: Rc::clone(&self.queue),
+        }
+    }
+}
+
+impl<M> fmt::Debug for Sender<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("queued", &self.queue.borrow().len())
+            .finish()
+    }
+}
+
+/// Runs an `Application` as an `AppDriver`.
+///
+/// Every tick it handles the messages that were sent since the last frame,
+/// then builds the view from the new state.
+pub struct Program<A: Application> {
+    app: A,
+    sender: Sender<A::Message>,
+}
+
+impl<A: Application> Program<A> {
+    pub fn new(app: A) -> Program<A> {
+        Program {
+            app,
+            sender: Sender::new(),
+        }
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    pub fn sender(&self) -> &Sender<A::Message> {
+        &self.sender
+    }
+
+    /// Pass every queued message to `update`, in the order they were sent.
+    pub fn process_messages(&mut self) {
+        while let Some(message) = self.sender.next() {
+            self.app.update(message);
+        }
+    }
+}
+
+impl<A: Application> AppDriver for Program<A> {
+    fn tick(&mut self, time: f32) -> Box<dyn Layout> {
+        if let Some(message) = self.app.frame(time) {
+            self.sender.send(message);
+        }
+        self.process_messages();
+        self.app.view(&self.sender)
+    }
+
+    fn is_animating(&self) -> bool {
+        self.app.is_animating()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{
+        BoxConstraints, Container, EventStatus, LayoutTree, Listener, PointerEvent,
+        PointerEventKind, PointerHandler,
+    };
+    use math::Vector2;
+
+    #[derive(Debug)]
+    enum Message {
+        Increment,
+        Set(u32),
+        Frame(f32),
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        count: u32,
+        time: f32,
+        // The count that each view was built with
+        views: RefCell<Vec<u32>>,
+    }
+
+    impl Application for Counter {
+        type Message = Message;
+
+        fn update(&mut self, message: Message) {
+            match message {
+                Message::Increment => self.count += 1,
+                Message::Set(count) => self.count = count,
+                Message::Frame(time) => self.time = time,
+            }
+        }
+
+        fn view(&self, sender: &Sender<Message>) -> Box<dyn Layout> {
+            self.views.borrow_mut().push(self.count);
+            let sender = sender.clone();
+            Box::new(Listener {
+                on_pointer: PointerHandler::new(move |event| {
+                    if event.kind != PointerEventKind::Down {
+                        return EventStatus::Ignored;
+                    }
+                    sender.send(Message::Increment);
+                    EventStatus::Handled
+                }),
+                child: Box::new(Container {
+                    width: Some(10.0),
+                    height: Some(10.0),
+                    ..Container::default()
+                }),
+            })
+        }
+
+        fn frame(&self, time: f32) -> Option<Message> {
+            Some(Message::Frame(time))
+        }
+    }
+
+    #[test]
+    fn messages_from_handlers_update_the_next_frame() {
+        let mut program = Program::new(Counter::default());
+        let widget = program.tick(0.0);
+        let tree = LayoutTree::from_root(widget.as_ref(), &constraints());
+        let _ = tree.dispatch_pointer_event(&pointer_down());
+        let _ = tree.dispatch_pointer_event(&pointer_down());
+        assert_eq!(0, program.app().count);
+
+        let _ = program.tick(16.0);
+        assert_eq!(2, program.app().count);
+        assert_eq!(vec![0, 2], *program.app().views.borrow());
+    }
+
+    #[test]
+    fn messages_are_handled_in_order_they_were_sent() {
+        let mut program = Program::new(Counter::default());
+        program.sender().send(Message::Set(5));
+        program.sender().send(Message::Increment);
+        program.sender().send(Message::Set(2));
+        program.sender().send(Message::Increment);
+        program.process_messages();
+        assert_eq!(3, program.app().count);
+    }
+
+    #[test]
+    fn frame_message_is_handled_before_view() {
+        let mut program = Program::new(Counter::default());
+        let _ = program.tick(42.0);
+        assert!((program.app().time - 42.0).abs() < f32::EPSILON);
+    }
+
+    fn constraints() -> BoxConstraints {
+        BoxConstraints::from_max(Vector2::new(100.0, 100.0))
+    }
+
+    fn pointer_down() -> PointerEvent {
+        PointerEvent::new(PointerEventKind::Down, 0, Vector2::new(5.0, 5.0))
+    }
+}
@@ -0,0 +1,360 @@
+use math::{Rect, Vector2};
+use std::collections::HashMap;
+
+/// Identifies a rasterized glyph in the atlas. The same character rendered at
+/// different font sizes is stored separately.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct GlyphKey {
+    pub ch: char,
+    // `f32` can't be hashed, so store the raw bits instead.
+    font_size_bits: u32,
+}
+
+impl GlyphKey {
+    #[must_use]
+    pub fn new(ch: char, font_size: f32) -> GlyphKey {
+        GlyphKey {
+            ch,
+            font_size_bits: font_size.to_bits(),
+        }
+    }
+
+    #[must_use]
+    pub fn font_size(&self) -> f32 {
+        f32::from_bits(self.font_size_bits)
+    }
+}
+
+/// A region of the atlas texture, in pixels.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRect {
+    /// Get the texture coordinates of the region, normalized between 0 and 1.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn uv(&self, atlas_width: u32, atlas_height: u32) -> Rect {
+        let size = Vector2::new(atlas_width as f32, atlas_height as f32);
+        let min = Vector2::new(self.x as f32, self.y as f32) / size;
+        let max = Vector2::new((self.x + self.width) as f32, (self.y + self.height) as f32) / size;
+        Rect::new(min, max)
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AtlasError {
+    /// The glyph is larger than the whole atlas.
+    TooLarge,
+    /// Every region that could fit the glyph holds glyphs that are used by the
+    /// current frame, so nothing can be evicted.
+    Full,
+}
+
+impl std::fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AtlasError::TooLarge => write!(f, "glyph is larger than the atlas"),
+            AtlasError::Full => write!(f, "glyph atlas is full"),
+        }
+    }
+}
+
+impl std::error::Error for AtlasError {}
+
+// A horizontal strip of the atlas. Glyphs are packed left-to-right along the
+// shelf, and every glyph on a shelf is evicted together.
+#[derive(Clone, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    cursor: u32,
+    last_used: u64,
+    glyphs: Vec<GlyphKey>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct AtlasEntry {
+    rect: AtlasRect,
+    shelf: usize,
+}
+
+/// Decides where each glyph lives in the glyph texture.
+///
+/// This only does the bookkeeping. It has no knowledge of the GPU, so the
+/// driver is responsible for rasterizing a glyph into the region returned by
+/// `allocate`. Glyphs are packed onto shelves. When there is no space left,
+/// the least recently used shelf that wasn't used in the current frame is
+/// cleared and reused.
+#[derive(Clone, Debug)]
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    padding: u32,
+    frame: u64,
+    shelves: Vec<Shelf>,
+    entries: HashMap<GlyphKey, AtlasEntry>,
+}
+
+impl GlyphAtlas {
+    /// Create an empty atlas. `padding` is the number of empty pixels left
+    /// around each glyph, to stop neighbouring glyphs bleeding into each other
+    /// when the texture is sampled.
+    #[must_use]
+    pub fn new(width: u32, height: u32, padding: u32) -> GlyphAtlas {
+        GlyphAtlas {
+            width,
+            height,
+            padding,
+            frame: 0,
+            shelves: vec![],
+            entries: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Start a new frame. Glyphs used during the current frame are never
+    /// evicted, so this must be called before painting each frame.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Look up a glyph that has already been rasterized, and mark it as used
+    /// by the current frame.
+    pub fn get(&mut self, key: GlyphKey) -> Option<AtlasRect> {
+        let entry = self.entries.get(&key)?;
+        self.shelves[entry.shelf].last_used = self.frame;
+        Some(entry.rect)
+    }
+
+    /// Reserve a region for a new glyph. The caller must rasterize the glyph
+    /// into the returned region, which may previously have held glyphs that
+    /// were evicted.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error when the glyph can never fit in the atlas, or when
+    /// there is no space left that isn't being used by the current frame.
+    pub fn allocate(
+        &mut self,
+        key: GlyphKey,
+        width: u32,
+        height: u32,
+    ) -> Result<AtlasRect, AtlasError> {
+        if let Some(rect) = self.get(key) {
+            return Ok(rect);
+        }
+        let padded_width = width + self.padding * 2;
+        let padded_height = height + self.padding * 2;
+        if padded_width > self.width || padded_height > self.height {
+            return Err(AtlasError::TooLarge);
+        }
+        let shelf = self
+            .find_shelf(padded_width, padded_height)
+            .or_else(|| self.push_shelf(padded_height))
+            .or_else(|| self.evict_shelf(padded_height))
+            .ok_or(AtlasError::Full)?;
+
+        let Shelf { y, cursor, .. } = self.shelves[shelf];
+        let rect = AtlasRect {
+            x: cursor + self.padding,
+            y: y + self.padding,
+            width,
+            height,
+        };
+        let shelf_ref = &mut self.shelves[shelf];
+        shelf_ref.cursor += padded_width;
+        shelf_ref.last_used = self.frame;
+        shelf_ref.glyphs.push(key);
+        self.entries.insert(key, AtlasEntry { rect, shelf });
+        Ok(rect)
+    }
+
+    // Find the shortest shelf that has room for the glyph. Shelves that are
+    // much taller than the glyph are skipped so small glyphs don't waste space.
+    fn find_shelf(&self, width: u32, height: u32) -> Option<usize> {
+        self.shelves
+            .iter()
+            .enumerate()
+            .filter(|(_, shelf)| {
+                shelf.height >= height
+                    && shelf.height <= height * 2
+                    && self.width - shelf.cursor >= width
+            })
+            .min_by_key(|(_, shelf)| shelf.height)
+            .map(|(i, _)| i)
+    }
+
+    // Open a new shelf below the existing ones, if there is room.
+    fn push_shelf(&mut self, height: u32) -> Option<usize> {
+        let y = self
+            .shelves
+            .last()
+            .map_or(0, |shelf| shelf.y + shelf.height);
+        if y + height > self.height {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            y,
+            height,
+            cursor: 0,
+            last_used: self.frame,
+            glyphs: vec![],
+        });
+        Some(self.shelves.len() - 1)
+    }
+
+    // Clear the least recently used shelf that is tall enough for the glyph.
+    fn evict_shelf(&mut self, height: u32) -> Option<usize> {
+        let frame = self.frame;
+        let (index, _) = self
+            .shelves
+            .iter()
+            .enumerate()
+            .filter(|(_, shelf)| shelf.height >= height && shelf.last_used < frame)
+            .min_by_key(|(_, shelf)| shelf.last_used)?;
+        let shelf = &mut self.shelves[index];
+        for key in shelf.glyphs.drain(..) {
+            self.entries.remove(&key);
+        }
+        shelf.cursor = 0;
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_packs_glyphs_left_to_right() {
+        let mut atlas = GlyphAtlas::new(100, 100, 0);
+        let a = atlas.allocate(key('a'), 10, 20).unwrap();
+        let b = atlas.allocate(key('b'), 10, 20).unwrap();
+        assert_eq!(rect(0, 0, 10, 20), a);
+        assert_eq!(rect(10, 0, 10, 20), b);
+    }
+
+    #[test]
+    fn atlas_starts_new_shelf_when_shelf_is_full() {
+        let mut atlas = GlyphAtlas::new(20, 100, 0);
+        atlas.allocate(key('a'), 10, 20).unwrap();
+        atlas.allocate(key('b'), 10, 20).unwrap();
+        let c = atlas.allocate(key('c'), 10, 20).unwrap();
+        assert_eq!(rect(0, 20, 10, 20), c);
+    }
+
+    #[test]
+    fn atlas_starts_new_shelf_for_much_taller_glyph() {
+        let mut atlas = GlyphAtlas::new(100, 100, 0);
+        atlas.allocate(key('a'), 10, 10).unwrap();
+        let b = atlas.allocate(key('b'), 10, 30).unwrap();
+        let c = atlas.allocate(key('c'), 10, 8).unwrap();
+        assert_eq!(rect(0, 10, 10, 30), b);
+        assert_eq!(rect(10, 0, 10, 8), c);
+    }
+
+    #[test]
+    fn atlas_adds_padding_around_glyphs() {
+        let mut atlas = GlyphAtlas::new(100, 100, 1);
+        let a = atlas.allocate(key('a'), 10, 20).unwrap();
+        let b = atlas.allocate(key('b'), 10, 20).unwrap();
+        assert_eq!(rect(1, 1, 10, 20), a);
+        assert_eq!(rect(13, 1, 10, 20), b);
+    }
+
+    #[test]
+    fn atlas_returns_cached_glyph() {
+        let mut atlas = GlyphAtlas::new(100, 100, 0);
+        let first = atlas.allocate(key('a'), 10, 20).unwrap();
+        let second = atlas.allocate(key('a'), 10, 20).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(Some(first), atlas.get(key('a')));
+        assert_eq!(1, atlas.len());
+    }
+
+    #[test]
+    fn atlas_stores_font_sizes_separately() {
+        let mut atlas = GlyphAtlas::new(100, 100, 0);
+        atlas.allocate(GlyphKey::new('a', 12.0), 10, 20).unwrap();
+        assert_eq!(None, atlas.get(GlyphKey::new('a', 14.0)));
+    }
+
+    #[test]
+    fn atlas_rejects_glyph_larger_than_atlas() {
+        let mut atlas = GlyphAtlas::new(10, 10, 0);
+        assert_eq!(Err(AtlasError::TooLarge), atlas.allocate(key('a'), 11, 5));
+    }
+
+    #[test]
+    fn atlas_evicts_least_recently_used_shelf() {
+        let mut atlas = GlyphAtlas::new(10, 20, 0);
+        atlas.allocate(key('a'), 10, 10).unwrap();
+        atlas.begin_frame();
+        atlas.allocate(key('b'), 10, 10).unwrap();
+        atlas.begin_frame();
+        atlas.get(key('a'));
+        atlas.begin_frame();
+
+        let c = atlas.allocate(key('c'), 10, 10).unwrap();
+        assert_eq!(rect(0, 10, 10, 10), c);
+        assert_eq!(None, atlas.get(key('b')));
+        assert!(atlas.get(key('a')).is_some());
+    }
+
+    #[test]
+    fn atlas_does_not_evict_glyphs_used_this_frame() {
+        let mut atlas = GlyphAtlas::new(10, 10, 0);
+        atlas.allocate(key('a'), 10, 10).unwrap();
+        assert_eq!(Err(AtlasError::Full), atlas.allocate(key('b'), 10, 10));
+        atlas.begin_frame();
+        assert!(atlas.allocate(key('b'), 10, 10).is_ok());
+        assert_eq!(None, atlas.get(key('a')));
+    }
+
+    #[test]
+    fn atlas_rect_uv_is_normalized() {
+        let uv = rect(10, 20, 30, 40).uv(100, 100);
+        assert_eq!(Rect::new((0.1, 0.2).into(), (0.4, 0.6).into()), uv);
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    fn key(ch: char) -> GlyphKey {
+        GlyphKey::new(ch, 12.0)
+    }
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> AtlasRect {
+        AtlasRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
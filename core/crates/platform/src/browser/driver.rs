@@ -4,12 +4,21 @@ use std::rc::Rc;
 use wasm_bindgen::prelude::wasm_bindgen;
 use web_sys::HtmlCanvasElement;
 
-use super::shaders::ShaderLibrary;
+use super::glyphs::GlyphCache;
+use super::shaders::{push_glyph_quad, push_image_quad, ShaderLibrary};
+use super::textures::{ImageRequests, TextureCache};
 use super::util::try_get_canvas;
 use super::WebGl;
+use crate::atlas::GlyphKey;
+use crate::software::Pixmap;
 use crate::AppDriver;
-use layout::{Color, LayoutBox, LayoutTree, Material};
-use math::{Rect, Vector2, Vector3};
+use layout::{
+    BorderRadius, Color, ElementTree, EventStatus, FocusManager, GlyphRun, ImagePaint, ImageSizes,
+    KeyEvent, KeyEventKind, KeyboardEvent, LayoutTree, Material, Modifiers, PointerEvent,
+    PointerEventKind, PointerRouter,
+};
+use math::{Rect, Vector2};
+use paint::{DisplayItem, DisplayList};
 
 #[wasm_bindgen]
 extern "C" {
@@ -27,30 +36,155 @@ pub struct BrowserDriver {
     canvas: HtmlCanvasElement,
     gl: Rc<WebGl>,
     shaders: ShaderLibrary,
+    glyphs: GlyphCache,
+    // Every image that has been loaded, keyed by its source
+    textures: TextureCache,
+    // Images that are used by the app, and still need to be loaded by JS
+    image_requests: ImageRequests,
     app: Box<dyn AppDriver>,
+    // The tree from the last frame. Pointer events are hit-tested against it,
+    // because that is what the user is looking at.
+    tree: LayoutTree,
+    // Sends the events of a pressed pointer to the widgets it went down on
+    pointers: PointerRouter,
+    focus: FocusManager,
+    // The state of stateful widgets, which is kept between frames
+    elements: ElementTree,
 }
 
 #[wasm_bindgen]
 impl BrowserDriver {
-    pub fn tick(&mut self, time: f32) {
+    // Returns whether the app is still animating, and needs another frame
+    // even if there is no input.
+    pub fn tick(&mut self, time: f32) -> bool {
         self.try_tick(time).unwrap();
+        self.app.is_animating() || self.elements.is_animating()
+    }
+
+    // `time` is the event's `timeStamp`, which is on the same clock as the
+    // frame times passed to `tick`.
+    pub fn pointer_down(&mut self, x: f32, y: f32, pointer_id: i32, time: f32) {
+        self.dispatch_pointer(PointerEventKind::Down, x, y, pointer_id, time);
+    }
+
+    pub fn pointer_move(&mut self, x: f32, y: f32, pointer_id: i32, time: f32) {
+        self.dispatch_pointer(PointerEventKind::Move, x, y, pointer_id, time);
+    }
+
+    pub fn pointer_up(&mut self, x: f32, y: f32, pointer_id: i32, time: f32) {
+        self.dispatch_pointer(PointerEventKind::Up, x, y, pointer_id, time);
+    }
+
+    pub fn pointer_cancel(&mut self, x: f32, y: f32, pointer_id: i32, time: f32) {
+        self.dispatch_pointer(PointerEventKind::Cancel, x, y, pointer_id, time);
+    }
+
+    // `delta_x` and `delta_y` are in pixels. Returns whether the event was
+    // handled, so the page can stop the browser from scrolling too.
+    pub fn pointer_wheel(&mut self, x: f32, y: f32, delta_x: f32, delta_y: f32) -> bool {
+        let event = PointerEvent::wheel(0, Vector2::new(x, y), Vector2::new(delta_x, delta_y));
+        self.tree.dispatch_pointer_event(&event) == EventStatus::Handled
+    }
+
+    // Returns whether the event was handled, so the page can call
+    // `preventDefault` and stop the browser from also acting on it.
+    #[allow(clippy::fn_params_excessive_bools)]
+    pub fn key_down(
+        &mut self,
+        key: &str,
+        shift: bool,
+        ctrl: bool,
+        alt: bool,
+        meta: bool,
+        repeat: bool,
+    ) -> bool {
+        let modifiers = Modifiers {
+            shift,
+            ctrl,
+            alt,
+            meta,
+        };
+        self.dispatch_key(KeyEventKind::Down, key, modifiers, repeat)
+    }
+
+    #[allow(clippy::fn_params_excessive_bools)]
+    pub fn key_up(&mut self, key: &str, shift: bool, ctrl: bool, alt: bool, meta: bool) -> bool {
+        let modifiers = Modifiers {
+            shift,
+            ctrl,
+            alt,
+            meta,
+        };
+        self.dispatch_key(KeyEventKind::Up, key, modifiers, false)
+    }
+
+    pub fn text_input(&mut self, text: &str) -> bool {
+        let event = KeyboardEvent::TextInput(text.to_string());
+        self.focus.dispatch(&self.tree, &event) == EventStatus::Handled
+    }
+
+    // `bytes` is a PNG file. Image widgets with the same `source` draw it from
+    // the next frame onwards.
+    pub fn load_image(&mut self, source: &str, bytes: &[u8]) {
+        if let Err(err) = self.try_load_image(source, bytes) {
+            error(&format!("could not load image {source}: {err}"));
+        }
+    }
+
+    // The sources of the images that were laid out since the last call and
+    // haven't been asked for before. JS fetches each one and passes it to
+    // `load_image`.
+    pub fn take_image_requests(&mut self) -> Vec<String> {
+        self.image_requests.take()
     }
 }
 
 impl BrowserDriver {
-    pub fn try_new(canvas_id: &str, app: Box<dyn AppDriver>) -> Result<BrowserDriver, Error> {
+    /// Create a driver that draws into the canvas with `canvas_id`. The app
+    /// is built by `build_app`, which is given the sizes of the images the
+    /// driver loads for its `Image` widgets to measure themselves with.
+    pub fn try_new<F>(canvas_id: &str, build_app: F) -> Result<BrowserDriver, Error>
+    where
+        F: FnOnce(ImageSizes) -> Box<dyn AppDriver>,
+    {
         let canvas = try_get_canvas(canvas_id)?;
         let gl = WebGl::try_new(&canvas)?;
         let gl = Rc::new(gl);
         let shaders = ShaderLibrary::try_new(&gl)?;
+        let glyphs = GlyphCache::try_new(&gl)?;
+        let textures = TextureCache::new(&gl);
+        let app = build_app(textures.sizes());
+        // Widgets read and move focus through the element tree's clone
+        let focus = FocusManager::new();
+        let mut elements = ElementTree::new();
+        elements.set_focus(focus.clone());
         Ok(BrowserDriver {
             canvas,
             gl,
             shaders,
+            glyphs,
+            textures,
+            image_requests: ImageRequests::new(),
             app,
+            tree: LayoutTree::new(),
+            pointers: PointerRouter::new(),
+            focus,
+            elements,
         })
     }
 
+    /// The sizes of the images loaded so far, for `Image` widgets to measure
+    /// themselves with. This keeps up to date as more images load.
+    #[must_use]
+    pub fn image_sizes(&self) -> ImageSizes {
+        self.textures.sizes()
+    }
+
+    pub fn try_load_image(&mut self, source: &str, bytes: &[u8]) -> Result<(), Error> {
+        let image = Pixmap::decode_png(bytes)?;
+        self.textures.try_insert(source, &image)
+    }
+
     pub fn clear(&self, color: Color) {
         self.gl.clear(color.r, color.g, color.b, color.a);
     }
@@ -62,51 +196,216 @@ impl BrowserDriver {
         let height = self.canvas.client_height() as f32;
         let viewport = Vector2::new(width, height);
         self.shaders.standard.set_viewport(viewport);
+        self.shaders.shadow.set_viewport(viewport);
+        self.shaders.text.set_viewport(viewport);
+        self.shaders.image.set_viewport(viewport);
+        self.glyphs.begin_frame();
 
         self.paint(time, viewport)?;
         Ok(())
     }
 
     pub fn paint(&mut self, time: f32, viewport: Vector2) -> Result<(), Error> {
-        let mut tree = LayoutTree::new();
-
         let widget_tree = self.app.tick(time);
+        self.elements.set_time(time);
         let constraints = BoxConstraints {
             min: Vector2::zero(),
             max: viewport,
         };
-        let root_sbox = widget_tree.layout(&mut tree, &constraints);
-        let root_lbox = LayoutBox::from_child(root_sbox, (0.0, 0.0));
-        let root_id = tree.insert(root_lbox);
-        tree.set_root(Some(root_id));
+        let tree = LayoutTree::from_root_with_elements(
+            widget_tree.as_ref(),
+            &constraints,
+            &mut self.elements,
+        );
 
         if time % 5000.0 < 50.0 {
             //super::util::log(&format!("{:#?}", tree));
         }
 
-        for (_, child, offset) in tree.iter() {
-            let min = child.bounds.min + offset + child.margin.min();
-            let max = child.bounds.max + offset - child.margin.max();
-            let rect = Rect::new(min, max);
-            self.draw_rect(rect, child.material)?;
+        self.request_images(&tree);
+        let list = DisplayList::from_tree(&tree);
+        self.tree = tree;
+        self.replay(&list, viewport)
+    }
+
+    // Request every image in the tree that hasn't loaded yet. This includes
+    // images that aren't visible, since they are laid out at their size once
+    // it is known.
+    fn request_images(&mut self, tree: &LayoutTree) {
+        for lbox in &tree.boxes {
+            if let Some(image) = &lbox.image {
+                if self.textures.get(&image.source).is_none() {
+                    self.image_requests.request(&image.source);
+                }
+            }
         }
-        Ok(())
     }
 
-    pub fn draw_rect(&mut self, rect: Rect, material: Option<Material>) -> Result<(), Error> {
-        match material {
-            Some(material) => self.shaders.standard.paint_rect(rect, material),
-            None => Ok(()),
+    /// Send a pointer event to the handlers under it in the last painted
+    /// frame, or to the handlers it went down on while it is pressed. `x` and
+    /// `y` are relative to the top-left corner of the canvas. Pressing a
+    /// pointer also focuses the focusable widget under it.
+    pub fn dispatch_pointer(
+        &mut self,
+        kind: PointerEventKind,
+        x: f32,
+        y: f32,
+        pointer_id: i32,
+        time: f32,
+    ) {
+        let event = PointerEvent {
+            time,
+            ..PointerEvent::new(kind, pointer_id, Vector2::new(x, y))
+        };
+        if kind == PointerEventKind::Down {
+            self.focus.focus_at(&self.tree, event.position);
         }
+        let _ = self.pointers.dispatch(&self.tree, &event);
+    }
+
+    /// Send a key event to the focused widget in the last painted frame, and
+    /// move focus when Tab isn't handled. Returns whether it was handled.
+    pub fn dispatch_key(
+        &mut self,
+        kind: KeyEventKind,
+        key: &str,
+        modifiers: Modifiers,
+        repeat: bool,
+    ) -> bool {
+        let event = KeyboardEvent::Key(KeyEvent {
+            repeat,
+            ..KeyEvent::new(kind, key, modifiers)
+        });
+        self.focus.dispatch(&self.tree, &event) == EventStatus::Handled
     }
 
-    pub fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color) -> Result<(), Error> {
-        let vertices = [
-            Vector3::new(start.x, start.y, 0.0),
-            Vector3::new(end.x, end.y, 0.0),
-        ];
-        self.shaders.standard.set_color(color.to_linear());
-        self.shaders.standard.paint_line(&vertices)?;
+    /// Draw every item in the display list, in order.
+    pub fn replay(&mut self, list: &DisplayList, viewport: Vector2) -> Result<(), Error> {
+        // The bounds of each clip, intersected with the clips outside of it,
+        // and its radius when it is rounded
+        let mut clips: Vec<(Rect, Option<(Rect, BorderRadius)>)> = vec![];
+        // How many rounded clips are drawn into the stencil buffer
+        let mut depth = 0;
+        for item in list {
+            // Rects and shadows are drawn by different shaders, so whatever the
+            // other one has queued must be drawn first to keep it underneath.
+            match item {
+                DisplayItem::Shadow { .. } => self.shaders.standard.flush()?,
+                _ => self.shaders.shadow.flush()?,
+            }
+            match item {
+                DisplayItem::Rect { rect, color } => {
+                    self.shaders
+                        .standard
+                        .push_rect(*rect, &Material::filled(*color))?;
+                }
+                DisplayItem::BorderedRect { rect, material } => {
+                    self.shaders.standard.push_rect(*rect, material)?;
+                }
+                DisplayItem::Shadow {
+                    rect,
+                    radius,
+                    blur_radius,
+                    color,
+                } => {
+                    self.shaders
+                        .shadow
+                        .push_shadow(*rect, *radius, *blur_radius, *color);
+                }
+                DisplayItem::Line {
+                    start,
+                    end,
+                    width,
+                    color,
+                } => {
+                    self.shaders
+                        .standard
+                        .push_line(*start, *end, *width, *color)?;
+                }
+                DisplayItem::PushClip { rect, radius } => {
+                    self.shaders.standard.flush()?;
+                    let bounds = match clips.last() {
+                        Some((parent, _)) => parent.intersection(*rect),
+                        None => *rect,
+                    };
+                    self.gl.set_scissor(Some(bounds), viewport);
+                    // The scissor test handles square clips. Rounded ones are
+                    // drawn into the stencil buffer on top of it.
+                    let rounded = (!radius.is_zero()).then_some((*rect, *radius));
+                    if let Some((rect, radius)) = rounded {
+                        self.gl.set_stencil_update(depth, true);
+                        self.shaders.standard.draw_clip_mask(rect, radius)?;
+                        depth += 1;
+                        self.gl.set_stencil_test(depth);
+                    }
+                    clips.push((bounds, rounded));
+                }
+                DisplayItem::PopClip => {
+                    self.shaders.standard.flush()?;
+                    if let Some((_, Some((rect, radius)))) = clips.pop() {
+                        self.gl.set_stencil_update(depth, false);
+                        self.shaders.standard.draw_clip_mask(rect, radius)?;
+                        depth -= 1;
+                        self.gl.set_stencil_test(depth);
+                    }
+                    let bounds = clips.last().map(|(bounds, _)| *bounds);
+                    self.gl.set_scissor(bounds, viewport);
+                }
+                DisplayItem::Text { origin, run } => {
+                    // Text is drawn by another shader, so queued rects must be
+                    // drawn first to keep them underneath.
+                    self.shaders.standard.flush()?;
+                    self.draw_text(run, *origin)?;
+                }
+                DisplayItem::Image { rect, image } => {
+                    self.shaders.standard.flush()?;
+                    self.draw_image(*rect, image)?;
+                }
+            }
+        }
+        self.shaders.standard.flush()?;
+        self.shaders.shadow.flush()?;
+        self.gl.set_scissor(None, viewport);
+        self.gl.set_stencil_test(0);
         Ok(())
     }
+
+    /// Draw an image over `rect`. Images that haven't loaded yet are skipped.
+    pub fn draw_image(&mut self, rect: Rect, image: &ImagePaint) -> Result<(), Error> {
+        let texture = match self.textures.get(&image.source) {
+            Some(texture) => texture,
+            None => return Ok(()),
+        };
+        let mut vertices = vec![];
+        push_image_quad(&mut vertices, rect, image, texture.size());
+        self.shaders.image.paint_image(&texture.texture, &vertices)
+    }
+
+    /// Draw a glyph run. `origin` is the top-left corner of the `LayoutBox`
+    /// that owns the run.
+    pub fn draw_text(&mut self, run: &GlyphRun, origin: Vector2) -> Result<(), Error> {
+        let line_height = run.style.line_height_px();
+        let (atlas_width, atlas_height) =
+            (self.glyphs.atlas().width(), self.glyphs.atlas().height());
+        let mut vertices = vec![];
+        for glyph in &run.glyphs {
+            if glyph.ch.is_whitespace() {
+                continue;
+            }
+            let key = GlyphKey::new(glyph.ch, run.style.font_size);
+            let region = match self.glyphs.try_get(key)? {
+                Some(region) => region,
+                None => continue,
+            };
+            let size = Vector2::new(region.width as f32, region.height as f32);
+            let min = origin + glyph.position + Vector2::new(0.0, (line_height - size.y) / 2.0);
+            let rect = Rect::new(min, min + size);
+            push_glyph_quad(&mut vertices, rect, region.uv(atlas_width, atlas_height));
+        }
+        self.shaders.text.paint_glyphs(
+            self.glyphs.texture(),
+            &vertices,
+            run.style.color.to_linear(),
+        )
+    }
 }
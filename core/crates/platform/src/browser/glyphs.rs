@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Error};
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, WebGlTexture};
+
+use super::WebGl;
+use crate::atlas::{AtlasRect, GlyphAtlas, GlyphKey};
+
+/// The width and height of the glyph atlas texture.
+const ATLAS_SIZE: u32 = 1024;
+
+/// The size of the scratch canvas glyphs are drawn into before being copied
+/// into the atlas. This limits the largest font size that can be rendered.
+const SCRATCH_SIZE: u32 = 256;
+
+/// The font family used to rasterize glyphs. This matches the default
+/// `MonospaceMeasurer` used during layout.
+pub const FONT_FAMILY: &str = "monospace";
+
+/// The height of a rasterized glyph as a multiple of the font size. Glyphs are
+/// drawn vertically centered in this cell, and the cell is vertically centered
+/// in the line box.
+const CELL_HEIGHT: f32 = 1.25;
+
+/// Rasterizes glyphs with a 2D canvas and stores them in a texture so that
+/// each glyph only needs to be drawn once. The placement of glyphs in the
+/// texture is decided by [GlyphAtlas].
+pub struct GlyphCache {
+    gl: Rc<WebGl>,
+    atlas: GlyphAtlas,
+    texture: WebGlTexture,
+    canvas: HtmlCanvasElement,
+    ctx: CanvasRenderingContext2d,
+}
+
+impl GlyphCache {
+    pub fn try_new(gl: &Rc<WebGl>) -> Result<GlyphCache, Error> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| anyhow!("could not get document"))?;
+        let canvas = document
+            .create_element("canvas")
+            .map_err(|_| anyhow!("could not create glyph canvas"))?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| anyhow!("could not create glyph canvas"))?;
+        canvas.set_width(SCRATCH_SIZE);
+        canvas.set_height(SCRATCH_SIZE);
+        let ctx = canvas
+            .get_context("2d")
+            .map_err(|_| anyhow!("could not get 2d context"))?
+            .ok_or_else(|| anyhow!("could not get 2d context"))?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| anyhow!("could not get 2d context"))?;
+        let texture = gl.new_texture(ATLAS_SIZE, ATLAS_SIZE)?;
+        Ok(GlyphCache {
+            gl: Rc::clone(gl),
+            atlas: GlyphAtlas::new(ATLAS_SIZE, ATLAS_SIZE, 1),
+            texture,
+            canvas,
+            ctx,
+        })
+    }
+
+    /// Must be called at the start of every frame so that glyphs in use are
+    /// not evicted.
+    pub fn begin_frame(&mut self) {
+        self.atlas.begin_frame();
+    }
+
+    pub fn texture(&self) -> &WebGlTexture {
+        &self.texture
+    }
+
+    pub fn atlas(&self) -> &GlyphAtlas {
+        &self.atlas
+    }
+
+    /// Get the region of the atlas that holds a glyph, rasterizing it first if
+    /// needed. Returns `None` for glyphs that have nothing to draw or that
+    /// don't fit in the atlas.
+    pub fn try_get(&mut self, key: GlyphKey) -> Result<Option<AtlasRect>, Error> {
+        if let Some(rect) = self.atlas.get(key) {
+            return Ok(Some(rect));
+        }
+
+        self.ctx
+            .set_font(&format!("{}px {}", key.font_size(), FONT_FAMILY));
+        let text = key.ch.to_string();
+        let metrics = self
+            .ctx
+            .measure_text(&text)
+            .map_err(|_| anyhow!("could not measure glyph"))?;
+        let width = (metrics.width().ceil() as u32).min(self.canvas.width());
+        let height = ((key.font_size() * CELL_HEIGHT).ceil() as u32).min(self.canvas.height());
+        if width == 0 || height == 0 {
+            return Ok(None);
+        }
+
+        let rect = match self.atlas.allocate(key, width, height) {
+            Ok(rect) => rect,
+            Err(err) => {
+                log::warn!("could not cache glyph {:?}: {}", key.ch, err);
+                return Ok(None);
+            }
+        };
+
+        let (width, height) = (f64::from(width), f64::from(height));
+        self.ctx.clear_rect(0.0, 0.0, width, height);
+        self.ctx.set_fill_style_str("white");
+        self.ctx.set_text_baseline("middle");
+        self.ctx
+            .fill_text(&text, 0.0, height / 2.0)
+            .map_err(|_| anyhow!("could not rasterize glyph"))?;
+        let pixels = self
+            .ctx
+            .get_image_data(0.0, 0.0, width, height)
+            .map_err(|_| anyhow!("could not read glyph pixels"))?
+            .data();
+        self.gl.upload_texture_region(
+            &self.texture,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            &pixels,
+        )?;
+        Ok(Some(rect))
+    }
+}
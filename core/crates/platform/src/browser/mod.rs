@@ -4,5 +4,11 @@ pub mod util;
 mod driver;
 pub use driver::BrowserDriver;
 
+mod glyphs;
+pub use glyphs::*;
+
+mod textures;
+pub use textures::*;
+
 mod webgl;
-pub use webgl::WebGl;
+pub use webgl::{DataTexture, DynamicBuffer, WebGl};
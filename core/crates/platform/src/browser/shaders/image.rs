@@ -0,0 +1,205 @@
+use anyhow::Error;
+use layout::{ImagePaint, ImagePlacement};
+use math::{Rect, Vector2};
+use web_sys::{WebGlProgram, WebGlTexture};
+
+use super::{DynamicBuffer, WebGl};
+use std::rc::Rc;
+
+const VERTEX_SHADER: &str = r#"
+// Position of the vertex
+attribute vec2 a_position;
+
+// Position relative to where the image is drawn, from (0, 0) at its top-left
+// corner to (1, 1) at its bottom-right corner
+attribute vec2 a_tile;
+
+// The part of the image that is shown in texture coordinates, as
+// (min.x, min.y, max.x, max.y)
+attribute vec4 a_uv_rect;
+
+// Whether the image repeats horizontally and vertically, as 0 or 1
+attribute vec2 a_repeat;
+
+// Pixel dimensions of the canvas
+uniform vec2 u_viewport;
+
+varying vec2 v_tile;
+varying vec4 v_uv_rect;
+varying vec2 v_repeat;
+
+void main() {
+    v_tile = a_tile;
+    v_uv_rect = a_uv_rect;
+    v_repeat = a_repeat;
+    vec2 zero_to_one = a_position / u_viewport;
+    vec2 zero_to_two = zero_to_one * 2.0;
+    vec2 clip_space = zero_to_two - 1.0;
+    vec2 origin_top_left = vec2(1.0, -1.0) * clip_space;
+    gl_Position = vec4(origin_top_left, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+precision mediump float;
+
+uniform sampler2D u_image;
+
+varying vec2 v_tile;
+varying vec4 v_uv_rect;
+varying vec2 v_repeat;
+
+void main() {
+    // Repeating is done here rather than with the texture wrap mode, because
+    // WebGL can only wrap textures with power of two sizes.
+    vec2 tile = v_tile;
+    if (v_repeat.x > 0.5) {
+        tile.x = fract(tile.x);
+    } else if (tile.x < 0.0 || tile.x >= 1.0) {
+        discard;
+    }
+    if (v_repeat.y > 0.5) {
+        tile.y = fract(tile.y);
+    } else if (tile.y < 0.0 || tile.y >= 1.0) {
+        discard;
+    }
+    vec2 uv = mix(v_uv_rect.xy, v_uv_rect.zw, tile);
+    gl_FragColor = texture2D(u_image, uv);
+}
+"#;
+
+/// The vertex attributes of the image shader as `(name, size)` pairs.
+const ATTRIBUTES: [(&str, i32); 4] = [
+    ("a_position", 2),
+    ("a_tile", 2),
+    ("a_uv_rect", 4),
+    ("a_repeat", 2),
+];
+
+/// The number of `f32` values per vertex.
+pub const IMAGE_VERTEX_SIZE: usize = 10;
+
+/// Draws images from their own textures. Each image is a separate draw call,
+/// because it binds a different texture.
+pub struct ImageShader {
+    gl: Rc<WebGl>,
+    program: WebGlProgram,
+    buffer: DynamicBuffer,
+
+    viewport: Vector2,
+}
+
+impl ImageShader {
+    pub fn try_new(gl: &Rc<WebGl>) -> Result<ImageShader, Error> {
+        let program = gl.try_create_shader_program(VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let buffer = gl.new_dynamic_buffer()?;
+        Ok(ImageShader {
+            gl: Rc::clone(gl),
+            program,
+            buffer,
+            viewport: Vector2::zero(),
+        })
+    }
+
+    pub fn set_viewport(&mut self, viewport: Vector2) {
+        self.viewport = viewport;
+    }
+
+    /// Paint an image quad built with [push_image_quad].
+    pub fn paint_image(&mut self, texture: &WebGlTexture, vertices: &[f32]) -> Result<(), Error> {
+        if vertices.is_empty() {
+            return Ok(());
+        }
+        self.gl
+            .set_uniform_vec2(&self.program, "u_viewport", self.viewport)?;
+        self.gl.set_uniform_i32(&self.program, "u_image", 0)?;
+        self.gl.upload_dynamic_buffer(&mut self.buffer, vertices)?;
+        self.gl.bind_texture(texture);
+        let vertex_count = (vertices.len() / IMAGE_VERTEX_SIZE) as i32;
+        self.gl
+            .draw_triangles(&self.program, &self.buffer, &ATTRIBUTES, vertex_count)
+    }
+}
+
+/// Append the two triangles that paint `image` over `rect`, for an image of
+/// `image_size` pixels. Nothing is added when the image doesn't cover any
+/// pixels.
+pub fn push_image_quad(
+    vertices: &mut Vec<f32>,
+    rect: Rect,
+    image: &ImagePaint,
+    image_size: Vector2,
+) {
+    let ImagePlacement {
+        source,
+        destination,
+    } = image.placement(image_size, rect);
+    let size = destination.size();
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return;
+    }
+    let uv_min = source.min / image_size;
+    let uv_max = source.max / image_size;
+    let repeat = [image.repeat.repeats_x(), image.repeat.repeats_y()].map(f32::from);
+    let corners = [
+        rect.min,
+        Vector2::new(rect.min.x, rect.max.y),
+        Vector2::new(rect.max.x, rect.min.y),
+        rect.max,
+    ];
+    for i in [0, 1, 2, 1, 2, 3] {
+        let position = corners[i];
+        let tile = (position - destination.min) / size;
+        vertices.extend_from_slice(&[position.x, position.y, tile.x, tile.y]);
+        vertices.extend_from_slice(&[uv_min.x, uv_min.y, uv_max.x, uv_max.y]);
+        vertices.extend_from_slice(&repeat);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{Alignment, BoxFit, ImageRepeat};
+
+    #[test]
+    fn attributes_match_vertex_size() {
+        let size: i32 = ATTRIBUTES.iter().map(|(_, size)| size).sum();
+        assert_eq!(IMAGE_VERTEX_SIZE as i32, size);
+    }
+
+    #[test]
+    fn image_quad_covers_rect_in_tile_space() {
+        let image = ImagePaint {
+            fit: BoxFit::None,
+            alignment: Alignment::top_left(),
+            repeat: ImageRepeat::RepeatX,
+            ..ImagePaint::default()
+        };
+        let mut vertices = vec![];
+        push_image_quad(
+            &mut vertices,
+            Rect::from_size((40.0, 10.0)),
+            &image,
+            Vector2::new(10.0, 20.0),
+        );
+        assert_eq!(6 * IMAGE_VERTEX_SIZE, vertices.len());
+        // The bottom-right corner is four tiles across, and the box only shows
+        // the top half of the image
+        let last = &vertices[5 * IMAGE_VERTEX_SIZE..];
+        assert_eq!(&[40.0, 10.0, 4.0, 1.0], &last[0..4]);
+        assert_eq!(&[0.0, 0.0, 1.0, 0.5], &last[4..8]);
+        assert_eq!(&[1.0, 0.0], &last[8..10]);
+    }
+
+    #[test]
+    fn empty_image_adds_no_vertices() {
+        let mut vertices = vec![];
+        push_image_quad(
+            &mut vertices,
+            Rect::from_size((40.0, 10.0)),
+            &ImagePaint::default(),
+            Vector2::zero(),
+        );
+        assert!(vertices.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::WebGl;
+use super::{DataTexture, DynamicBuffer, WebGl};
 use anyhow::Error;
 use math::Vector3;
 use std::rc::Rc;
@@ -6,8 +6,20 @@ use std::rc::Rc;
 mod standard;
 pub use standard::*;
 
+mod shadow;
+pub use shadow::*;
+
+mod text;
+pub use text::*;
+
+mod image;
+pub use image::*;
+
 pub struct ShaderLibrary {
     pub standard: StandardShader,
+    pub shadow: ShadowShader,
+    pub text: TextShader,
+    pub image: ImageShader,
 }
 
 impl ShaderLibrary {
@@ -15,6 +27,14 @@ impl ShaderLibrary {
         super::util::log("before standard shader");
         let standard = StandardShader::try_new(gl)?;
         super::util::log("after standard shader");
-        Ok(ShaderLibrary { standard })
+        let shadow = ShadowShader::try_new(gl)?;
+        let text = TextShader::try_new(gl)?;
+        let image = ImageShader::try_new(gl)?;
+        Ok(ShaderLibrary {
+            standard,
+            shadow,
+            text,
+            image,
+        })
     }
 }
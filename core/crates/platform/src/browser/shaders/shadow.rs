@@ -0,0 +1,274 @@
+use anyhow::Error;
+use math::{Rect, Vector2};
+use web_sys::WebGlProgram;
+
+use super::{DynamicBuffer, WebGl};
+use layout::{BorderRadius, Color};
+use std::rc::Rc;
+
+const VERTEX_SHADER: &str = r#"
+// Position of the vertex
+attribute vec2 a_position;
+
+// Color of the shadow
+attribute vec4 a_color;
+
+// Bounds of the shadow before it is blurred as (min.x, min.y, max.x, max.y)
+attribute vec4 a_rect;
+
+// Corner radii as (top left, top right, bottom right, bottom left), already
+// scaled down to fit the rect
+attribute vec4 a_radii;
+
+// Standard deviation of the blur
+attribute float a_sigma;
+
+// Pixel dimensions of the canvas
+uniform vec2 u_viewport;
+
+varying vec2 v_position;
+varying vec4 v_color;
+varying vec4 v_rect;
+varying vec4 v_radii;
+varying float v_sigma;
+
+void main() {
+    v_position = a_position;
+    v_color = a_color;
+    v_rect = a_rect;
+    v_radii = a_radii;
+    v_sigma = a_sigma;
+
+    vec2 zero_to_one = a_position / u_viewport;
+    vec2 zero_to_two = zero_to_one * 2.0;
+    vec2 clip_space = zero_to_two - 1.0;
+    vec2 origin_top_left = vec2(1.0, -1.0) * clip_space;
+    gl_Position = vec4(origin_top_left, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+precision mediump float;
+
+varying vec2 v_position;
+varying vec4 v_color;
+varying vec4 v_rect;
+varying vec4 v_radii;
+varying float v_sigma;
+
+float gaussian(float x, float sigma) {
+    return exp(-(x * x) / (2.0 * sigma * sigma)) / (2.5066283 * sigma);
+}
+
+// An approximation of the error function, with a maximum error of 5e-4
+vec2 erf(vec2 x) {
+    vec2 s = sign(x);
+    vec2 a = abs(x);
+    x = 1.0 + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    x *= x;
+    return s - s / (x * x);
+}
+
+// The horizontal blur at `x` of the row of the rounded rect at `y`, relative
+// to the center of the rect. The ends of the row are pulled in by the corners
+// on that side.
+float row_coverage(float x, float y, vec2 half_size, float sigma) {
+    vec2 radii = y < 0.0 ? vec2(v_radii.x, v_radii.y) : vec2(v_radii.w, v_radii.z);
+    vec2 delta = min(half_size.y - radii - abs(y), 0.0);
+    vec2 extent = half_size.x - radii + sqrt(max(radii * radii - delta * delta, 0.0));
+    vec2 integral = 0.5 + 0.5 * erf((x + vec2(extent.x, -extent.y)) * (0.70710678 / sigma));
+    return integral.x - integral.y;
+}
+
+// The blur is separable for a plain rect. With rounded corners the horizontal
+// blur of each row is still exact, and the vertical blur is integrated
+// numerically with a few samples. See
+// https://madebyevan.com/shaders/fast-rounded-rectangle-shadows/.
+void main() {
+    vec2 center = (v_rect.xy + v_rect.zw) / 2.0;
+    vec2 half_size = max((v_rect.zw - v_rect.xy) / 2.0, 0.0);
+    vec2 p = v_position - center;
+    float sigma = v_sigma;
+
+    // Only the rows within three standard deviations of the point contribute
+    float start = clamp(-3.0 * sigma, p.y - half_size.y, p.y + half_size.y);
+    float end = clamp(3.0 * sigma, p.y - half_size.y, p.y + half_size.y);
+    float step = (end - start) / 4.0;
+    float y = start + step * 0.5;
+    float coverage = 0.0;
+    for (int i = 0; i < 4; i++) {
+        coverage += row_coverage(p.x, p.y - y, half_size, sigma) * gaussian(y, sigma) * step;
+        y += step;
+    }
+    if (coverage <= 0.0) {
+        discard;
+    }
+    gl_FragColor = vec4(v_color.rgb, v_color.a * min(coverage, 1.0));
+}
+"#;
+
+/// The vertex attributes of the shadow shader as `(name, size)` pairs, in the
+/// order they are laid out in each vertex.
+const ATTRIBUTES: [(&str, i32); 5] = [
+    ("a_position", 2),
+    ("a_color", 4),
+    ("a_rect", 4),
+    ("a_radii", 4),
+    ("a_sigma", 1),
+];
+
+/// The number of `f32` values per vertex.
+pub const SHADOW_VERTEX_SIZE: usize = 15;
+
+/// Accumulates blurred shadows into a single interleaved vertex buffer, like
+/// `RectBatch`. Each shadow is drawn as a quad that covers its blur.
+#[derive(Clone, Default, Debug)]
+pub struct ShadowBatch {
+    vertices: Vec<f32>,
+}
+
+impl ShadowBatch {
+    #[must_use]
+    pub fn new() -> ShadowBatch {
+        ShadowBatch::default()
+    }
+
+    /// Add `rect` with rounded corners, blurred with a Gaussian whose standard
+    /// deviation is half of `blur_radius`.
+    pub fn push_shadow(
+        &mut self,
+        rect: Rect,
+        radius: BorderRadius,
+        blur_radius: f32,
+        color: Color,
+    ) {
+        let sigma = blur_radius.max(0.0) / 2.0;
+        let bounds = rect.inflate(3.0 * sigma);
+        let corners = [
+            bounds.min,
+            Vector2::new(bounds.min.x, bounds.max.y),
+            Vector2::new(bounds.max.x, bounds.min.y),
+            bounds.max,
+        ];
+        let color = color.to_linear();
+        let radii = radius.fit(rect.size()).to_array();
+        for i in [0, 1, 2, 1, 2, 3] {
+            let position = corners[i];
+            self.vertices.extend_from_slice(&[position.x, position.y]);
+            self.vertices
+                .extend_from_slice(&[color.x, color.y, color.z, color.w]);
+            self.vertices
+                .extend_from_slice(&[rect.min.x, rect.min.y, rect.max.x, rect.max.y]);
+            self.vertices.extend_from_slice(&radii);
+            self.vertices.push(sigma);
+        }
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    #[must_use]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / SHADOW_VERTEX_SIZE
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+}
+
+/// Draws rounded rects blurred with a Gaussian. Shadows are queued with
+/// `push_shadow` and drawn together when the batch is flushed.
+pub struct ShadowShader {
+    gl: Rc<WebGl>,
+    program: WebGlProgram,
+    buffer: DynamicBuffer,
+    batch: ShadowBatch,
+
+    viewport: Vector2,
+}
+
+impl ShadowShader {
+    pub fn try_new(gl: &Rc<WebGl>) -> Result<ShadowShader, Error> {
+        let program = gl.try_create_shader_program(VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let buffer = gl.new_dynamic_buffer()?;
+        Ok(ShadowShader {
+            gl: Rc::clone(gl),
+            program,
+            buffer,
+            batch: ShadowBatch::new(),
+            viewport: Vector2::zero(),
+        })
+    }
+
+    pub fn set_viewport(&mut self, viewport: Vector2) {
+        self.viewport = viewport;
+    }
+
+    pub fn push_shadow(
+        &mut self,
+        rect: Rect,
+        radius: BorderRadius,
+        blur_radius: f32,
+        color: Color,
+    ) {
+        self.batch.push_shadow(rect, radius, blur_radius, color);
+    }
+
+    /// Draw everything queued since the last flush in a single draw call. Like
+    /// `StandardShader::flush`, this must be called before drawing with another
+    /// shader.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        self.gl
+            .set_uniform_vec2(&self.program, "u_viewport", self.viewport)?;
+        self.gl
+            .upload_dynamic_buffer(&mut self.buffer, self.batch.vertices())?;
+        self.gl.draw_triangles(
+            &self.program,
+            &self.buffer,
+            &ATTRIBUTES,
+            self.batch.vertex_count() as i32,
+        )?;
+        self.batch.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_match_vertex_size() {
+        let size: i32 = ATTRIBUTES.iter().map(|(_, size)| size).sum();
+        assert_eq!(SHADOW_VERTEX_SIZE as i32, size);
+    }
+
+    #[test]
+    fn shadow_quad_covers_blur() {
+        let mut batch = ShadowBatch::new();
+        batch.push_shadow(
+            Rect::from_pos((10.0, 10.0), (20.0, 20.0)),
+            BorderRadius::all(4.0),
+            4.0,
+            Color::black(),
+        );
+        assert_eq!(6, batch.vertex_count());
+        let vertex = &batch.vertices()[..SHADOW_VERTEX_SIZE];
+        // The quad extends three standard deviations past the rect
+        assert_eq!(&[4.0, 4.0], &vertex[0..2]);
+        assert_eq!(&[10.0, 10.0, 30.0, 30.0], &vertex[6..10]);
+        assert_eq!(&[4.0, 4.0, 4.0, 4.0], &vertex[10..14]);
+        assert_eq!(2.0, vertex[14]);
+    }
+}
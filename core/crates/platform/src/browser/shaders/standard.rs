@@ -1,22 +1,29 @@
 use anyhow::Error;
-use math::{Rect, Vector2, Vector3, Vector4};
+use math::{Rect, Vector2};
 use web_sys::WebGlProgram;
 
-use super::WebGl;
-use layout::{BorderSide, Borders, Color, Material};
+use super::{DataTexture, DynamicBuffer, WebGl};
+use layout::{lerp_stops, BorderRadius, Color, ColorStop, Material, Paint};
+use std::borrow::Cow;
 use std::rc::Rc;
 
 const VERTEX_SHADER: &str = r#"
 // Position of the vertex
 attribute vec2 a_position;
 
+// Index of the first texel of the rect's data in `u_rect_data`
+attribute float a_rect;
+
 // Pixel dimensions of the canvas
 uniform vec2 u_viewport;
 
 varying vec2 v_position;
+varying float v_rect;
 
 void main() {
     v_position = a_position;
+    v_rect = a_rect;
+
     vec2 zero_to_one = a_position / u_viewport;
     vec2 zero_to_two = zero_to_one * 2.0;
     vec2 clip_space = zero_to_two - 1.0;
@@ -26,55 +33,486 @@ void main() {
 "#;
 
 const FRAGMENT_SHADER: &str = r#"
+// Texel indices and positions need more precision than mediump guarantees
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
 precision mediump float;
+#endif
 
-// Color of the rect
-uniform vec4 u_color;
-
-struct border_side {
-    bool enabled;
-    float width;
-    vec4 color;
-};
+// The fill, bounds, borders and radii of every rect in the batch, laid out as
+// described by `RectBatch`
+uniform sampler2D u_rect_data;
 
-uniform border_side u_borders[4];
+// Size of `u_rect_data` in texels
+uniform vec2 u_rect_data_size;
 
-uniform vec2 u_rect_min;
-uniform vec2 u_rect_max;
+// Set while drawing rounded clips into the stencil buffer, which only keeps
+// the pixels whose centers are inside the rect
+uniform bool u_clip_mask;
 
-// Current position
 varying vec2 v_position;
+varying float v_rect;
+
+// The texel at `offset` in the data of this rect
+vec4 rect_data(float offset) {
+    float index = floor(v_rect + 0.5) + offset;
+    float row = floor(index / u_rect_data_size.x);
+    vec2 texel = vec2(index - row * u_rect_data_size.x, row) + 0.5;
+    return texture2D(u_rect_data, texel / u_rect_data_size);
+}
+
+// An approximation of the signed distance from `delta` to an ellipse centered
+// on the origin, which is exact for circles. Negative inside the ellipse.
+float ellipse_distance(vec2 delta, vec2 radii) {
+    vec2 scaled = delta / radii;
+    float gradient = length(scaled / radii);
+    if (gradient <= 0.0) {
+        return -min(radii.x, radii.y);
+    }
+    float len = length(scaled);
+    return (len - 1.0) * len / gradient;
+}
+
+// How much of the pixel is covered by the rect with rounded corners, from 0 to
+// 1. The straight edges cover the whole pixel when its center is inside them,
+// and only the curves are antialiased. `radii_x` and `radii_y` are ordered
+// like the corner radii of the rect.
+float rounded_rect_coverage(vec2 p, vec2 rect_min, vec2 rect_max, vec4 radii_x, vec4 radii_y) {
+    if (p.x < rect_min.x || p.y < rect_min.y || p.x > rect_max.x || p.y > rect_max.y) {
+        return 0.0;
+    }
+    vec2 middle = (rect_min + rect_max) / 2.0;
+    vec2 corner;
+    vec2 direction;
+    vec2 radii;
+    if (p.x < middle.x && p.y < middle.y) {
+        corner = rect_min;
+        direction = vec2(1.0, 1.0);
+        radii = vec2(radii_x.x, radii_y.x);
+    } else if (p.y < middle.y) {
+        corner = vec2(rect_max.x, rect_min.y);
+        direction = vec2(-1.0, 1.0);
+        radii = vec2(radii_x.y, radii_y.y);
+    } else if (p.x >= middle.x) {
+        corner = rect_max;
+        direction = vec2(-1.0, -1.0);
+        radii = vec2(radii_x.z, radii_y.z);
+    } else {
+        corner = vec2(rect_min.x, rect_max.y);
+        direction = vec2(1.0, -1.0);
+        radii = vec2(radii_x.w, radii_y.w);
+    }
+    if (radii.x <= 0.0 || radii.y <= 0.0) {
+        return 1.0;
+    }
+    vec2 delta = p - (corner + direction * radii);
+    if (delta.x * direction.x > 0.0 || delta.y * direction.y > 0.0) {
+        return 1.0;
+    }
+    return clamp(0.5 - ellipse_distance(delta, radii), 0.0, 1.0);
+}
+
+vec4 premultiply(vec4 color) {
+    return vec4(color.rgb * color.a, color.a);
+}
+
+// How far `t` is from the stop at `from` to the one at `to`, from 0 to 1
+float stop_mix(float t, float from, float to) {
+    return to > from ? clamp((t - from) / (to - from), 0.0, 1.0) : step(to, t);
+}
+
+// How far `p` is along the gradient. Linear gradients are stored as
+// (1, axis.x, axis.y, offset), where the offset is the dot product with the
+// axis plus the offset. Radial gradients are stored as (2, center, radius).
+// Solid fills are all zero, so they are always at the start.
+float gradient_offset(vec2 p) {
+    vec4 gradient = rect_data(7.0);
+    if (gradient.x > 1.5) {
+        return gradient.w > 0.0 ? length(p - gradient.yz) / gradient.w : 1.0;
+    }
+    return dot(p, gradient.yz) + gradient.w;
+}
+
+// Each color stop is stored as its offset followed by its color
+float stop_offset(float index) {
+    return rect_data(9.0 + index * 2.0).x;
+}
+
+vec4 stop_color(float index) {
+    return premultiply(rect_data(10.0 + index * 2.0));
+}
+
+// The fill color at this pixel. The stops on either side of the pixel are
+// found with a binary search, which takes 16 steps for `MAX_COLOR_STOPS`.
+// Colors are blended with premultiplied alpha.
+vec4 fill_color(vec2 p) {
+    float t = gradient_offset(p);
+    float last = rect_data(8.0).x - 1.0;
+    // The last stop at or before `t`, or the first stop when there isn't one
+    float low = 0.0;
+    float high = last;
+    for (int i = 0; i < 16; i++) {
+        if (low >= high) {
+            break;
+        }
+        float middle = ceil((low + high) / 2.0);
+        if (stop_offset(middle) <= t) {
+            low = middle;
+        } else {
+            high = middle - 1.0;
+        }
+    }
+    vec4 color = stop_color(low);
+    if (low < last) {
+        float from = stop_offset(low);
+        float to = stop_offset(low + 1.0);
+        color = mix(color, stop_color(low + 1.0), stop_mix(t, from, to));
+    }
+    if (color.a <= 0.0) {
+        return vec4(0.0);
+    }
+    return vec4(color.rgb / color.a, color.a);
+}
+
+// How far a point is into a border, relative to the width of the border
+float depth(float distance, float width) {
+    return width > 0.0 ? distance / width : 1.0e20;
+}
 
 void main() {
-    border_side top_border = u_borders[0];
-    border_side bottom_border = u_borders[1];
-    border_side left_border = u_borders[2];
-    border_side right_border = u_borders[3];
-
-    // Probably need to do something here during resizing on fractional values
-    if (top_border.enabled && v_position.y < u_rect_min.y + top_border.width) {
-        gl_FragColor = top_border.color;
-        return;
-    } else if (bottom_border.enabled && v_position.y > u_rect_max.y - bottom_border.width) {
-        gl_FragColor = bottom_border.color;
-        return;
-    } else if (left_border.enabled && v_position.x < u_rect_min.x + left_border.width) {
-        gl_FragColor = left_border.color;
-        return;
-    } else if (right_border.enabled && v_position.x > u_rect_max.x - right_border.width) {
-        gl_FragColor = right_border.color;
+    vec2 p = v_position;
+    vec4 bounds = rect_data(0.0);
+    vec4 border_widths = rect_data(1.0);
+    vec4 radii = rect_data(6.0);
+    vec2 rect_min = bounds.xy;
+    vec2 rect_max = bounds.zw;
+    float top = border_widths.x;
+    float bottom = border_widths.y;
+    float left = border_widths.z;
+    float right = border_widths.w;
+
+    // The inside edge of the borders is rounded by each corner radius minus
+    // the widths of the borders next to it, so it is elliptical where they
+    // differ.
+    vec2 inner_min = rect_min + vec2(left, top);
+    vec2 inner_max = rect_max - vec2(right, bottom);
+    vec4 inner_x = max(radii - vec4(left, right, right, left), 0.0);
+    vec4 inner_y = max(radii - vec4(top, top, bottom, bottom), 0.0);
+
+    float outer_coverage = rounded_rect_coverage(p, rect_min, rect_max, radii, radii);
+    if (u_clip_mask) {
+        if (outer_coverage < 0.5) {
+            discard;
+        }
+        gl_FragColor = vec4(1.0);
         return;
     }
-    gl_FragColor = u_color;
+    float fill_coverage = min(
+        rounded_rect_coverage(p, inner_min, inner_max, inner_x, inner_y),
+        outer_coverage
+    );
+
+    // Top and bottom borders win in square corners. In rounded corners the
+    // pixel belongs to whichever border it is further into, relative to their
+    // widths, so the colors meet along the corner.
+    vec2 middle = (rect_min + rect_max) / 2.0;
+    bool in_top = p.y < inner_min.y;
+    bool in_bottom = !in_top && p.y > inner_max.y;
+    bool in_left = p.x < inner_min.x;
+    bool in_right = !in_left && p.x > inner_max.x;
+    bool is_top = in_top || (!in_bottom && p.y < middle.y);
+    bool is_left = in_left || (!in_right && p.x < middle.x);
+    float radius = is_top
+        ? (is_left ? radii.x : radii.y)
+        : (is_left ? radii.w : radii.z);
+    float vertical_depth = is_top
+        ? depth(p.y - rect_min.y, top)
+        : depth(rect_max.y - p.y, bottom);
+    float horizontal_depth = is_left
+        ? depth(p.x - rect_min.x, left)
+        : depth(rect_max.x - p.x, right);
+    bool in_vertical = in_top || in_bottom;
+    bool in_horizontal = in_left || in_right;
+    bool vertical;
+    if (in_vertical && !in_horizontal) {
+        vertical = true;
+    } else if (in_horizontal && !in_vertical) {
+        vertical = false;
+    } else if (in_vertical && radius <= 0.0) {
+        vertical = true;
+    } else {
+        vertical = vertical_depth <= horizontal_depth;
+    }
+    // The border colors are stored top, bottom, left, right
+    vec4 border_color = vertical
+        ? rect_data(is_top ? 2.0 : 3.0)
+        : rect_data(is_left ? 4.0 : 5.0);
+
+    // Weight the fill and border by how much of the pixel each covers
+    vec4 fill = fill_color(p);
+    float fill_alpha = fill.a * fill_coverage;
+    float border_alpha = border_color.a * (outer_coverage - fill_coverage);
+    float alpha = fill_alpha + border_alpha;
+    if (alpha <= 0.0) {
+        discard;
+    }
+    vec3 color = (fill.rgb * fill_alpha + border_color.rgb * border_alpha) / alpha;
+    gl_FragColor = vec4(color, alpha);
 }
 "#;
 
+/// The vertex attributes of the standard shader as `(name, size)` pairs, in the
+/// order they are laid out in each vertex.
+const ATTRIBUTES: [(&str, i32); 2] = [("a_position", 2), ("a_rect", 1)];
+
+/// The number of `f32` values per vertex.
+pub const RECT_VERTEX_SIZE: usize = 3;
+
+/// The number of RGBA texels of data stored for each rect before its color
+/// stops.
+pub const RECT_HEADER_TEXELS: usize = 9;
+
+/// The number of RGBA texels of data stored for each color stop.
+pub const STOP_TEXELS: usize = 2;
+
+/// The number of texels in each row of the data texture. This is a power of
+/// two, so the shader can find the row of a texel exactly.
+pub const RECT_DATA_WIDTH: usize = 1024;
+
+/// The most color stops the shader can search through. Gradients with more
+/// stops are resampled to this many evenly spaced stops.
+pub const MAX_COLOR_STOPS: usize = 1 << 16;
+
+/// Each rect is drawn as two triangles.
+const VERTICES_PER_RECT: usize = 6;
+
+/// Accumulates rects into a single vertex buffer, so that a whole frame of
+/// boxes can be drawn with one `draw_arrays` call.
+///
+/// Each vertex only holds its position and the index of its rect, which keeps
+/// the shader within the eight vertex attributes and varyings that WebGL
+/// guarantees. Everything the fragment shader needs to know about the rect is
+/// stored once, as RGBA texels of a data texture:
+///
+/// 0. The bounds as (min.x, min.y, max.x, max.y)
+/// 1. The border widths as (top, bottom, left, right), which are zero for
+///    missing borders
+/// 2. to 5. The border colors, in the same order
+/// 6. The corner radii clockwise from the top-left, scaled down to fit
+/// 7. The gradient, as described by `gradient_data`
+/// 8. The number of color stops, as (count, 0, 0, 0)
+/// 9. onwards. Each color stop, as its offset along the gradient in
+///    (offset, 0, 0, 0) followed by its color. Solid fills have one stop
+///
+/// The underlying `Vec`s keep their capacity when cleared, so building the
+/// batch doesn't allocate once it has warmed up.
+#[derive(Clone, Default, Debug)]
+pub struct RectBatch {
+    vertices: Vec<f32>,
+    data: Vec<f32>,
+}
+
+impl RectBatch {
+    #[must_use]
+    pub fn new() -> RectBatch {
+        RectBatch::default()
+    }
+
+    /// Add a rect filled and bordered with `material`.
+    pub fn push_rect(&mut self, rect: Rect, material: &Material) {
+        let corners = [
+            rect.min,
+            Vector2::new(rect.min.x, rect.max.y),
+            Vector2::new(rect.max.x, rect.min.y),
+            rect.max,
+        ];
+        self.push_quad(corners, rect, material);
+    }
+
+    /// Add a line as a quad of the given width.
+    pub fn push_line(&mut self, start: Vector2, end: Vector2, width: f32, color: Color) {
+        let delta = end - start;
+        if delta.is_zero() {
+            return;
+        }
+        let direction = delta.normalized();
+        let normal = Vector2::new(-direction.y, direction.x) * (width / 2.0);
+        let corners = [start + normal, start - normal, end + normal, end - normal];
+        let min = Vector2::new(
+            corners.iter().map(|c| c.x).fold(f32::INFINITY, f32::min),
+            corners.iter().map(|c| c.y).fold(f32::INFINITY, f32::min),
+        );
+        let max = Vector2::new(
+            corners
+                .iter()
+                .map(|c| c.x)
+                .fold(f32::NEG_INFINITY, f32::max),
+            corners
+                .iter()
+                .map(|c| c.y)
+                .fold(f32::NEG_INFINITY, f32::max),
+        );
+        self.push_quad(corners, Rect::new(min, max), &Material::filled(color));
+    }
+
+    /// The corners are ordered so that `[0, 1, 2]` and `[1, 2, 3]` form the two
+    /// triangles of the quad.
+    fn push_quad(&mut self, corners: [Vector2; 4], bounds: Rect, material: &Material) {
+        let index = self.texel_count() as f32;
+        for i in [0, 1, 2, 1, 2, 3] {
+            let position = corners[i];
+            self.vertices
+                .extend_from_slice(&[position.x, position.y, index]);
+        }
+
+        let sides = material.borders.to_array();
+        self.push_texel([bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y]);
+        self.push_texel(sides.map(|side| side.map_or(0.0, |side| side.width)));
+        for side in sides {
+            let color = side.map_or(Color::transparent(), |side| side.color);
+            self.push_texel(color.to_linear().into());
+        }
+        self.push_texel(material.radius.fit(bounds.size()).to_array());
+        self.push_texel(gradient_data(&material.fill, bounds));
+        match &material.fill {
+            Paint::Solid(color) => self.push_stops(&[ColorStop::new(0.0, *color)]),
+            Paint::LinearGradient(gradient) => self.push_stops(&fit_stops(&gradient.stops)),
+            Paint::RadialGradient(gradient) => self.push_stops(&fit_stops(&gradient.stops)),
+        }
+    }
+
+    fn push_stops(&mut self, stops: &[ColorStop]) {
+        self.push_texel([stops.len() as f32, 0.0, 0.0, 0.0]);
+        for stop in stops {
+            self.push_texel([stop.offset, 0.0, 0.0, 0.0]);
+            self.push_texel(stop.color.to_linear().into());
+        }
+    }
+
+    fn push_texel(&mut self, texel: [f32; 4]) {
+        self.data.extend_from_slice(&texel);
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    /// The data of every rect, as RGBA texels.
+    #[must_use]
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    #[must_use]
+    pub fn texel_count(&self) -> usize {
+        self.data.len() / 4
+    }
+
+    /// Add empty texels until the data fills whole rows of a texture that is
+    /// `width` texels wide.
+    pub fn pad_data(&mut self, width: usize) {
+        let texels = self.texel_count().div_ceil(width) * width;
+        self.data.resize(texels * 4, 0.0);
+    }
+
+    #[must_use]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / RECT_VERTEX_SIZE
+    }
+
+    /// The number of rects (or lines) in the batch.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.vertex_count() / VERTICES_PER_RECT
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.data.clear();
+    }
+}
+
+/// The number of texels of data `RectBatch::push_rect` stores for a rect
+/// filled with `paint`.
+#[must_use]
+pub fn rect_data_texels(paint: &Paint) -> usize {
+    let stops = match paint {
+        Paint::Solid(_) => 1,
+        Paint::LinearGradient(gradient) => gradient.stops.len().clamp(1, MAX_COLOR_STOPS),
+        Paint::RadialGradient(gradient) => gradient.stops.len().clamp(1, MAX_COLOR_STOPS),
+    };
+    RECT_HEADER_TEXELS + stops * STOP_TEXELS
+}
+
+// The color stops of a gradient as the shader searches them. Gradients without
+// stops are transparent, and gradients with more than `MAX_COLOR_STOPS` are
+// resampled to that many evenly spaced stops.
+fn fit_stops(stops: &[ColorStop]) -> Cow<'_, [ColorStop]> {
+    match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) if stops.len() > MAX_COLOR_STOPS => {
+            let step = (last.offset - first.offset) / (MAX_COLOR_STOPS - 1) as f32;
+            // The offsets only increase, so the stop before each one is found
+            // by walking forwards from the stop before the previous one
+            let mut before = 0;
+            let resampled = (0..MAX_COLOR_STOPS)
+                .map(|i| {
+                    let offset = first.offset + step * i as f32;
+                    while before + 1 < stops.len() && stops[before + 1].offset <= offset {
+                        before += 1;
+                    }
+                    ColorStop::new(offset, lerp_stops(&stops[before..], offset))
+                })
+                .collect();
+            Cow::Owned(resampled)
+        }
+        (Some(_), Some(_)) => Cow::Borrowed(stops),
+        _ => Cow::Owned(vec![ColorStop::default()]),
+    }
+}
+
+// The texel that describes how far each point is along the gradient of the
+// paint when it fills `bounds`. Linear gradients are stored as an axis and an
+// offset, so the distance is `dot(point, axis) + offset`, and radial gradients
+// are stored as their circle. Solid fills are always at the start.
+fn gradient_data(paint: &Paint, bounds: Rect) -> [f32; 4] {
+    match paint {
+        Paint::Solid(_) => [0.0; 4],
+        Paint::LinearGradient(gradient) => {
+            let (start, end) = gradient.line(bounds);
+            let delta = end - start;
+            let length = Vector2::dot(delta, delta);
+            if length <= 0.0 {
+                return [1.0, 0.0, 0.0, 0.0];
+            }
+            let axis = delta / length;
+            [1.0, axis.x, axis.y, -Vector2::dot(start, axis)]
+        }
+        Paint::RadialGradient(gradient) => {
+            let (center, radius) = gradient.circle(bounds);
+            [2.0, center.x, center.y, radius]
+        }
+    }
+}
+
+/// Draws filled and bordered rects. Rects are queued with `push_rect` and
+/// drawn together when the batch is flushed.
 pub struct StandardShader {
     gl: Rc<WebGl>,
     program: WebGlProgram,
+    buffer: DynamicBuffer,
+    data: DataTexture,
+    // The most texels of rect data that fit in the data texture
+    max_texels: usize,
+    batch: RectBatch,
 
     viewport: Vector2,
-    color: Vector4,
 }
 
 impl StandardShader {
@@ -82,11 +520,17 @@ impl StandardShader {
         crate::browser::util::log("before create shader program");
         let program = gl.try_create_shader_program(VERTEX_SHADER, FRAGMENT_SHADER)?;
         crate::browser::util::log("after create shader program");
+        let buffer = gl.new_dynamic_buffer()?;
+        gl.try_enable_float_textures()?;
+        let data = gl.new_data_texture(RECT_DATA_WIDTH as u32)?;
         Ok(StandardShader {
             gl: Rc::clone(gl),
             program,
+            buffer,
+            data,
+            max_texels: RECT_DATA_WIDTH * gl.max_texture_size() as usize,
+            batch: RectBatch::new(),
             viewport: Vector2::zero(),
-            color: Vector4::new(1.0, 1.0, 1.0, 1.0),
         })
     }
 
@@ -94,82 +538,315 @@ impl StandardShader {
         self.viewport = viewport;
     }
 
-    pub fn set_color(&mut self, color: Vector4) {
-        self.color = color;
+    pub fn push_rect(&mut self, rect: Rect, material: &Material) -> Result<(), Error> {
+        self.make_room(rect_data_texels(&material.fill))?;
+        self.batch.push_rect(rect, material);
+        Ok(())
     }
 
-    fn set_uniforms(&self) -> Result<(), Error> {
-        self.gl
-            .set_uniform_vec2(&self.program, "u_viewport", self.viewport)?;
+    pub fn push_line(
+        &mut self,
+        start: Vector2,
+        end: Vector2,
+        width: f32,
+        color: Color,
+    ) -> Result<(), Error> {
+        self.make_room(rect_data_texels(&Paint::Solid(color)))?;
+        self.batch.push_line(start, end, width, color);
         Ok(())
     }
 
-    pub fn paint_mesh(&self, vertices: &[Vector3]) -> Result<(), Error> {
-        self.set_uniforms()?;
-        let buffer = self.gl.new_array_buffer(vertices)?;
-        self.gl.draw_mesh(&self.program, "a_position", &buffer)?;
+    // Flush the batch if `texels` more texels of rect data wouldn't fit in the
+    // data texture.
+    fn make_room(&mut self, texels: usize) -> Result<(), Error> {
+        if self.batch.texel_count() + texels > self.max_texels {
+            self.flush()?;
+        }
         Ok(())
     }
 
-    pub fn paint_line(&self, vertices: &[Vector3]) -> Result<(), Error> {
-        self.set_uniforms()?;
-        let buffer = self.gl.new_array_buffer(vertices)?;
-        self.gl.draw_line(&self.program, "a_position", &buffer)?;
-        Ok(())
+    /// Draw `rect` with rounded corners into the stencil buffer, after
+    /// drawing everything that is queued. Pixels are covered when their center
+    /// is inside the rect. `WebGl::set_stencil_update` decides what happens to
+    /// the stencil.
+    pub fn draw_clip_mask(&mut self, rect: Rect, radius: BorderRadius) -> Result<(), Error> {
+        self.flush()?;
+        let material = Material {
+            radius,
+            ..Material::filled(Color::white())
+        };
+        self.batch.push_rect(rect, &material);
+        self.draw(true)
     }
 
-    pub fn paint_rect(&mut self, rect: Rect, material: Material) -> Result<(), Error> {
-        self.set_color(material.fill.to_linear());
-        let (min_x, min_y) = rect.min.into();
-        let (max_x, max_y) = rect.max.into();
+    /// Draw everything queued since the last flush in a single draw call. This
+    /// must be called before drawing with another shader, so that painting
+    /// order is preserved, and at the end of every frame.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.draw(false)
+    }
+
+    fn draw(&mut self, clip_mask: bool) -> Result<(), Error> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        self.batch.pad_data(RECT_DATA_WIDTH);
+        self.gl
+            .upload_data_texture(&mut self.data, self.batch.data())?;
         self.gl
-            .set_uniform_vec4(&self.program, "u_color", self.color)?;
-        self.set_borders(material.borders)?;
+            .set_uniform_vec2(&self.program, "u_viewport", self.viewport)?;
+        self.gl.set_uniform_i32(&self.program, "u_rect_data", 0)?;
         self.gl
-            .set_uniform_vec2(&self.program, "u_rect_max", rect.max)?;
+            .set_uniform_vec2(&self.program, "u_rect_data_size", self.data.size())?;
         self.gl
-            .set_uniform_vec2(&self.program, "u_rect_min", rect.min)?;
-        let vertices: [Vector3; 6] = [
-            Vector3::new(min_x, min_y, 0.0),
-            Vector3::new(min_x, max_y, 0.0),
-            Vector3::new(max_x, min_y, 0.0),
-            Vector3::new(min_x, max_y, 0.0),
-            Vector3::new(max_x, min_y, 0.0),
-            Vector3::new(max_x, max_y, 0.0),
-        ];
-        self.paint_mesh(&vertices)?;
+            .set_uniform_i32(&self.program, "u_clip_mask", i32::from(clip_mask))?;
+        self.gl
+            .upload_dynamic_buffer(&mut self.buffer, self.batch.vertices())?;
+        self.gl.bind_texture(&self.data.texture);
+        self.gl.draw_triangles(
+            &self.program,
+            &self.buffer,
+            &ATTRIBUTES,
+            self.batch.vertex_count() as i32,
+        )?;
+        self.batch.clear();
         Ok(())
     }
+}
 
-    fn set_borders(&mut self, borders: Borders) -> Result<(), Error> {
-        self.set_border(0, borders.top)?;
-        self.set_border(1, borders.bottom)?;
-        self.set_border(2, borders.left)?;
-        self.set_border(3, borders.right)?;
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{Alignment, BorderRadius, Borders, LinearGradient, RadialGradient};
+
+    #[test]
+    fn attributes_match_vertex_size() {
+        let size: i32 = ATTRIBUTES.iter().map(|(_, size)| size).sum();
+        assert_eq!(RECT_VERTEX_SIZE as i32, size);
     }
 
-    fn set_border(&mut self, i: usize, maybe_border: Option<BorderSide>) -> Result<(), Error> {
-        let BorderSide { color, width } = match maybe_border {
-            Some(border) => border,
-            None => {
-                self.gl.set_uniform_i32(
-                    &self.program,
-                    &format!("u_borders[{:?}].enabled", i),
-                    0,
-                )?;
-                return Ok(());
-            }
+    #[test]
+    fn rect_batch_adds_six_vertices_per_rect() {
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        assert_eq!(2, batch.len());
+        assert_eq!(12, batch.vertex_count());
+        assert_eq!(12 * RECT_VERTEX_SIZE, batch.vertices().len());
+        let texels = rect_data_texels(&Material::default().fill);
+        assert_eq!(RECT_HEADER_TEXELS + STOP_TEXELS, texels);
+        assert_eq!(2 * texels, batch.texel_count());
+    }
+
+    #[test]
+    fn rect_batch_vertices_cover_rect() {
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_pos((1.0, 2.0), (3.0, 4.0)), &Material::default());
+        let positions: Vec<(f32, f32)> = batch
+            .vertices()
+            .chunks(RECT_VERTEX_SIZE)
+            .map(|vertex| (vertex[0], vertex[1]))
+            .collect();
+        let expected = vec![
+            (1.0, 2.0),
+            (1.0, 6.0),
+            (4.0, 2.0),
+            (1.0, 6.0),
+            (4.0, 2.0),
+            (4.0, 6.0),
+        ];
+        assert_eq!(expected, positions);
+    }
+
+    #[test]
+    fn rect_batch_vertices_point_at_their_rect_data() {
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        let indices: Vec<f32> = batch
+            .vertices()
+            .chunks(RECT_VERTEX_SIZE)
+            .map(|vertex| vertex[2])
+            .collect();
+        let mut expected = vec![0.0; 6];
+        expected.extend([(RECT_HEADER_TEXELS + STOP_TEXELS) as f32; 6]);
+        assert_eq!(expected, indices);
+    }
+
+    #[test]
+    fn rect_batch_encodes_material_once_per_rect() {
+        let mut batch = RectBatch::new();
+        let material = Material {
+            fill: Color::red().into(),
+            borders: Borders::left(Color::blue(), 2.0),
+            ..Material::default()
         };
-        self.gl
-            .set_uniform_i32(&self.program, &format!("u_borders[{:?}].enabled", i), 1)?;
-        self.gl
-            .set_uniform_f32(&self.program, &format!("u_borders[{:?}].width", i), width)?;
-        self.gl.set_uniform_vec4(
-            &self.program,
-            &format!("u_borders[{:?}].color", i),
-            color.to_linear(),
-        )?;
-        Ok(())
+        batch.push_rect(Rect::from_pos((1.0, 2.0), (3.0, 4.0)), &material);
+
+        let mut expected = vec![];
+        expected.extend_from_slice(&[1.0, 2.0, 4.0, 6.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 2.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 1.0, 1.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[1.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]);
+        expected.extend_from_slice(&[1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(expected.as_slice(), batch.data());
+    }
+
+    #[test]
+    fn rect_batch_encodes_radii_fitted_to_rect() {
+        let mut batch = RectBatch::new();
+        let material = Material {
+            radius: BorderRadius {
+                top_left: 4.0,
+                bottom_right: 20.0,
+                ..BorderRadius::default()
+            },
+            ..Material::default()
+        };
+        batch.push_rect(Rect::from_size((10.0, 40.0)), &material);
+        // The bottom corners are twice as wide as the rect, so every corner halves
+        assert_eq!(&[2.0, 0.0, 10.0, 0.0], texel(&batch, 6));
+    }
+
+    #[test]
+    fn rect_batch_encodes_linear_gradient_axis() {
+        let mut batch = RectBatch::new();
+        let gradient = LinearGradient::new(
+            90.0,
+            vec![
+                ColorStop::new(0.0, Color::red()),
+                ColorStop::new(1.0, Color::blue()),
+            ],
+        );
+        let material = Material::filled(Paint::LinearGradient(gradient));
+        batch.push_rect(Rect::from_pos((10.0, 0.0), (10.0, 10.0)), &material);
+        let [kind, x, y, offset] = *texel(&batch, 7) else {
+            unreachable!()
+        };
+        assert_eq!(1.0, kind);
+        let offset_at = |point: Vector2| (x * point.x + y * point.y + offset).round();
+        assert_eq!(0.0, offset_at(Vector2::new(10.0, 10.0)));
+        assert_eq!(1.0, offset_at(Vector2::new(20.0, 0.0)));
+        assert_eq!(&[2.0, 0.0, 0.0, 0.0], texel(&batch, 8));
+        assert_eq!(&[1.0, 0.0, 0.0, 0.0], texel(&batch, 11));
+        assert_eq!(&[0.0, 0.0, 1.0, 1.0], texel(&batch, 12));
+        assert_eq!(13, batch.texel_count());
+    }
+
+    #[test]
+    fn rect_batch_encodes_radial_gradient_circle() {
+        let mut batch = RectBatch::new();
+        let gradient = RadialGradient::new(
+            Alignment::new(0.5, 0.5),
+            0.5,
+            vec![ColorStop::new(0.0, Color::red())],
+        );
+        let material = Material::filled(Paint::RadialGradient(gradient));
+        batch.push_rect(Rect::from_pos((10.0, 0.0), (20.0, 10.0)), &material);
+        assert_eq!(&[2.0, 20.0, 5.0, 5.0], texel(&batch, 7));
+    }
+
+    #[test]
+    fn rect_batch_encodes_every_color_stop() {
+        let colors = [Color::red(), Color::green(), Color::blue(), Color::white()];
+        let stops: Vec<ColorStop> = (0..7)
+            .map(|i| ColorStop::new(i as f32 / 6.0, colors[i % 4]))
+            .collect();
+        let paint = Paint::LinearGradient(LinearGradient::new(0.0, stops.clone()));
+        let mut batch = RectBatch::new();
+        batch.push_rect(
+            Rect::from_size((10.0, 10.0)),
+            &Material::filled(paint.clone()),
+        );
+        assert_eq!(rect_data_texels(&paint), batch.texel_count());
+        assert_eq!(&[7.0, 0.0, 0.0, 0.0], texel(&batch, 8));
+        for (i, stop) in stops.iter().enumerate() {
+            let index = RECT_HEADER_TEXELS + i * STOP_TEXELS;
+            assert_eq!(&[stop.offset, 0.0, 0.0, 0.0], texel(&batch, index));
+            let color: [f32; 4] = stop.color.to_linear().into();
+            assert_eq!(&color, texel(&batch, index + 1));
+        }
+    }
+
+    #[test]
+    fn rect_batch_resamples_gradients_past_max_color_stops() {
+        let stops = (0..=MAX_COLOR_STOPS)
+            .map(|i| ColorStop::new(i as f32, Color::red()))
+            .collect();
+        let paint = Paint::RadialGradient(RadialGradient::new(Alignment::center(), 1.0, stops));
+        let mut batch = RectBatch::new();
+        batch.push_rect(
+            Rect::from_size((10.0, 10.0)),
+            &Material::filled(paint.clone()),
+        );
+        assert_eq!(rect_data_texels(&paint), batch.texel_count());
+        assert_eq!(&[MAX_COLOR_STOPS as f32, 0.0, 0.0, 0.0], texel(&batch, 8));
+        // The first and last stops are kept, and the rest are evenly spaced
+        let last = RECT_HEADER_TEXELS + (MAX_COLOR_STOPS - 1) * STOP_TEXELS;
+        assert_eq!(
+            &[MAX_COLOR_STOPS as f32, 0.0, 0.0, 0.0],
+            texel(&batch, last)
+        );
+    }
+
+    #[test]
+    fn rect_batch_fills_gradient_without_stops_with_transparent() {
+        let paint = Paint::LinearGradient(LinearGradient::new(0.0, vec![]));
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::filled(paint));
+        assert_eq!(&[1.0, 0.0, 0.0, 0.0], texel(&batch, 8));
+        assert_eq!(&[0.0; 4], texel(&batch, 10));
+    }
+
+    #[test]
+    fn rect_batch_line_is_quad_around_segment() {
+        let mut batch = RectBatch::new();
+        batch.push_line((0.0, 5.0).into(), (10.0, 5.0).into(), 2.0, Color::red());
+        assert_eq!(1, batch.len());
+        assert_eq!(&[0.0, 4.0, 10.0, 6.0], texel(&batch, 0));
+    }
+
+    #[test]
+    fn rect_batch_skips_zero_length_line() {
+        let mut batch = RectBatch::new();
+        batch.push_line((5.0, 5.0).into(), (5.0, 5.0).into(), 2.0, Color::red());
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn rect_batch_pads_data_to_whole_rows() {
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        batch.pad_data(8);
+        assert_eq!(16, batch.texel_count());
+        assert_eq!(&[0.0; 4], texel(&batch, 15));
+        batch.pad_data(8);
+        assert_eq!(16, batch.texel_count());
+    }
+
+    #[test]
+    fn rect_batch_keeps_capacity_when_cleared() {
+        let mut batch = RectBatch::new();
+        batch.push_rect(Rect::from_size((10.0, 10.0)), &Material::default());
+        let capacity = (batch.vertices.capacity(), batch.data.capacity());
+        batch.clear();
+        assert!(batch.is_empty());
+        assert_eq!(0, batch.texel_count());
+        assert_eq!(capacity, (batch.vertices.capacity(), batch.data.capacity()));
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    // The texel at `index` in the data of the batch
+    fn texel(batch: &RectBatch, index: usize) -> &[f32] {
+        &batch.data()[index * 4..(index + 1) * 4]
     }
 }
@@ -0,0 +1,115 @@
+use anyhow::Error;
+use math::{Rect, Vector2, Vector4};
+use web_sys::{WebGlProgram, WebGlTexture};
+
+use super::{DynamicBuffer, WebGl};
+use std::rc::Rc;
+
+const VERTEX_SHADER: &str = r#"
+// Position of the vertex
+attribute vec2 a_position;
+
+// Position in the glyph atlas
+attribute vec2 a_uv;
+
+// Pixel dimensions of the canvas
+uniform vec2 u_viewport;
+
+varying vec2 v_uv;
+
+void main() {
+    v_uv = a_uv;
+    vec2 zero_to_one = a_position / u_viewport;
+    vec2 zero_to_two = zero_to_one * 2.0;
+    vec2 clip_space = zero_to_two - 1.0;
+    vec2 origin_top_left = vec2(1.0, -1.0) * clip_space;
+    gl_Position = vec4(origin_top_left, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+precision mediump float;
+
+// Glyphs are rasterized in white, so only the alpha channel is used as
+// coverage.
+uniform sampler2D u_atlas;
+
+// Color of the text
+uniform vec4 u_color;
+
+varying vec2 v_uv;
+
+void main() {
+    float coverage = texture2D(u_atlas, v_uv).a;
+    gl_FragColor = vec4(u_color.rgb, u_color.a * coverage);
+}
+"#;
+
+/// The vertex attributes of the text shader as `(name, size)` pairs.
+const ATTRIBUTES: [(&str, i32); 2] = [("a_position", 2), ("a_uv", 2)];
+
+/// The number of `f32` values per vertex: `[x, y, u, v]`.
+pub const TEXT_VERTEX_SIZE: usize = 4;
+
+/// Draws glyph quads that sample from the glyph atlas texture.
+pub struct TextShader {
+    gl: Rc<WebGl>,
+    program: WebGlProgram,
+    // Reused for every run, so painting text doesn't allocate GPU memory.
+    buffer: DynamicBuffer,
+
+    viewport: Vector2,
+}
+
+impl TextShader {
+    pub fn try_new(gl: &Rc<WebGl>) -> Result<TextShader, Error> {
+        let program = gl.try_create_shader_program(VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let buffer = gl.new_dynamic_buffer()?;
+        Ok(TextShader {
+            gl: Rc::clone(gl),
+            program,
+            buffer,
+            viewport: Vector2::zero(),
+        })
+    }
+
+    pub fn set_viewport(&mut self, viewport: Vector2) {
+        self.viewport = viewport;
+    }
+
+    /// Paint glyph quads built with [push_glyph_quad].
+    pub fn paint_glyphs(
+        &mut self,
+        atlas: &WebGlTexture,
+        vertices: &[f32],
+        color: Vector4,
+    ) -> Result<(), Error> {
+        if vertices.is_empty() {
+            return Ok(());
+        }
+        self.gl
+            .set_uniform_vec2(&self.program, "u_viewport", self.viewport)?;
+        self.gl.set_uniform_vec4(&self.program, "u_color", color)?;
+        self.gl.set_uniform_i32(&self.program, "u_atlas", 0)?;
+        self.gl.upload_dynamic_buffer(&mut self.buffer, vertices)?;
+        self.gl.bind_texture(atlas);
+        let vertex_count = (vertices.len() / TEXT_VERTEX_SIZE) as i32;
+        self.gl
+            .draw_triangles(&self.program, &self.buffer, &ATTRIBUTES, vertex_count)
+    }
+}
+
+/// Append the two triangles that cover `rect` on screen and `uv` in the atlas.
+pub fn push_glyph_quad(vertices: &mut Vec<f32>, rect: Rect, uv: Rect) {
+    let corners = [
+        (rect.min.x, rect.min.y, uv.min.x, uv.min.y),
+        (rect.min.x, rect.max.y, uv.min.x, uv.max.y),
+        (rect.max.x, rect.min.y, uv.max.x, uv.min.y),
+        (rect.min.x, rect.max.y, uv.min.x, uv.max.y),
+        (rect.max.x, rect.min.y, uv.max.x, uv.min.y),
+        (rect.max.x, rect.max.y, uv.max.x, uv.max.y),
+    ];
+    for (x, y, u, v) in corners {
+        vertices.extend_from_slice(&[x, y, u, v]);
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Error;
+use layout::ImageSizes;
+use math::Vector2;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use web_sys::WebGlTexture;
+
+use super::WebGl;
+use crate::software::Pixmap;
+
+/// An image that has been uploaded to the GPU.
+pub struct ImageTexture {
+    pub texture: WebGlTexture,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageTexture {
+    #[must_use]
+    pub fn size(&self) -> Vector2 {
+        Vector2::new(self.width as f32, self.height as f32)
+    }
+}
+
+/// Keeps a texture for every loaded image, keyed by the image source used by
+/// `Image` widgets. The size of each image is also recorded in `sizes`, so
+/// layout can measure images once they have loaded.
+pub struct TextureCache {
+    gl: Rc<WebGl>,
+    textures: HashMap<String, ImageTexture>,
+    sizes: ImageSizes,
+}
+
+impl TextureCache {
+    #[must_use]
+    pub fn new(gl: &Rc<WebGl>) -> TextureCache {
+        TextureCache {
+            gl: Rc::clone(gl),
+            textures: HashMap::new(),
+            sizes: ImageSizes::new(),
+        }
+    }
+
+    /// Upload an image, replacing any image that was already loaded with the
+    /// same source.
+    pub fn try_insert(&mut self, source: &str, image: &Pixmap) -> Result<(), Error> {
+        let (width, height) = (image.width(), image.height());
+        let texture = self.gl.new_texture(width, height)?;
+        self.gl
+            .upload_texture_region(&texture, 0, 0, width, height, image.data())?;
+        let texture = ImageTexture {
+            texture,
+            width,
+            height,
+        };
+        self.sizes.insert(source, texture.size());
+        self.textures.insert(source.to_string(), texture);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get(&self, source: &str) -> Option<&ImageTexture> {
+        self.textures.get(source)
+    }
+
+    /// The sizes of the loaded images. This is shared with the cache, so it
+    /// keeps up to date as more images load.
+    #[must_use]
+    pub fn sizes(&self) -> ImageSizes {
+        self.sizes.clone()
+    }
+}
+
+/// The image sources that need to be loaded. Each source is only requested
+/// once, so an image that fails to load isn't fetched again every frame.
+#[derive(Clone, Default, Debug)]
+pub struct ImageRequests {
+    requested: HashSet<String>,
+    pending: Vec<String>,
+}
+
+impl ImageRequests {
+    #[must_use]
+    pub fn new() -> ImageRequests {
+        ImageRequests::default()
+    }
+
+    /// Ask for `source` to be loaded, unless it already has been.
+    pub fn request(&mut self, source: &str) {
+        if !self.requested.contains(source) {
+            self.requested.insert(source.to_string());
+            self.pending.push(source.to_string());
+        }
+    }
+
+    /// The sources requested since the last call, in the order they were
+    /// first requested.
+    pub fn take(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_requests_only_request_each_source_once() {
+        let mut requests = ImageRequests::new();
+        requests.request("a.png");
+        requests.request("b.png");
+        requests.request("a.png");
+        assert_eq!(vec!["a.png", "b.png"], requests.take());
+        assert!(requests.take().is_empty());
+        requests.request("b.png");
+        requests.request("c.png");
+        assert_eq!(vec!["c.png"], requests.take());
+    }
+}
@@ -14,17 +14,6 @@ extern "C" {
     pub fn error(s: &str);
 }
 
-// The JS object passsed into the [get_context_with_context_options]
-// WebGlRenderingContext constructor. For some reason cargo sees this as dead
-// code.
-#[wasm_bindgen]
-struct WebGlOptions {
-    #[allow(dead_code)]
-    alpha: bool,
-    #[allow(dead_code)]
-    premultiplied_alpha: bool,
-}
-
 /// Try to get a reference to the [WebGlCanvasElement] identified by the provided ID.
 pub fn try_get_canvas(canvas_id: &str) -> Result<HtmlCanvasElement, Error> {
     let window = web_sys::window().ok_or_else(|| anyhow!("could not get window"))?;
@@ -40,14 +29,20 @@ pub fn try_get_canvas(canvas_id: &str) -> Result<HtmlCanvasElement, Error> {
 
 /// Try to get a [WebGlRenderingContext] from a reference to a [HtmlCanvasElement].
 pub fn try_get_webgl_context(canvas: &HtmlCanvasElement) -> Result<WebGlRenderingContext, Error> {
-    let options = WebGlOptions {
-        alpha: false,
+    let options = js_sys::Object::new();
+    for (key, value) in [
+        ("alpha", false),
         // This is needed otherwise semi-transparent colors are assumed to have
         // transparency multiplied into their color, and are rendered weirdly.
-        premultiplied_alpha: false,
-    };
+        ("premultipliedAlpha", false),
+        // Rounded clips are drawn into the stencil buffer
+        ("stencil", true),
+    ] {
+        js_sys::Reflect::set(&options, &key.into(), &value.into())
+            .map_err(|_| anyhow!("could not set webgl context options"))?;
+    }
     canvas
-        .get_context_with_context_options("webgl", &options.into())
+        .get_context_with_context_options("webgl", &options)
         .map_err(|_| anyhow::anyhow!("could not get webgl context"))?
         .ok_or_else(|| anyhow::anyhow!("could not get webgl context"))?
         .dyn_into::<WebGlRenderingContext>()
@@ -1,9 +1,8 @@
 use anyhow::{anyhow, Error};
-use bytemuck::cast_slice;
 use js_sys::WebAssembly;
-use math::{Vector2, Vector3, Vector4};
+use math::{Rect, Vector2, Vector4};
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlCanvasElement, WebGlBuffer, WebGlProgram, WebGlRenderingContext};
+use web_sys::{HtmlCanvasElement, WebGlBuffer, WebGlProgram, WebGlRenderingContext, WebGlTexture};
 
 pub struct WebGl {
     pub gl: WebGlRenderingContext,
@@ -17,47 +16,81 @@ impl WebGl {
 
     pub fn clear(&self, r: f32, g: f32, b: f32, a: f32) {
         self.gl.clear_color(r, g, b, a);
-        self.gl.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
+        self.gl.clear(
+            WebGlRenderingContext::COLOR_BUFFER_BIT | WebGlRenderingContext::STENCIL_BUFFER_BIT,
+        );
     }
 
-    pub fn draw_line(
-        &self,
-        program: &WebGlProgram,
-        vertex_attribute_name: &str,
-        buffer: &Buffer,
-    ) -> Result<(), Error> {
-        self.gl.use_program(Some(program));
-
+    /// Only draw pixels whose stencil value is `depth`, or draw anywhere when
+    /// `depth` is zero. Rounded clips are drawn into the stencil buffer, so
+    /// the stencil holds how many of them cover each pixel.
+    pub fn set_stencil_test(&self, depth: u32) {
+        self.gl.color_mask(true, true, true, true);
+        if depth == 0 {
+            self.gl.disable(WebGlRenderingContext::STENCIL_TEST);
+            return;
+        }
+        self.gl.enable(WebGlRenderingContext::STENCIL_TEST);
         self.gl
-            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&buffer.buffer));
-        let location = self.gl.get_attrib_location(program, vertex_attribute_name) as u32;
-        self.gl.vertex_attrib_pointer_with_i32(
-            location,
-            buffer.element_size as i32,
-            WebGlRenderingContext::FLOAT,
-            false,
-            0,
-            0,
+            .stencil_func(WebGlRenderingContext::EQUAL, depth as i32, 0xff);
+        self.gl.stencil_op(
+            WebGlRenderingContext::KEEP,
+            WebGlRenderingContext::KEEP,
+            WebGlRenderingContext::KEEP,
         );
-        self.gl.enable_vertex_attrib_array(location);
-        self.gl
-            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, None);
+    }
 
-        self.gl.draw_arrays(
-            WebGlRenderingContext::LINES,
-            0,
-            (buffer.len / buffer.element_size) as i32,
-        );
+    /// Make the following draws add one to the stencil value of the pixels
+    /// they cover that are inside all `depth` clips, or remove one when
+    /// `increment` is false, without drawing any color. Call `set_stencil_test`
+    /// afterwards to draw normally again.
+    pub fn set_stencil_update(&self, depth: u32, increment: bool) {
+        self.gl.enable(WebGlRenderingContext::STENCIL_TEST);
+        self.gl.color_mask(false, false, false, false);
+        self.gl
+            .stencil_func(WebGlRenderingContext::EQUAL, depth as i32, 0xff);
+        let op = if increment {
+            WebGlRenderingContext::INCR
+        } else {
+            WebGlRenderingContext::DECR
+        };
+        self.gl
+            .stencil_op(WebGlRenderingContext::KEEP, WebGlRenderingContext::KEEP, op);
+    }
 
-        Ok(())
+    /// Restrict drawing to `rect`, or allow drawing anywhere when `rect` is
+    /// `None`. The rect is in pixels from the top-left corner of the viewport.
+    pub fn set_scissor(&self, rect: Option<Rect>, viewport: Vector2) {
+        match rect {
+            Some(rect) => {
+                // Only pixels whose centers are inside the rect are drawn, like
+                // the edges of shapes. WebGL measures the scissor box from the
+                // bottom-left corner.
+                let pixel = |edge: f32| (edge - 0.5).ceil() as i32;
+                let (min_x, max_x) = (pixel(rect.min.x), pixel(rect.max.x));
+                let (min_y, max_y) = (
+                    pixel(viewport.y - rect.max.y),
+                    pixel(viewport.y - rect.min.y),
+                );
+                self.gl.enable(WebGlRenderingContext::SCISSOR_TEST);
+                self.gl
+                    .scissor(min_x, min_y, (max_x - min_x).max(0), (max_y - min_y).max(0));
+            }
+            None => self.gl.disable(WebGlRenderingContext::SCISSOR_TEST),
+        }
     }
 
-    pub fn draw_mesh(
+    /// Draw triangles from a buffer of interleaved vertices. `attributes` are
+    /// `(name, size)` pairs in the order they appear in each vertex, where the
+    /// size is the number of `f32` components.
+    pub fn draw_triangles(
         &self,
         program: &WebGlProgram,
-        vertex_attribute_name: &str,
-        buffer: &Buffer,
+        buffer: &DynamicBuffer,
+        attributes: &[(&str, i32)],
+        vertex_count: i32,
     ) -> Result<(), Error> {
+        self.gl.use_program(Some(program));
         self.gl.enable(WebGlRenderingContext::BLEND);
         self.gl.blend_func(
             WebGlRenderingContext::SRC_ALPHA,
@@ -65,61 +98,255 @@ impl WebGl {
         );
         self.gl
             .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&buffer.buffer));
+        let stride = attributes.iter().map(|(_, size)| size).sum();
+        let mut offset = 0;
+        for (name, size) in attributes {
+            self.bind_attribute(program, name, *size, stride, offset)?;
+            offset += size;
+        }
+        self.gl
+            .draw_arrays(WebGlRenderingContext::TRIANGLES, 0, vertex_count);
+        self.gl
+            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, None);
+        Ok(())
+    }
 
-        // Upload to GPU
-        self.gl.use_program(Some(program));
-        let location = self.gl.get_attrib_location(program, vertex_attribute_name) as u32;
+    /// Point a vertex attribute at the currently bound array buffer. `size`,
+    /// `stride` and `offset` are measured in `f32` elements, not bytes.
+    pub fn bind_attribute(
+        &self,
+        program: &WebGlProgram,
+        name: &str,
+        size: i32,
+        stride: i32,
+        offset: i32,
+    ) -> Result<(), Error> {
+        let location = self.gl.get_attrib_location(program, name);
+        if location < 0 {
+            return Err(anyhow!("could not get location for attribute {}", name));
+        }
+        let location = location as u32;
         self.gl.vertex_attrib_pointer_with_i32(
             location,
-            buffer.element_size as i32,
+            size,
             WebGlRenderingContext::FLOAT,
             false,
-            0,
-            0,
+            stride * 4,
+            offset * 4,
         );
         self.gl.enable_vertex_attrib_array(location);
-        self.gl.draw_arrays(
-            WebGlRenderingContext::TRIANGLES,
-            0,
-            (buffer.len / buffer.element_size) as i32,
-        );
-        self.gl
-            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, None);
         Ok(())
     }
 
-    pub fn new_array_buffer(&self, values: &[Vector3]) -> Result<Buffer, Error> {
-        let bytes: &[f32] = cast_slice(values);
-        let memory_buffer = wasm_bindgen::memory()
-            .dyn_into::<WebAssembly::Memory>()
-            .map_err(|_| anyhow!("could not get web assembly memory"))?
-            .buffer();
-        // Divide by 4 to get an index to individual f32 elements, because an f32
-        // is 4 bytes long.
-        let arr_location = bytes.as_ptr() as u32 / 4;
-        let js_array = js_sys::Float32Array::new(&memory_buffer)
-            .subarray(arr_location, arr_location + bytes.len() as u32);
-
+    /// Create an empty buffer that is meant to be refilled every frame.
+    pub fn new_dynamic_buffer(&self) -> Result<DynamicBuffer, Error> {
         let buffer = self
             .gl
             .create_buffer()
             .ok_or_else(|| anyhow!("could not create buffer"))?;
+        Ok(DynamicBuffer {
+            buffer,
+            capacity: 0,
+        })
+    }
+
+    /// Replace the contents of a `DynamicBuffer`. The GPU storage is only
+    /// reallocated when `values` doesn't fit in the current capacity, so
+    /// uploading a similar amount of data every frame doesn't allocate.
+    pub fn upload_dynamic_buffer(
+        &self,
+        buffer: &mut DynamicBuffer,
+        values: &[f32],
+    ) -> Result<(), Error> {
         self.gl
-            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&buffer));
-        self.gl.buffer_data_with_array_buffer_view(
+            .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&buffer.buffer));
+        if values.len() > buffer.capacity {
+            buffer.capacity = values.len().next_power_of_two();
+            self.gl.buffer_data_with_i32(
+                WebGlRenderingContext::ARRAY_BUFFER,
+                (buffer.capacity * 4) as i32,
+                WebGlRenderingContext::DYNAMIC_DRAW,
+            );
+        }
+        let js_array = float32_array(values)?;
+        self.gl.buffer_sub_data_with_i32_and_array_buffer_view(
             WebGlRenderingContext::ARRAY_BUFFER,
+            0,
             &js_array,
-            WebGlRenderingContext::STATIC_DRAW,
         );
         self.gl
             .bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, None);
-        Ok(Buffer {
-            buffer,
-            len: bytes.len() as u32,
-            element_size: 3,
+        Ok(())
+    }
+
+    /// Bind a texture to the first texture unit.
+    pub fn bind_texture(&self, texture: &WebGlTexture) {
+        self.gl.active_texture(WebGlRenderingContext::TEXTURE0);
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(texture));
+    }
+
+    /// Create a transparent RGBA texture.
+    pub fn new_texture(&self, width: u32, height: u32) -> Result<WebGlTexture, Error> {
+        let texture = self
+            .gl
+            .create_texture()
+            .ok_or_else(|| anyhow!("could not create texture"))?;
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&texture));
+        let pixels = vec![0; (width * height * 4) as usize];
+        self.gl
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                WebGlRenderingContext::TEXTURE_2D,
+                0,
+                WebGlRenderingContext::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                WebGlRenderingContext::RGBA,
+                WebGlRenderingContext::UNSIGNED_BYTE,
+                Some(&pixels),
+            )
+            .map_err(|_| anyhow!("could not allocate texture"))?;
+        self.set_texture_parameters(WebGlRenderingContext::LINEAR);
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, None);
+        Ok(texture)
+    }
+
+    // Set the filtering of the bound texture, and clamp it to its edges.
+    fn set_texture_parameters(&self, filter: u32) {
+        for (param, value) in [
+            (WebGlRenderingContext::TEXTURE_MIN_FILTER, filter),
+            (WebGlRenderingContext::TEXTURE_MAG_FILTER, filter),
+            (
+                WebGlRenderingContext::TEXTURE_WRAP_S,
+                WebGlRenderingContext::CLAMP_TO_EDGE,
+            ),
+            (
+                WebGlRenderingContext::TEXTURE_WRAP_T,
+                WebGlRenderingContext::CLAMP_TO_EDGE,
+            ),
+        ] {
+            self.gl
+                .tex_parameteri(WebGlRenderingContext::TEXTURE_2D, param, value as i32);
+        }
+    }
+
+    /// Enable the float textures that `DataTexture`s are made of. They are an
+    /// extension in WebGL 1, but almost every device supports them.
+    pub fn try_enable_float_textures(&self) -> Result<(), Error> {
+        match self.gl.get_extension("OES_texture_float") {
+            Ok(Some(_)) => Ok(()),
+            _ => Err(anyhow!("float textures are not supported")),
+        }
+    }
+
+    /// The largest width and height of a texture.
+    pub fn max_texture_size(&self) -> u32 {
+        self.gl
+            .get_parameter(WebGlRenderingContext::MAX_TEXTURE_SIZE)
+            .ok()
+            .and_then(|size| size.as_f64())
+            // The smallest maximum allowed by WebGL
+            .map_or(64, |size| size as u32)
+    }
+
+    /// Create an empty float texture that is `width` texels wide, and is meant
+    /// to be refilled every frame. Texels are read exactly, without filtering.
+    pub fn new_data_texture(&self, width: u32) -> Result<DataTexture, Error> {
+        let texture = self
+            .gl
+            .create_texture()
+            .ok_or_else(|| anyhow!("could not create texture"))?;
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&texture));
+        self.set_texture_parameters(WebGlRenderingContext::NEAREST);
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, None);
+        Ok(DataTexture {
+            texture,
+            width,
+            rows: 0,
         })
     }
 
+    /// Replace the first rows of a `DataTexture` with `values`, which are RGBA
+    /// texels and must fill whole rows. Like `upload_dynamic_buffer`, the GPU
+    /// storage is only reallocated when `values` doesn't fit.
+    pub fn upload_data_texture(
+        &self,
+        texture: &mut DataTexture,
+        values: &[f32],
+    ) -> Result<(), Error> {
+        let rows = (values.len() / (texture.width as usize * 4)) as u32;
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&texture.texture));
+        if rows > texture.rows {
+            texture.rows = rows.next_power_of_two();
+            self.gl
+                .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                    WebGlRenderingContext::TEXTURE_2D,
+                    0,
+                    WebGlRenderingContext::RGBA as i32,
+                    texture.width as i32,
+                    texture.rows as i32,
+                    0,
+                    WebGlRenderingContext::RGBA,
+                    WebGlRenderingContext::FLOAT,
+                    None,
+                )
+                .map_err(|_| anyhow!("could not allocate data texture"))?;
+        }
+        let js_array = float32_array(values)?;
+        self.gl
+            .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                WebGlRenderingContext::TEXTURE_2D,
+                0,
+                0,
+                0,
+                texture.width as i32,
+                rows as i32,
+                WebGlRenderingContext::RGBA,
+                WebGlRenderingContext::FLOAT,
+                Some(&js_array),
+            )
+            .map_err(|_| anyhow!("could not upload data texture"))?;
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, None);
+        Ok(())
+    }
+
+    /// Overwrite a region of a texture with RGBA pixels.
+    pub fn upload_texture_region(
+        &self,
+        texture: &WebGlTexture,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<(), Error> {
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(texture));
+        self.gl
+            .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+                WebGlRenderingContext::TEXTURE_2D,
+                0,
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                WebGlRenderingContext::RGBA,
+                WebGlRenderingContext::UNSIGNED_BYTE,
+                Some(pixels),
+            )
+            .map_err(|_| anyhow!("could not upload texture region"))?;
+        self.gl
+            .bind_texture(WebGlRenderingContext::TEXTURE_2D, None);
+        Ok(())
+    }
+
     pub fn set_uniform_i32(
         &self,
         program: &WebGlProgram,
@@ -191,13 +418,45 @@ impl WebGl {
     }
 }
 
-/// Represents an arbitrary amount of data held in the GPU.
+/// Get a view of `values` in WebAssembly memory. The view is only valid until
+/// the next allocation, so it must be consumed immediately.
+fn float32_array(values: &[f32]) -> Result<js_sys::Float32Array, Error> {
+    let memory_buffer = wasm_bindgen::memory()
+        .dyn_into::<WebAssembly::Memory>()
+        .map_err(|_| anyhow!("could not get web assembly memory"))?
+        .buffer();
+    // Divide by 4 to get an index to individual f32 elements, because an f32
+    // is 4 bytes long.
+    let arr_location = values.as_ptr() as u32 / 4;
+    Ok(js_sys::Float32Array::new(&memory_buffer)
+        .subarray(arr_location, arr_location + values.len() as u32))
+}
+
+/// A GPU buffer that is reused across frames.
 #[derive(Clone, Debug)]
-pub struct Buffer {
+pub struct DynamicBuffer {
     /// The [WebGlBuffer] that holds the data.
     pub buffer: WebGlBuffer,
-    /// The number of elements in the buffer.
-    pub len: u32,
-    /// The number of elements per type. For example, Vector2 is size 2.
-    pub element_size: u32,
+    /// The number of `f32` elements the buffer can hold without reallocating.
+    capacity: usize,
+}
+
+/// A float texture that is reused across frames, for passing data to shaders
+/// that doesn't fit in vertex attributes.
+#[derive(Clone, Debug)]
+pub struct DataTexture {
+    /// The [WebGlTexture] that holds the data.
+    pub texture: WebGlTexture,
+    /// The number of texels in each row.
+    width: u32,
+    /// The number of rows the texture can hold without reallocating.
+    rows: u32,
+}
+
+impl DataTexture {
+    /// The size of the texture in texels, for finding texels in a shader.
+    #[must_use]
+    pub fn size(&self) -> Vector2 {
+        Vector2::new(self.width as f32, self.rows as f32)
+    }
 }
@@ -1,8 +1,18 @@
 #![warn(clippy::all)]
 use layout::Layout;
 
+pub mod application;
+pub mod atlas;
 pub mod browser;
+pub mod software;
 
 pub trait AppDriver {
     fn tick(&mut self, time: f32) -> Box<dyn Layout>;
+
+    /// Whether the app needs another frame even if there is no input, because
+    /// something is still animating. Apps that don't track their animations
+    /// are always ticked.
+    fn is_animating(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,5 @@
+mod pixmap;
+pub use pixmap::*;
+
+mod renderer;
+pub use renderer::*;
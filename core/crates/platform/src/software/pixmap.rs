@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Error};
+use layout::Color;
+use std::path::Path;
+
+/// An RGBA image with 8 bits per channel. Pixels are stored row by row,
+/// starting from the top-left corner.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Pixmap {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Pixmap {
+    /// Create a fully transparent image.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> Pixmap {
+        Pixmap {
+            width,
+            height,
+            data: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Wrap existing RGBA pixel data.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Result<Pixmap, Error> {
+        if data.len() != (width * height * 4) as usize {
+            return Err(anyhow!(
+                "expected {} bytes for a {}x{} image but got {}",
+                width * height * 4,
+                width,
+                height,
+                data.len()
+            ));
+        }
+        Ok(Pixmap {
+            width,
+            height,
+            data,
+        })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw RGBA bytes of the image.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the RGBA value of a pixel.
+    ///
+    /// # Panics
+    ///
+    /// Will panic when the pixel is outside the image.
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = self.index(x, y);
+        self.data[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Overwrite every pixel with `color`.
+    pub fn fill(&mut self, color: Color) {
+        let rgba = to_rgba8(color);
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// Draw `color` over a pixel with "source over" alpha blending.
+    pub fn blend_pixel(&mut self, x: u32, y: u32, color: Color) {
+        let src = color.to_linear();
+        if src.w <= 0.0 {
+            return;
+        }
+        let [r, g, b, a] = self.pixel(x, y);
+        let dst_a = f32::from(a) / 255.0;
+        let out_a = src.w + dst_a * (1.0 - src.w);
+        let blend = |src_c: f32, dst_c: u8| {
+            let dst_c = f32::from(dst_c) / 255.0;
+            (src_c * src.w + dst_c * dst_a * (1.0 - src.w)) / out_a
+        };
+        self.set_pixel(
+            x,
+            y,
+            [
+                to_u8(blend(src.x, r)),
+                to_u8(blend(src.y, g)),
+                to_u8(blend(src.z, b)),
+                to_u8(out_a),
+            ],
+        );
+    }
+
+    /// Encode the image as a PNG file.
+    pub fn encode_png(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![];
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.data)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Write the image to a PNG file, replacing the file if it already exists.
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        std::fs::write(path, self.encode_png()?)?;
+        Ok(())
+    }
+
+    /// Decode a PNG file. Images without an alpha channel are treated as
+    /// opaque.
+    pub fn decode_png(bytes: &[u8]) -> Result<Pixmap, Error> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => return Err(anyhow!("unexpected indexed png")),
+        };
+        Pixmap::from_rgba(info.width, info.height, data)
+    }
+
+    /// Read a PNG file from disk.
+    pub fn read_png<P: AsRef<Path>>(path: P) -> Result<Pixmap, Error> {
+        let bytes = std::fs::read(path)?;
+        Pixmap::decode_png(&bytes)
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) is outside the {}x{} image",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        ((y * self.width + x) * 4) as usize
+    }
+}
+
+/// Convert a color with components from 0 - 255 into bytes.
+#[must_use]
+pub fn to_rgba8(color: Color) -> [u8; 4] {
+    let linear = color.to_linear();
+    [
+        to_u8(linear.x),
+        to_u8(linear.y),
+        to_u8(linear.z),
+        to_u8(linear.w),
+    ]
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
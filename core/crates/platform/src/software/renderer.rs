@@ -0,0 +1,851 @@
+use layout::{BorderRadius, BoxConstraints, Color, ImagePaint, Layout, LayoutTree, Material};
+use math::{Rect, Vector2};
+use paint::{DisplayItem, DisplayList};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::Pixmap;
+
+/// Paints a `DisplayList` into a `Pixmap` on the CPU.
+///
+/// This produces the same output as the `StandardShader`: pixels are covered
+/// when their center is inside a shape, borders are resolved per pixel in the
+/// same order as the fragment shader, and everything is alpha blended. It
+/// doesn't need a GPU or a browser, so it can run in tests and on CI.
+///
+/// Text is not drawn yet, because there are no fonts available outside of the
+/// browser. Images are only drawn once they have been added with `add_image`.
+#[derive(Clone, Debug)]
+pub struct SoftwareRenderer {
+    pixmap: Pixmap,
+    clips: Vec<Clip>,
+    // Images that can be painted, keyed by their source
+    images: HashMap<String, Rc<Pixmap>>,
+}
+
+impl SoftwareRenderer {
+    /// Create a renderer that draws into a transparent image.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> SoftwareRenderer {
+        SoftwareRenderer {
+            pixmap: Pixmap::new(width, height),
+            clips: vec![],
+            images: HashMap::new(),
+        }
+    }
+
+    /// Lay out and paint a widget tree, using the size of the image as the
+    /// maximum constraints.
+    #[must_use]
+    pub fn render(widget: &dyn Layout, width: u32, height: u32, background: Color) -> Pixmap {
+        let viewport = Vector2::new(width as f32, height as f32);
+        let tree = LayoutTree::from_root(widget, &BoxConstraints::from_max(viewport));
+        let mut renderer = SoftwareRenderer::new(width, height);
+        renderer.clear(background);
+        renderer.paint_tree(&tree);
+        renderer.into_pixmap()
+    }
+
+    #[must_use]
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.pixmap
+    }
+
+    #[must_use]
+    pub fn into_pixmap(self) -> Pixmap {
+        self.pixmap
+    }
+
+    /// Make an image available to paint for `Image` widgets with `source`.
+    pub fn add_image<S: Into<String>>(&mut self, source: S, image: Pixmap) {
+        self.images.insert(source.into(), Rc::new(image));
+    }
+
+    pub fn clear(&mut self, color: Color) {
+        self.pixmap.fill(color);
+    }
+
+    pub fn paint_tree(&mut self, tree: &LayoutTree) {
+        self.replay(&DisplayList::from_tree(tree));
+    }
+
+    /// Draw every item in the display list, in order.
+    pub fn replay(&mut self, list: &DisplayList) {
+        for item in list {
+            match item {
+                DisplayItem::Rect { rect, color } => {
+                    self.fill_rect(*rect, &Material::filled(*color));
+                }
+                DisplayItem::BorderedRect { rect, material } => {
+                    self.fill_rect(*rect, material);
+                }
+                DisplayItem::Shadow {
+                    rect,
+                    radius,
+                    blur_radius,
+                    color,
+                } => self.fill_shadow(*rect, *radius, *blur_radius, *color),
+                DisplayItem::Line {
+                    start,
+                    end,
+                    width,
+                    color,
+                } => self.fill_line(*start, *end, *width, *color),
+                DisplayItem::PushClip { rect, radius } => {
+                    let bounds = match self.clips.last() {
+                        Some(parent) => parent.bounds.intersection(*rect),
+                        None => *rect,
+                    };
+                    let radii = radius.fit(rect.size()).to_array();
+                    let corners = (!radius.is_zero()).then_some(Corners { x: radii, y: radii });
+                    self.clips.push(Clip {
+                        bounds,
+                        rect: *rect,
+                        corners,
+                    });
+                }
+                DisplayItem::PopClip => {
+                    self.clips.pop();
+                }
+                DisplayItem::Image { rect, image } => self.draw_image(*rect, image),
+                DisplayItem::Text { .. } => {}
+            }
+        }
+        self.clips.clear();
+    }
+
+    /// Fill a rect and its borders. Each pixel is shaded the same way as the
+    /// fragment shader: top and bottom borders take priority over the left and
+    /// right borders, except in rounded corners where the two borders meet
+    /// along the corner. Gradients are sampled at each pixel center, and
+    /// borders are drawn over them.
+    pub fn fill_rect(&mut self, rect: Rect, material: &Material) {
+        let sides = material.borders.to_array();
+        let widths = sides.map(|side| side.map_or(0.0, |side| side.width));
+        let colors = sides.map(|side| side.map_or(Color::transparent(), |side| side.color));
+        let radii = material.radius.fit(rect.size()).to_array();
+        let outer = Corners { x: radii, y: radii };
+        let (inner_rect, inner) = inner_edge(rect, widths, radii);
+        self.shade(rect, |point| {
+            let outer_coverage = rounded_rect_coverage(point, rect, &outer);
+            if outer_coverage <= 0.0 {
+                return None;
+            }
+            let fill_coverage =
+                rounded_rect_coverage(point, inner_rect, &inner).min(outer_coverage);
+            let side = border_side(point, rect, inner_rect, widths, radii);
+            let color = mix_coverage(
+                material.fill.color_at(point, rect),
+                fill_coverage,
+                colors[side],
+                outer_coverage - fill_coverage,
+            );
+            Some(color)
+        });
+    }
+
+    /// Fill a rect with rounded corners, blurred with a Gaussian whose
+    /// standard deviation is half of `blur_radius`. This uses the same
+    /// analytic approximation as the `ShadowShader`, so the blur is smooth
+    /// rather than sampled from a blurred image.
+    pub fn fill_shadow(
+        &mut self,
+        rect: Rect,
+        radius: BorderRadius,
+        blur_radius: f32,
+        color: Color,
+    ) {
+        let sigma = blur_radius / 2.0;
+        if sigma <= 0.0 {
+            let material = Material {
+                radius,
+                ..Material::filled(color)
+            };
+            self.fill_rect(rect, &material);
+            return;
+        }
+        let radii = radius.fit(rect.size()).to_array();
+        self.shade(rect.inflate(3.0 * sigma), |point| {
+            let coverage = rounded_box_shadow(point, rect, radii, sigma);
+            (coverage > 0.0)
+                .then(|| Color::rgba(color.r, color.g, color.b, color.a * coverage.min(1.0)))
+        });
+    }
+
+    /// Draw an image that was added with `add_image` inside `rect`. Each pixel
+    /// takes the nearest pixel of the image, without any filtering. Images
+    /// that haven't been added aren't drawn.
+    pub fn draw_image(&mut self, rect: Rect, image: &ImagePaint) {
+        let Some(pixmap) = self.images.get(&image.source).map(Rc::clone) else {
+            return;
+        };
+        let size = Vector2::new(pixmap.width() as f32, pixmap.height() as f32);
+        self.shade(rect, |point| {
+            let position = image.sample(size, point, rect)?;
+            let x = (position.x as u32).min(pixmap.width() - 1);
+            let y = (position.y as u32).min(pixmap.height() - 1);
+            let [r, g, b, a] = pixmap.pixel(x, y);
+            Some(Color::rgba(
+                f32::from(r),
+                f32::from(g),
+                f32::from(b),
+                f32::from(a),
+            ))
+        });
+    }
+
+    /// Fill a line with square ends that is `width` pixels wide.
+    pub fn fill_line(&mut self, start: Vector2, end: Vector2, width: f32, color: Color) {
+        let delta = end - start;
+        if delta.is_zero() {
+            return;
+        }
+        let length = delta.magnitude();
+        let direction = delta.normalized();
+        let normal = Vector2::new(-direction.y, direction.x);
+        let extent = normal * (width / 2.0);
+        let corners = [start + extent, start - extent, end + extent, end - extent];
+        let bounds = Rect::new(
+            Vector2::new(
+                corners.iter().map(|c| c.x).fold(f32::INFINITY, f32::min),
+                corners.iter().map(|c| c.y).fold(f32::INFINITY, f32::min),
+            ),
+            Vector2::new(
+                corners
+                    .iter()
+                    .map(|c| c.x)
+                    .fold(f32::NEG_INFINITY, f32::max),
+                corners
+                    .iter()
+                    .map(|c| c.y)
+                    .fold(f32::NEG_INFINITY, f32::max),
+            ),
+        );
+        self.shade(bounds, |point| {
+            let along = Vector2::dot(point - start, direction);
+            let across = Vector2::dot(point - start, normal);
+            let inside = (0.0..length).contains(&along) && across.abs() < width / 2.0;
+            inside.then_some(color)
+        });
+    }
+
+    // Blend the color returned by `shader` into every pixel whose center is
+    // inside both `bounds` and the current clip. The shader is given the pixel
+    // center and can skip the pixel by returning `None`.
+    fn shade<F>(&mut self, bounds: Rect, shader: F)
+    where
+        F: Fn(Vector2) -> Option<Color>,
+    {
+        let bounds = match self.clips.last() {
+            Some(clip) => clip.bounds.intersection(bounds),
+            None => bounds,
+        };
+        let (min_x, max_x) = pixel_span(bounds.min.x, bounds.max.x, self.pixmap.width());
+        let (min_y, max_y) = pixel_span(bounds.min.y, bounds.max.y, self.pixmap.height());
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let center = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+                if !self.clips.iter().all(|clip| clip.contains(center)) {
+                    continue;
+                }
+                if let Some(color) = shader(center) {
+                    self.pixmap.blend_pixel(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+// A clip that was pushed by the display list.
+#[derive(Clone, Debug)]
+struct Clip {
+    // The intersection of this clip with every clip outside of it
+    bounds: Rect,
+    rect: Rect,
+    // The rounded corners of `rect`, if it has any
+    corners: Option<Corners>,
+}
+
+impl Clip {
+    // Whether the pixel centered at `point` is inside the rounded corners. Like
+    // the stencil the `BrowserDriver` draws rounded clips into, a pixel is
+    // either inside or outside, so the curves aren't antialiased.
+    fn contains(&self, point: Vector2) -> bool {
+        self.corners
+            .as_ref()
+            .is_none_or(|corners| rounded_rect_coverage(point, self.rect, corners) >= 0.5)
+    }
+}
+
+// The x and y radius of each corner, ordered like `BorderRadius::to_array`.
+// The corners of the inside edge of a border are elliptical when the borders
+// on either side of them have different widths.
+#[derive(Clone, Debug)]
+struct Corners {
+    x: [f32; 4],
+    y: [f32; 4],
+}
+
+// Indices into `Borders::to_array`
+const TOP: usize = 0;
+const BOTTOM: usize = 1;
+const LEFT: usize = 2;
+const RIGHT: usize = 3;
+
+// The inside edge of the borders of `rect`, which is rounded by each corner
+// radius minus the width of the borders next to it.
+fn inner_edge(rect: Rect, widths: [f32; 4], radii: [f32; 4]) -> (Rect, Corners) {
+    let inner = Rect::new(
+        rect.min + Vector2::new(widths[LEFT], widths[TOP]),
+        rect.max - Vector2::new(widths[RIGHT], widths[BOTTOM]),
+    );
+    let horizontal = [LEFT, RIGHT, RIGHT, LEFT];
+    let vertical = [TOP, TOP, BOTTOM, BOTTOM];
+    let corners = Corners {
+        x: [0, 1, 2, 3].map(|i| (radii[i] - widths[horizontal[i]]).max(0.0)),
+        y: [0, 1, 2, 3].map(|i| (radii[i] - widths[vertical[i]]).max(0.0)),
+    };
+    (inner, corners)
+}
+
+// How much of the pixel centered at `point` is covered by `rect` with rounded
+// `corners`, from 0 to 1. Like everything else, the straight edges cover the
+// whole pixel when its center is inside them. Only the curves are antialiased,
+// using the signed distance from the pixel center to the curve.
+fn rounded_rect_coverage(point: Vector2, rect: Rect, corners: &Corners) -> f32 {
+    if point.x < rect.min.x || point.y < rect.min.y || point.x > rect.max.x || point.y > rect.max.y
+    {
+        return 0.0;
+    }
+    let middle = (rect.min + rect.max) / 2.0;
+    let (index, corner, direction) = match (point.x < middle.x, point.y < middle.y) {
+        (true, true) => (0, rect.min, Vector2::new(1.0, 1.0)),
+        (false, true) => (
+            1,
+            Vector2::new(rect.max.x, rect.min.y),
+            Vector2::new(-1.0, 1.0),
+        ),
+        (false, false) => (2, rect.max, Vector2::new(-1.0, -1.0)),
+        (true, false) => (
+            3,
+            Vector2::new(rect.min.x, rect.max.y),
+            Vector2::new(1.0, -1.0),
+        ),
+    };
+    let radii = Vector2::new(corners.x[index], corners.y[index]);
+    if radii.x <= 0.0 || radii.y <= 0.0 {
+        return 1.0;
+    }
+    let delta = point - (corner + direction * radii);
+    if delta.x * direction.x > 0.0 || delta.y * direction.y > 0.0 {
+        return 1.0;
+    }
+    (0.5 - ellipse_distance(delta, radii)).clamp(0.0, 1.0)
+}
+
+// An approximation of the signed distance from `delta` to an ellipse centered
+// on the origin, which is exact for circles. Negative inside the ellipse.
+fn ellipse_distance(delta: Vector2, radii: Vector2) -> f32 {
+    let scaled = delta / radii;
+    let gradient = (scaled / radii).magnitude();
+    if gradient <= 0.0 {
+        return -radii.min();
+    }
+    let length = scaled.magnitude();
+    (length - 1.0) * length / gradient
+}
+
+// The border whose color is used at `point`. Where two borders meet in a
+// square corner the top or bottom border wins, but in a rounded corner the
+// point belongs to whichever border it is further into, relative to the
+// widths, so the colors meet along the corner.
+fn border_side(
+    point: Vector2,
+    rect: Rect,
+    inner: Rect,
+    widths: [f32; 4],
+    radii: [f32; 4],
+) -> usize {
+    let middle = (rect.min + rect.max) / 2.0;
+    let band = |before: bool, after: bool, first: usize, second: usize| {
+        if before {
+            Some(first)
+        } else if after {
+            Some(second)
+        } else {
+            None
+        }
+    };
+    let vertical = band(point.y < inner.min.y, point.y > inner.max.y, TOP, BOTTOM);
+    let horizontal = band(point.x < inner.min.x, point.x > inner.max.x, LEFT, RIGHT);
+    let v = vertical.unwrap_or(if point.y < middle.y { TOP } else { BOTTOM });
+    let h = horizontal.unwrap_or(if point.x < middle.x { LEFT } else { RIGHT });
+    let corner = match (v, h) {
+        (TOP, LEFT) => 0,
+        (TOP, _) => 1,
+        (_, RIGHT) => 2,
+        _ => 3,
+    };
+    let depth = |side: usize| {
+        let distance = match side {
+            TOP => point.y - rect.min.y,
+            BOTTOM => rect.max.y - point.y,
+            LEFT => point.x - rect.min.x,
+            _ => rect.max.x - point.x,
+        };
+        if widths[side] > 0.0 {
+            distance / widths[side]
+        } else {
+            f32::INFINITY
+        }
+    };
+    match (vertical, horizontal) {
+        (Some(v), None) => v,
+        (None, Some(h)) => h,
+        (Some(v), Some(_)) if radii[corner] <= 0.0 => v,
+        _ if depth(v) <= depth(h) => v,
+        _ => h,
+    }
+}
+
+// How much of the pixel centered at `point` is covered by `rect` with rounded
+// corners after it is blurred with a Gaussian of standard deviation `sigma`.
+//
+// The blur is separable for a plain rect. With rounded corners the horizontal
+// blur of each row is still exact, using the error function across the width
+// of the row, and the vertical blur is integrated numerically with a few
+// samples. See https://madebyevan.com/shaders/fast-rounded-rectangle-shadows/.
+fn rounded_box_shadow(point: Vector2, rect: Rect, radii: [f32; 4], sigma: f32) -> f32 {
+    const SAMPLES: usize = 4;
+    let center = (rect.min + rect.max) / 2.0;
+    let half = (rect.max - rect.min) / 2.0;
+    let half = Vector2::new(half.x.max(0.0), half.y.max(0.0));
+    let point = point - center;
+    // Only the rows within three standard deviations of the point contribute
+    let start = (-3.0 * sigma).clamp(point.y - half.y, point.y + half.y);
+    let end = (3.0 * sigma).clamp(point.y - half.y, point.y + half.y);
+    let step = (end - start) / SAMPLES as f32;
+    (0..SAMPLES)
+        .map(|i| {
+            let y = start + step * (i as f32 + 0.5);
+            row_coverage(point.x, point.y - y, half, radii, sigma) * gaussian(y, sigma) * step
+        })
+        .sum()
+}
+
+// The horizontal blur at `x` of the row of the rounded rect at `y`, relative
+// to the center of the rect. The ends of the row are pulled in by the corners
+// on that side.
+fn row_coverage(x: f32, y: f32, half: Vector2, radii: [f32; 4], sigma: f32) -> f32 {
+    let (left, right) = if y < 0.0 {
+        (radii[0], radii[1])
+    } else {
+        (radii[3], radii[2])
+    };
+    let extent = |radius: f32| {
+        let delta = (half.y - radius - y.abs()).min(0.0);
+        half.x - radius + (radius * radius - delta * delta).max(0.0).sqrt()
+    };
+    let scale = std::f32::consts::FRAC_1_SQRT_2 / sigma;
+    let low = 0.5 + 0.5 * erf((x + extent(left)) * scale);
+    let high = 0.5 + 0.5 * erf((x - extent(right)) * scale);
+    low - high
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    let norm = (2.0 * std::f32::consts::PI).sqrt() * sigma;
+    (-(x * x) / (2.0 * sigma * sigma)).exp() / norm
+}
+
+// An approximation of the error function, with a maximum error of 5e-4. This
+// is the same one used by the shader, which doesn't have `erf`.
+fn erf(x: f32) -> f32 {
+    let a = x.abs();
+    let t = 1.0 + (0.278_393 + (0.230_389 + 0.078_108 * a * a) * a) * a;
+    let t = t * t;
+    x.signum() * (1.0 - 1.0 / (t * t))
+}
+
+// Combine the fill and border colors, weighted by how much of the pixel each
+// of them covers.
+fn mix_coverage(fill: Color, fill_coverage: f32, border: Color, border_coverage: f32) -> Color {
+    let fill_alpha = fill.a * fill_coverage;
+    let border_alpha = border.a * border_coverage;
+    let alpha = fill_alpha + border_alpha;
+    if alpha <= 0.0 {
+        return Color::transparent();
+    }
+    let channel = |fill: f32, border: f32| (fill * fill_alpha + border * border_alpha) / alpha;
+    Color::rgba(
+        channel(fill.r, border.r),
+        channel(fill.g, border.g),
+        channel(fill.b, border.b),
+        alpha,
+    )
+}
+
+// The range of pixels whose centers are inside `min..max`, clamped to the
+// size of the image.
+fn pixel_span(min: f32, max: f32, size: u32) -> (u32, u32) {
+    let clamp = |value: f32| (value - 0.5).ceil().clamp(0.0, size as f32) as u32;
+    (clamp(min), clamp(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use layout::{Alignment, BorderSide, Borders, BoxFit, BoxShadow, Container, EdgeInsets};
+
+    #[test]
+    fn clear_fills_every_pixel() {
+        let mut renderer = SoftwareRenderer::new(2, 2);
+        renderer.clear(Color::red());
+        let pixmap = renderer.pixmap();
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert_eq!(RED, pixmap.pixel(x, y));
+        }
+    }
+
+    #[test]
+    fn rect_covers_pixels_with_centers_inside() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.fill_rect(
+            Rect::new(Vector2::new(1.0, 1.0), Vector2::new(2.6, 3.0)),
+            &Material::filled(Color::red()),
+        );
+        let pixmap = renderer.pixmap();
+        assert_eq!(CLEAR, pixmap.pixel(0, 1));
+        assert_eq!(RED, pixmap.pixel(1, 1));
+        assert_eq!(RED, pixmap.pixel(2, 2));
+        assert_eq!(CLEAR, pixmap.pixel(3, 2));
+        assert_eq!(CLEAR, pixmap.pixel(1, 3));
+    }
+
+    #[test]
+    fn rect_outside_image_is_clipped() {
+        let mut renderer = SoftwareRenderer::new(2, 2);
+        renderer.fill_rect(
+            Rect::new(Vector2::new(-10.0, -10.0), Vector2::new(10.0, 10.0)),
+            &Material::filled(Color::red()),
+        );
+        assert_eq!(RED, renderer.pixmap().pixel(1, 1));
+    }
+
+    #[test]
+    fn borders_are_drawn_per_side() {
+        let mut renderer = SoftwareRenderer::new(5, 5);
+        let material = Material {
+            fill: Color::black().into(),
+            borders: Borders {
+                top: Some(BorderSide::new(Color::red(), 1.0)),
+                bottom: Some(BorderSide::new(Color::green(), 2.0)),
+                left: Some(BorderSide::new(Color::blue(), 1.0)),
+                right: None,
+            },
+            ..Material::default()
+        };
+        renderer.fill_rect(Rect::from_size((5.0, 5.0)), &material);
+        let pixmap = renderer.pixmap();
+        assert_eq!(RED, pixmap.pixel(2, 0));
+        assert_eq!(GREEN, pixmap.pixel(2, 3));
+        assert_eq!(GREEN, pixmap.pixel(2, 4));
+        assert_eq!(BLUE, pixmap.pixel(0, 2));
+        assert_eq!(BLACK, pixmap.pixel(4, 2));
+        assert_eq!(BLACK, pixmap.pixel(2, 2));
+    }
+
+    #[test]
+    fn top_border_takes_priority_in_corner() {
+        let mut renderer = SoftwareRenderer::new(3, 3);
+        let material = Material {
+            fill: Color::black().into(),
+            borders: Borders {
+                top: Some(BorderSide::new(Color::red(), 1.0)),
+                left: Some(BorderSide::new(Color::blue(), 1.0)),
+                ..Borders::default()
+            },
+            ..Material::default()
+        };
+        renderer.fill_rect(Rect::from_size((3.0, 3.0)), &material);
+        assert_eq!(RED, renderer.pixmap().pixel(0, 0));
+    }
+
+    #[test]
+    fn rounded_corners_are_antialiased() {
+        let mut renderer = SoftwareRenderer::new(20, 20);
+        let material = Material {
+            radius: BorderRadius::all(10.0),
+            ..Material::filled(Color::red())
+        };
+        renderer.fill_rect(Rect::from_size((20.0, 20.0)), &material);
+        let pixmap = renderer.pixmap();
+        assert_eq!(CLEAR, pixmap.pixel(0, 0));
+        assert_eq!(CLEAR, pixmap.pixel(19, 19));
+        assert_eq!(RED, pixmap.pixel(10, 10));
+        assert_eq!(RED, pixmap.pixel(3, 3));
+        // The curve passes through this pixel, so it is partly covered
+        let [r, g, b, a] = pixmap.pixel(1, 4);
+        assert_eq!([255, 0, 0], [r, g, b]);
+        assert!(a > 0 && a < 255, "alpha {a}");
+    }
+
+    #[test]
+    fn rounded_border_inside_edge_follows_border_widths() {
+        let mut renderer = SoftwareRenderer::new(20, 20);
+        let material = Material {
+            fill: Color::black().into(),
+            borders: Borders {
+                top: Some(BorderSide::new(Color::red(), 2.0)),
+                left: Some(BorderSide::new(Color::blue(), 6.0)),
+                ..Borders::default()
+            },
+            radius: BorderRadius::all(10.0),
+            ..Material::default()
+        };
+        renderer.fill_rect(Rect::from_size((20.0, 20.0)), &material);
+        let pixmap = renderer.pixmap();
+        // The inside corner is an ellipse 4 pixels wide and 8 pixels tall
+        assert_eq!(BLACK, pixmap.pixel(7, 5));
+        // Outside of the ellipse, and further into the left border than the
+        // top one relative to their widths
+        assert_eq!(BLUE, pixmap.pixel(6, 3));
+        assert_eq!(RED, pixmap.pixel(10, 1));
+        assert_eq!(BLUE, pixmap.pixel(1, 10));
+    }
+
+    #[test]
+    fn shadow_fades_out_from_edge() {
+        let mut renderer = SoftwareRenderer::new(40, 20);
+        renderer.fill_shadow(
+            Rect::new(Vector2::new(10.0, -20.0), Vector2::new(30.0, 40.0)),
+            BorderRadius::zero(),
+            4.0,
+            Color::black(),
+        );
+        let alpha = |x| renderer.pixmap().pixel(x, 10)[3];
+        // Solid in the middle, under half covered just outside the edge, and
+        // gone three standard deviations past it
+        assert_eq!(255, alpha(20));
+        assert!((90..=115).contains(&alpha(9)), "alpha {}", alpha(9));
+        assert!(alpha(5) < alpha(7) && alpha(7) < alpha(9));
+        assert_eq!(0, alpha(3));
+        assert_eq!(alpha(9), alpha(30));
+    }
+
+    #[test]
+    fn shadow_without_blur_is_solid() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.fill_shadow(
+            Rect::from_pos((1.0, 1.0), (2.0, 2.0)),
+            BorderRadius::zero(),
+            0.0,
+            Color::red(),
+        );
+        let pixmap = renderer.pixmap();
+        assert_eq!(RED, pixmap.pixel(1, 1));
+        assert_eq!(CLEAR, pixmap.pixel(0, 0));
+    }
+
+    #[test]
+    fn image_is_fit_inside_rect() {
+        // A 2x1 image, red on the left and blue on the right
+        let image = Pixmap::from_rgba(2, 1, [RED, BLUE].concat()).unwrap();
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.add_image("flag.png", image);
+        let paint = ImagePaint {
+            source: "flag.png".to_string(),
+            fit: BoxFit::Contain,
+            alignment: Alignment::center(),
+            ..ImagePaint::default()
+        };
+        renderer.draw_image(Rect::from_size((4.0, 4.0)), &paint);
+        let pixmap = renderer.pixmap();
+        // Scaled up to 4x2 and centered vertically
+        assert_eq!(CLEAR, pixmap.pixel(0, 0));
+        assert_eq!(RED, pixmap.pixel(0, 1));
+        assert_eq!(RED, pixmap.pixel(1, 2));
+        assert_eq!(BLUE, pixmap.pixel(2, 1));
+        assert_eq!(BLUE, pixmap.pixel(3, 2));
+        assert_eq!(CLEAR, pixmap.pixel(3, 3));
+    }
+
+    #[test]
+    fn image_that_isnt_added_is_skipped() {
+        let mut renderer = SoftwareRenderer::new(2, 2);
+        let paint = ImagePaint {
+            source: "missing.png".to_string(),
+            ..ImagePaint::default()
+        };
+        renderer.draw_image(Rect::from_size((2.0, 2.0)), &paint);
+        assert_eq!(CLEAR, renderer.pixmap().pixel(0, 0));
+    }
+
+    #[test]
+    fn translucent_rect_is_blended() {
+        let mut renderer = SoftwareRenderer::new(1, 1);
+        renderer.clear(Color::blue());
+        renderer.fill_rect(
+            Rect::from_size((1.0, 1.0)),
+            &Material::filled(Color::red().alpha(0.5)),
+        );
+        assert_eq!([128, 0, 128, 255], renderer.pixmap().pixel(0, 0));
+    }
+
+    #[test]
+    fn translucent_rect_over_transparent_keeps_color() {
+        let mut renderer = SoftwareRenderer::new(1, 1);
+        renderer.fill_rect(
+            Rect::from_size((1.0, 1.0)),
+            &Material::filled(Color::red().alpha(0.5)),
+        );
+        assert_eq!([255, 0, 0, 128], renderer.pixmap().pixel(0, 0));
+    }
+
+    #[test]
+    fn line_covers_pixels_along_segment() {
+        let mut renderer = SoftwareRenderer::new(4, 3);
+        renderer.fill_line(
+            Vector2::new(0.0, 1.5),
+            Vector2::new(4.0, 1.5),
+            1.0,
+            Color::red(),
+        );
+        let pixmap = renderer.pixmap();
+        assert_eq!(RED, pixmap.pixel(0, 1));
+        assert_eq!(RED, pixmap.pixel(3, 1));
+        assert_eq!(CLEAR, pixmap.pixel(0, 0));
+        assert_eq!(CLEAR, pixmap.pixel(0, 2));
+    }
+
+    #[test]
+    fn nested_clips_intersect() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_size((3.0, 3.0)),
+            radius: BorderRadius::zero(),
+        });
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_pos((2.0, 2.0), (3.0, 3.0)),
+            radius: BorderRadius::zero(),
+        });
+        list.push(DisplayItem::Rect {
+            rect: Rect::from_size((4.0, 4.0)),
+            color: Color::red(),
+        });
+        list.push(DisplayItem::PopClip);
+        list.push(DisplayItem::PopClip);
+
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.replay(&list);
+        let pixmap = renderer.pixmap();
+        assert_eq!(RED, pixmap.pixel(2, 2));
+        assert_eq!(CLEAR, pixmap.pixel(1, 1));
+        assert_eq!(CLEAR, pixmap.pixel(3, 3));
+    }
+
+    #[test]
+    fn rounded_clip_cuts_off_corners() {
+        let mut list = DisplayList::new();
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_size((8.0, 8.0)),
+            radius: BorderRadius::all(4.0),
+        });
+        list.push(DisplayItem::PushClip {
+            rect: Rect::from_pos((4.0, 0.0), (4.0, 8.0)),
+            radius: BorderRadius::zero(),
+        });
+        list.push(DisplayItem::Rect {
+            rect: Rect::from_size((8.0, 8.0)),
+            color: Color::red(),
+        });
+        list.push(DisplayItem::PopClip);
+        list.push(DisplayItem::PopClip);
+        list.push(DisplayItem::Rect {
+            rect: Rect::from_size((1.0, 1.0)),
+            color: Color::blue(),
+        });
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        renderer.replay(&list);
+        let pixmap = renderer.pixmap();
+        // The outer clip rounds the corners of the inner one
+        assert_eq!(CLEAR, pixmap.pixel(7, 0));
+        assert_eq!(CLEAR, pixmap.pixel(7, 7));
+        assert_eq!(RED, pixmap.pixel(6, 1));
+        assert_eq!(RED, pixmap.pixel(4, 4));
+        assert_eq!(CLEAR, pixmap.pixel(3, 4));
+        // Nothing is clipped once the clips are popped
+        assert_eq!(BLUE, pixmap.pixel(0, 0));
+    }
+
+    #[test]
+    fn container_clips_child_to_rounded_corners() {
+        let container = Container {
+            width: Some(10.0),
+            height: Some(10.0),
+            border_radius: BorderRadius::all(5.0),
+            clip: true,
+            child: Some(Box::new(Container {
+                width: Some(10.0),
+                height: Some(10.0),
+                color: Color::red(),
+                ..Container::default()
+            })),
+            ..Container::default()
+        };
+        let pixmap = SoftwareRenderer::render(&container, 10, 10, Color::black());
+        assert_eq!(BLACK, pixmap.pixel(0, 0));
+        assert_eq!(BLACK, pixmap.pixel(9, 9));
+        assert_eq!(RED, pixmap.pixel(5, 0));
+        assert_eq!(RED, pixmap.pixel(5, 5));
+    }
+
+    #[test]
+    fn shadow_with_negative_spread_past_center_is_empty() {
+        let container = Container {
+            width: Some(40.0),
+            height: Some(40.0),
+            margin: EdgeInsets::all(10.0),
+            shadows: vec![BoxShadow::new(Vector2::zero(), 8.0, -30.0, Color::red())],
+            ..Container::default()
+        };
+        let pixmap = SoftwareRenderer::render(&container, 60, 60, Color::black());
+        assert_eq!(BLACK, pixmap.pixel(30, 30));
+        assert_eq!(BLACK, pixmap.pixel(10, 10));
+    }
+
+    #[test]
+    fn render_lays_out_and_paints_widget() {
+        let container = Container {
+            width: Some(2.0),
+            height: Some(2.0),
+            margin: EdgeInsets::all(1.0),
+            color: Color::red(),
+            ..Container::default()
+        };
+        let pixmap = SoftwareRenderer::render(&container, 4, 4, Color::black());
+        assert_eq!(BLACK, pixmap.pixel(0, 0));
+        assert_eq!(RED, pixmap.pixel(1, 1));
+        assert_eq!(RED, pixmap.pixel(2, 2));
+        assert_eq!(BLACK, pixmap.pixel(3, 3));
+    }
+
+    #[test]
+    fn pixmap_round_trips_through_png() {
+        let mut renderer = SoftwareRenderer::new(3, 2);
+        renderer.fill_rect(Rect::from_size((1.0, 2.0)), &Material::filled(Color::red()));
+        let pixmap = renderer.into_pixmap();
+        let bytes = pixmap.encode_png().unwrap();
+        assert_eq!(pixmap, Pixmap::decode_png(&bytes).unwrap());
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+}
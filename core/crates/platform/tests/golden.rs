@@ -0,0 +1,26 @@
+use layout::Color;
+use math::Vector2;
+use paint::{DisplayItem, DisplayList};
+use test_util::assert_display_list_golden;
+
+#[test]
+fn lines() {
+    // Horizontal, vertical and diagonal lines of different widths, with square
+    // ends
+    let lines = [
+        ((4.0, 6.5), (44.0, 6.5), 1.0, Color::red()),
+        ((4.0, 14.0), (44.0, 14.0), 4.0, Color::green()),
+        ((52.0, 4.0), (52.0, 44.0), 3.0, Color::blue()),
+        ((6.0, 42.0), (42.0, 22.0), 2.0, Color::yellow()),
+    ];
+    let mut list = DisplayList::new();
+    for (start, end, width, color) in lines {
+        list.push(DisplayItem::Line {
+            start: start.into(),
+            end: end.into(),
+            width,
+            color,
+        });
+    }
+    assert_display_list_golden(&list, Vector2::new(64.0, 48.0), "lines");
+}
@@ -0,0 +1,251 @@
+use layout::{Color, Layout};
+use math::Vector2;
+use paint::DisplayList;
+use platform::software::{Pixmap, SoftwareRenderer};
+use std::path::{Path, PathBuf};
+
+/// Set this environment variable to overwrite the golden images with the
+/// current output instead of comparing against them, e.g.
+/// `UPDATE_GOLDENS=1 cargo test`.
+pub const UPDATE_GOLDENS_VAR: &str = "UPDATE_GOLDENS";
+
+/// Golden images are stored in this directory, relative to the root of the
+/// crate that is being tested.
+pub const GOLDEN_DIR: &str = "tests/golden";
+
+/// Controls how a rendered widget is compared against its golden image.
+#[derive(Clone, Copy, Debug)]
+pub struct GoldenOptions {
+    /// The largest difference allowed in any channel of a pixel before the
+    /// pixel is considered different.
+    pub tolerance: u8,
+    /// The color the widget is painted over.
+    pub background: Color,
+}
+
+impl Default for GoldenOptions {
+    fn default() -> GoldenOptions {
+        GoldenOptions {
+            tolerance: 2,
+            background: Color::black(),
+        }
+    }
+}
+
+/// The result of comparing two images of the same size.
+#[derive(Clone, Debug)]
+pub struct PixmapDiff {
+    /// The number of pixels that differ by more than the tolerance.
+    pub differing_pixels: usize,
+    /// A copy of the expected image where matching pixels are faded and
+    /// differing pixels are painted bright red.
+    pub image: Pixmap,
+}
+
+/// Render a widget with the CPU rasterizer and compare it against the golden
+/// image `tests/golden/{name}.png`.
+///
+/// When the images differ, the rendered image and a diff image are written
+/// next to the golden as `{name}.actual.png` and `{name}.diff.png`. Run the
+/// tests with `UPDATE_GOLDENS=1` to accept the new output.
+///
+/// # Panics
+///
+/// Will panic when the rendered image doesn't match the golden image, or when
+/// there is no golden image yet.
+pub fn assert_golden(widget: &dyn Layout, viewport: Vector2, name: &str) {
+    assert_golden_with(widget, viewport, name, &GoldenOptions::default());
+}
+
+/// Like [`assert_golden`], but with custom options.
+///
+/// # Panics
+///
+/// Will panic when the rendered image doesn't match the golden image, or when
+/// there is no golden image yet.
+pub fn assert_golden_with(
+    widget: &dyn Layout,
+    viewport: Vector2,
+    name: &str,
+    options: &GoldenOptions,
+) {
+    let (width, height) = (viewport.x.round() as u32, viewport.y.round() as u32);
+    let actual = SoftwareRenderer::render(widget, width, height, options.background);
+    compare_golden(&actual, name, options.tolerance);
+}
+
+/// Like [`assert_golden`], but paints a display list instead of laying out a
+/// widget. This covers paint commands that no widget produces.
+///
+/// # Panics
+///
+/// Will panic when the rendered image doesn't match the golden image, or when
+/// there is no golden image yet.
+pub fn assert_display_list_golden(list: &DisplayList, viewport: Vector2, name: &str) {
+    let options = GoldenOptions::default();
+    let (width, height) = (viewport.x.round() as u32, viewport.y.round() as u32);
+    let mut renderer = SoftwareRenderer::new(width, height);
+    renderer.clear(options.background);
+    renderer.replay(list);
+    compare_golden(renderer.pixmap(), name, options.tolerance);
+}
+
+// Compare a rendered image against the golden image `name`, or overwrite the
+// golden when `UPDATE_GOLDENS` is set.
+fn compare_golden(actual: &Pixmap, name: &str, tolerance: u8) {
+    let dir = golden_dir();
+    let golden_path = dir.join(format!("{name}.png"));
+    let actual_path = dir.join(format!("{name}.actual.png"));
+    let diff_path = dir.join(format!("{name}.diff.png"));
+
+    if std::env::var_os(UPDATE_GOLDENS_VAR).is_some() {
+        std::fs::create_dir_all(&dir).expect("could not create golden directory");
+        write_png(actual, &golden_path);
+        remove_if_exists(&actual_path);
+        remove_if_exists(&diff_path);
+        return;
+    }
+
+    let expected = match Pixmap::read_png(&golden_path) {
+        Ok(expected) => expected,
+        Err(err) => {
+            write_png(actual, &actual_path);
+            panic!(
+                "\nCould not read golden image {}: {}\nRendered image written to {}\nRun with {}=1 to create it.",
+                golden_path.display(),
+                err,
+                actual_path.display(),
+                UPDATE_GOLDENS_VAR,
+            );
+        }
+    };
+
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        write_png(actual, &actual_path);
+        panic!(
+            "\nGolden image {} is {}x{} but the rendered image is {}x{}.\nRendered image written to {}",
+            golden_path.display(),
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height(),
+            actual_path.display(),
+        );
+    }
+
+    let diff = diff_pixmaps(&expected, actual, tolerance);
+    if diff.differing_pixels > 0 {
+        write_png(actual, &actual_path);
+        write_png(&diff.image, &diff_path);
+        panic!(
+            "\nRendered image does not match golden image {}. {} pixels differ.\nRendered image: {}\nDiff image:     {}\nRun with {}=1 to accept the new image.",
+            golden_path.display(),
+            diff.differing_pixels,
+            actual_path.display(),
+            diff_path.display(),
+            UPDATE_GOLDENS_VAR,
+        );
+    }
+    remove_if_exists(&actual_path);
+    remove_if_exists(&diff_path);
+}
+
+/// Compare two images pixel by pixel. A pixel differs when any of its channels
+/// differ by more than `tolerance`.
+///
+/// # Panics
+///
+/// Will panic when the images are different sizes.
+#[must_use]
+pub fn diff_pixmaps(expected: &Pixmap, actual: &Pixmap, tolerance: u8) -> PixmapDiff {
+    assert!(
+        expected.width() == actual.width() && expected.height() == actual.height(),
+        "\nImages are different sizes. Expected {}x{} but got {}x{}",
+        expected.width(),
+        expected.height(),
+        actual.width(),
+        actual.height(),
+    );
+    let mut differing_pixels = 0;
+    let mut image = Pixmap::new(expected.width(), expected.height());
+    for y in 0..expected.height() {
+        for x in 0..expected.width() {
+            let lhs = expected.pixel(x, y);
+            let rhs = actual.pixel(x, y);
+            let differs = lhs.iter().zip(rhs).any(|(a, b)| a.abs_diff(b) > tolerance);
+            if differs {
+                differing_pixels += 1;
+                image.set_pixel(x, y, [255, 0, 0, 255]);
+            } else {
+                let [r, g, b, _] = lhs;
+                image.set_pixel(x, y, [r / 4, g / 4, b / 4, 255]);
+            }
+        }
+    }
+    PixmapDiff {
+        differing_pixels,
+        image,
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    crate::crate_dir().join(GOLDEN_DIR)
+}
+
+fn write_png(pixmap: &Pixmap, path: &Path) {
+    if let Err(err) = pixmap.write_png(path) {
+        panic!("could not write {}: {}", path.display(), err);
+    }
+}
+
+fn remove_if_exists(path: &Path) {
+    if path.exists() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_of_identical_images_is_empty() {
+        let image = pixmap(&[[1, 2, 3, 255], [4, 5, 6, 255]]);
+        let diff = diff_pixmaps(&image, &image, 0);
+        assert_eq!(0, diff.differing_pixels);
+    }
+
+    #[test]
+    fn diff_ignores_changes_within_tolerance() {
+        let expected = pixmap(&[[100, 100, 100, 255]]);
+        let actual = pixmap(&[[102, 98, 100, 255]]);
+        assert_eq!(0, diff_pixmaps(&expected, &actual, 2).differing_pixels);
+        assert_eq!(1, diff_pixmaps(&expected, &actual, 1).differing_pixels);
+    }
+
+    #[test]
+    fn diff_marks_differing_pixels_red() {
+        let expected = pixmap(&[[0, 0, 0, 255], [200, 200, 200, 255]]);
+        let actual = pixmap(&[[0, 0, 0, 255], [0, 0, 0, 255]]);
+        let diff = diff_pixmaps(&expected, &actual, 0);
+        assert_eq!(1, diff.differing_pixels);
+        assert_eq!([0, 0, 0, 255], diff.image.pixel(0, 0));
+        assert_eq!([255, 0, 0, 255], diff.image.pixel(1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "different sizes")]
+    fn diff_of_different_sizes_panics() {
+        let _ = diff_pixmaps(&Pixmap::new(1, 1), &Pixmap::new(2, 1), 0);
+    }
+
+    // --------------------------------------------------
+    // Helpers
+    // --------------------------------------------------
+
+    // A single row of pixels
+    fn pixmap(pixels: &[[u8; 4]]) -> Pixmap {
+        let data = pixels.iter().flatten().copied().collect();
+        Pixmap::from_rgba(pixels.len() as u32, 1, data).unwrap()
+    }
+}
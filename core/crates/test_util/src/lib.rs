@@ -1,4 +1,11 @@
 use std::fmt::Debug;
+use std::path::PathBuf;
+
+mod golden;
+pub use golden::*;
+
+mod snapshot;
+pub use snapshot::*;
 
 /// # Panics
 ///
@@ -21,3 +28,12 @@ pub fn assert_slice_eq<T: PartialEq + Debug>(expected: &[T], actual: &[T]) {
         );
     }
 }
+
+// Goldens and snapshots belong to the crate under test. Cargo sets
+// `CARGO_MANIFEST_DIR` when running tests, so read it at runtime rather than
+// with `env!`, which would point at this crate.
+fn crate_dir() -> PathBuf {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .unwrap_or_else(|| ".".into())
+        .into()
+}
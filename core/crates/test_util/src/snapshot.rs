@@ -0,0 +1,185 @@
+use std::path::Path;
+
+/// Set this environment variable to overwrite the snapshot files with the
+/// current output instead of comparing against them, e.g.
+/// `UPDATE_SNAPSHOTS=1 cargo test`.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// Snapshot files are stored in this directory, relative to the root of the
+/// crate that is being tested.
+pub const SNAPSHOT_DIR: &str = "tests/snapshots";
+
+/// Compare a text snapshot, like a `LayoutTree` dump, against an inline
+/// expected string.
+///
+/// Both strings are normalized first: leading and trailing blank lines and
+/// trailing whitespace are removed, and the indentation shared by every line
+/// is stripped. This means the expected string can be indented to match the
+/// surrounding test code.
+///
+/// # Panics
+///
+/// Will panic when the snapshots are different.
+pub fn assert_snapshot_eq(expected: &str, actual: &str) {
+    let expected = normalize_snapshot(expected);
+    let actual = normalize_snapshot(actual);
+    if expected != actual {
+        panic!(
+            "\nSnapshots do not match.\n{}",
+            diff_lines(&expected, &actual)
+        );
+    }
+}
+
+/// Compare a text snapshot against the file `tests/snapshots/{name}.snap`.
+///
+/// When the snapshots differ, the new snapshot is written next to the old
+/// one as `{name}.snap.new`. Run the tests with `UPDATE_SNAPSHOTS=1` to accept
+/// the new output.
+///
+/// # Panics
+///
+/// Will panic when the snapshots are different, or when there is no snapshot
+/// file yet.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let dir = crate::crate_dir().join(SNAPSHOT_DIR);
+    let path = dir.join(format!("{name}.snap"));
+    let new_path = dir.join(format!("{name}.snap.new"));
+    let actual = normalize_snapshot(actual) + "\n";
+
+    if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+        std::fs::create_dir_all(&dir).expect("could not create snapshot directory");
+        write_file(&path, &actual);
+        remove_if_exists(&new_path);
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(err) => {
+            write_file(&new_path, &actual);
+            panic!(
+                "\nCould not read snapshot {}: {}\nNew snapshot written to {}\nRun with {}=1 to create it.",
+                path.display(),
+                err,
+                new_path.display(),
+                UPDATE_SNAPSHOTS_VAR,
+            );
+        }
+    };
+
+    let expected = normalize_snapshot(&expected) + "\n";
+    if expected != actual {
+        write_file(&new_path, &actual);
+        panic!(
+            "\nSnapshot {} does not match.\n{}\nNew snapshot written to {}\nRun with {}=1 to accept it.",
+            path.display(),
+            diff_lines(&expected, &actual),
+            new_path.display(),
+            UPDATE_SNAPSHOTS_VAR,
+        );
+    }
+    remove_if_exists(&new_path);
+}
+
+/// Remove blank lines from the start and end, trailing whitespace from every
+/// line, and the indentation that every non-blank line has in common.
+#[must_use]
+pub fn normalize_snapshot(snapshot: &str) -> String {
+    let lines: Vec<&str> = snapshot.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return String::new(),
+    };
+    let lines = &lines[start..=end];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+// Print both snapshots line by line, marking the lines that differ.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(lhs), Some(rhs)) if lhs == rhs => {
+                out += &format!("  {lhs}\n");
+            }
+            (lhs, rhs) => {
+                if let Some(lhs) = lhs {
+                    out += &format!("- {lhs}\n");
+                }
+                if let Some(rhs) = rhs {
+                    out += &format!("+ {rhs}\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Err(err) = std::fs::write(path, contents) {
+        panic!("could not write {}: {}", path.display(), err);
+    }
+}
+
+fn remove_if_exists(path: &Path) {
+    if path.exists() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_strips_shared_indentation() {
+        let snapshot = "
+            a
+              b
+            c
+        ";
+        assert_eq!("a\n  b\nc", normalize_snapshot(snapshot));
+    }
+
+    #[test]
+    fn normalize_keeps_inner_blank_lines() {
+        assert_eq!("a\n\nb", normalize_snapshot("\n  a  \n\n  b\n\n"));
+    }
+
+    #[test]
+    fn normalize_empty_snapshot() {
+        assert_eq!("", normalize_snapshot("\n   \n"));
+    }
+
+    #[test]
+    fn assert_snapshot_eq_ignores_indentation() {
+        assert_snapshot_eq(
+            "
+            root
+              child
+            ",
+            "root\n  child\n",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "-   child\n+   other")]
+    fn assert_snapshot_eq_shows_differing_lines() {
+        assert_snapshot_eq("root\n  child", "root\n  other");
+    }
+}
@@ -1,41 +1,83 @@
 use layout::{
-    Alignment, Axis, Borders, Color, Container, CrossAxisAlignment, EdgeInsets, Flex, Flexible,
-    Layout, MainAxisAlignment, MainAxisSize, Positioned, Stack,
+    Alignment, Animation, AnimationController, Axis, Borders, Color, Container, CrossAxisAlignment,
+    Curve, EdgeInsets, Flex, Flexible, Image, ImageSizes, Layout, MainAxisAlignment, MainAxisSize,
+    Positioned, Stack, Text, TextStyle,
 };
 use math::Vector2;
-use platform::AppDriver;
+use platform::application::{Application, Sender};
+use std::rc::Rc;
+
+pub enum Message {
+    Frame(f32),
+}
 
 pub struct App {
     position: Vector2,
+    sidebar_size: AnimationController,
+    // The sizes of the images the driver has loaded
+    images: ImageSizes,
 }
 
-impl AppDriver for App {
-    fn tick(&mut self, time: f32) -> Box<dyn Layout> {
-        self.sidebar(time)
+impl Application for App {
+    type Message = Message;
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Frame(time) => {
+                self.sidebar_size.tick(time);
+                self.position = App::moving_box_position(time);
+            }
+        }
+    }
+
+    fn view(&self, _: &Sender<Message>) -> Box<dyn Layout> {
+        self.sidebar()
+    }
+
+    fn frame(&self, time: f32) -> Option<Message> {
+        Some(Message::Frame(time))
+    }
+
+    fn is_animating(&self) -> bool {
+        self.sidebar_size.is_animating()
     }
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(images: ImageSizes) -> App {
         let position = Vector2::zero();
-        App { position }
+        let mut sidebar_size = AnimationController::new(1000.0).with_curve(Curve::EaseInOut);
+        sidebar_size.repeat(true);
+        App {
+            position,
+            sidebar_size,
+            images,
+        }
     }
 
     #[allow(dead_code)]
-    pub fn sidebar(&self, time: f32) -> Box<dyn Layout> {
-        let speed = 0.003;
-        let size_multiplier = 0.5 + (0.5 * (time * speed).sin());
-        let size = 200.0 * size_multiplier + 1.0; // Extra 1 to accomodate border padding
+    pub fn sidebar(&self) -> Box<dyn Layout> {
+        let size = 200.0 * self.sidebar_size.value() + 1.0; // Extra 1 to accomodate border padding
 
         let border_color = Color::rgba(70.0, 70.0, 70.0, 255.0);
         let mut files: Vec<Box<dyn layout::FlexLayout>> = vec![];
         let mut files2: Vec<Box<dyn layout::FlexLayout>> = vec![];
-        for _ in 0..10 {
+        for i in 0..10 {
             files.push(Box::new(Container {
                 height: Some(20.0),
                 width: None,
                 color: Color::rgba(40.0, 40.0, 40.0, 255.0),
                 margin: EdgeInsets::bottom(5.0),
+                padding: EdgeInsets::horizontal(6.0),
+                child: Some(Box::new(Text {
+                    text: format!("file_{}.rs", i),
+                    style: TextStyle {
+                        font_size: 12.0,
+                        line_height: 20.0 / 12.0,
+                        color: Color::rgba(200.0, 200.0, 200.0, 255.0),
+                    },
+                    ..Default::default()
+                })),
                 ..Default::default()
             }));
             files2.push(Box::new(Container {
@@ -75,6 +117,14 @@ impl App {
                                     width: Some(40.0),
                                     color: Color::rgba(45.0, 45.0, 45.0, 255.0),
                                     margin: EdgeInsets::bottom(5.0),
+                                    alignment: Alignment::center(),
+                                    child: Some(Box::new(Image {
+                                        source: "logo.png".to_string(),
+                                        width: Some(24.0),
+                                        height: Some(24.0),
+                                        measurer: Rc::new(self.images.clone()),
+                                        ..Default::default()
+                                    })),
                                     ..Default::default()
                                 }),
                                 Box::new(Container {
@@ -270,15 +320,17 @@ impl App {
         })
     }
 
-    #[allow(dead_code)]
-    fn render_moving_box(&mut self, time: f32) -> Box<dyn Layout> {
+    fn moving_box_position(time: f32) -> Vector2 {
         let speed = 0.005;
         let radius = 100.0;
         let offset = Vector2::new(100.0, 100.0);
-        self.position.x = 100.0 + radius * (time * speed).sin();
-        self.position.y = 100.0 + radius * (time * speed).cos();
-        self.position += offset;
+        let x = 100.0 + radius * (time * speed).sin();
+        let y = 100.0 + radius * (time * speed).cos();
+        Vector2::new(x, y) + offset
+    }
 
+    #[allow(dead_code)]
+    fn render_moving_box(&self, _: f32) -> Box<dyn Layout> {
         Box::new(Positioned {
             position: self.position,
             child: Box::new(Container {
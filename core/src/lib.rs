@@ -1,4 +1,5 @@
 use log::Level;
+use platform::application::Program;
 use platform::browser::BrowserDriver;
 use wasm_bindgen::prelude::wasm_bindgen;
 
@@ -21,6 +22,5 @@ pub fn start(canvas_id: &str) -> BrowserDriver {
 
     console_log::init_with_level(Level::Debug).unwrap();
 
-    let app = App::new();
-    BrowserDriver::try_new(canvas_id, Box::new(app)).unwrap()
+    BrowserDriver::try_new(canvas_id, |images| Box::new(Program::new(App::new(images)))).unwrap()
 }
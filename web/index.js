@@ -5,18 +5,105 @@ if (!canvas) {
     console.error("failed to get canvas element");
 }
 
-const gl = canvas.getContext("webgl");
+// The first call decides the attributes of the context. Rounded clips are
+// drawn into the stencil buffer.
+const gl = canvas.getContext("webgl", { stencil: true });
 if (!gl) {
     console.error("failed to get webgl context")
 }
 
 let app = wasm.start("app");
 
+// Frames are only requested while the app is animating, or after input that
+// may have changed it.
+let frameRequested = false;
+function requestFrame() {
+    if (!frameRequested) {
+        frameRequested = true;
+        requestAnimationFrame(update);
+    }
+}
+
 function update(now) {
-    app.tick(now);
-    requestAnimationFrame(update);
+    frameRequested = false;
+    if (app.tick(now)) {
+        requestFrame();
+    }
+    for (const source of app.take_image_requests()) {
+        loadImage(source);
+    }
+}
+requestFrame();
+
+// Fetch a PNG and hand it to the app, which draws it wherever an image widget
+// uses the same source.
+function loadImage(source) {
+    fetch(source)
+        .then(response => response.arrayBuffer())
+        .then(buffer => {
+            app.load_image(source, new Uint8Array(buffer));
+            requestFrame();
+        })
+        .catch(err => console.error(`failed to load image ${source}: ${err}`));
 }
-requestAnimationFrame(update);
+
+// The canvas captures a pressed pointer, so it still gets the move and up
+// events when a drag leaves it.
+canvas.addEventListener("pointerdown", function(e) {
+    canvas.setPointerCapture(e.pointerId);
+    app.pointer_down(e.offsetX, e.offsetY, e.pointerId, e.timeStamp);
+    requestFrame();
+});
+canvas.addEventListener("pointermove", function(e) {
+    app.pointer_move(e.offsetX, e.offsetY, e.pointerId, e.timeStamp);
+    requestFrame();
+});
+canvas.addEventListener("pointerup", function(e) {
+    app.pointer_up(e.offsetX, e.offsetY, e.pointerId, e.timeStamp);
+    requestFrame();
+});
+canvas.addEventListener("pointercancel", function(e) {
+    app.pointer_cancel(e.offsetX, e.offsetY, e.pointerId, e.timeStamp);
+    requestFrame();
+});
+// Wheel deltas can be in lines or pages instead of pixels. The listener isn't
+// passive, so scrolling the page can be prevented when the app handles it.
+const WHEEL_LINE_HEIGHT = 16;
+canvas.addEventListener("wheel", function(e) {
+    let scale = 1;
+    if (e.deltaMode === WheelEvent.DOM_DELTA_LINE) {
+        scale = WHEEL_LINE_HEIGHT;
+    } else if (e.deltaMode === WheelEvent.DOM_DELTA_PAGE) {
+        scale = canvas.clientHeight;
+    }
+    if (app.pointer_wheel(e.offsetX, e.offsetY, e.deltaX * scale, e.deltaY * scale)) {
+        e.preventDefault();
+    }
+    requestFrame();
+}, { passive: false });
+
+// Keyboard events go to the window, because the canvas can't take focus.
+// Focus between widgets is managed by the app instead.
+window.addEventListener("keydown", function(e) {
+    const handled = app.key_down(e.key, e.shiftKey, e.ctrlKey, e.altKey, e.metaKey, e.repeat);
+    if (handled) {
+        e.preventDefault();
+    }
+    // Printable keys have a single character name. Forward them as text
+    // unless a shortcut modifier is held.
+    if (!handled && e.key.length === 1 && !e.ctrlKey && !e.metaKey) {
+        if (app.text_input(e.key)) {
+            e.preventDefault();
+        }
+    }
+    requestFrame();
+});
+window.addEventListener("keyup", function(e) {
+    if (app.key_up(e.key, e.shiftKey, e.ctrlKey, e.altKey, e.metaKey)) {
+        e.preventDefault();
+    }
+    requestFrame();
+});
 
 function resizeCanvasToDisplaySize(canvas, multiplier) {
     const width = window.innerWidth;
@@ -30,6 +117,7 @@ resizeCanvasToDisplaySize(canvas, window.devicePixelRatio);
 window.addEventListener("resize", function(e) {
     debounce(function() {
         resizeCanvasToDisplaySize(canvas, window.devicePixelRatio);
+        requestFrame();
     }, 250);
 });
 